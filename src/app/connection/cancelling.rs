@@ -0,0 +1,156 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{mpsc::Sender, Arc, Mutex},
+};
+
+use uuid::Uuid;
+
+use super::{Connection, ConnectionKey, DbResponse};
+
+/// Backend that is serving some connection, which is enough
+/// to ask server to cancel whatever is running there
+struct CancelTarget {
+    url: String,
+    database: String,
+    backend_pid: i64,
+    // set once user asked to cancel, until worker takes it
+    cancel_requested: bool,
+}
+
+/// Backends of connections running user queries, worker registers its backend
+/// once connected and removes it when connection is dropped. Canceller marks
+/// the target as requested before cancelling, so that worker could tell
+/// query cancelled by user from the one stopped by timeout
+#[derive(Clone, Default)]
+pub(super) struct CancelTargets {
+    targets: Arc<Mutex<HashMap<ConnectionKey, CancelTarget>>>,
+    // connections serving some request right now, only there is something to cancel
+    running: Arc<Mutex<HashSet<ConnectionKey>>>,
+}
+
+impl CancelTargets {
+    pub(super) fn register(&self, key: ConnectionKey, connection: &Connection, backend_pid: i64) {
+        let target = CancelTarget {
+            url: connection.url.clone(),
+            database: connection.name.clone(),
+            backend_pid,
+            cancel_requested: false,
+        };
+        self.targets.lock().unwrap().insert(key, target);
+    }
+
+    pub(super) fn unregister(&self, key: &ConnectionKey) {
+        self.targets.lock().unwrap().remove(key);
+    }
+
+    /// Marks that worker of the connection started or finished serving request
+    pub(super) fn set_running(&self, key: &ConnectionKey, running: bool) {
+        let mut keys = self.running.lock().unwrap();
+        if running {
+            keys.insert(key.clone());
+        } else {
+            keys.remove(key);
+        }
+    }
+
+    /// Tells if user asked to cancel query running for the connection, resetting the request
+    pub(super) fn take_request(&self, key: &ConnectionKey) -> bool {
+        self.targets
+            .lock()
+            .unwrap()
+            .get_mut(key)
+            .is_some_and(|target| std::mem::take(&mut target.cancel_requested))
+    }
+
+    /// Marks cancel as requested and returns url, database and backend
+    /// to cancel, None if nothing is running in the connection
+    pub(super) fn request(&self, key: &ConnectionKey) -> Option<(String, String, i64)> {
+        if !self.running.lock().unwrap().contains(key) {
+            return None;
+        }
+        self.targets.lock().unwrap().get_mut(key).map(|target| {
+            target.cancel_requested = true;
+            (
+                target.url.clone(),
+                target.database.clone(),
+                target.backend_pid,
+            )
+        })
+    }
+}
+
+// Cancelling does not go through connection workers, because the worker
+// holding the connection is busy exactly with the query to be cancelled
+pub(super) struct QueryCanceller {
+    tx: Sender<DbResponse>,
+    targets: CancelTargets,
+}
+
+impl QueryCanceller {
+    pub(super) fn new(tx: Sender<DbResponse>, targets: CancelTargets) -> Self {
+        Self { tx, targets }
+    }
+
+    pub(super) async fn cancel_query(self, server_id: Uuid, database: String) {
+        let response = self.process_request(server_id, database).await;
+        if let Err(e) = self.tx.send(response) {
+            tracing::error!("Error sending cancel response: {:?}", e);
+        }
+    }
+
+    async fn process_request(&self, server_id: Uuid, database: String) -> DbResponse {
+        let key = ConnectionKey {
+            name: database,
+            server_id,
+        };
+        let (url, database, backend_pid) = match self.targets.request(&key) {
+            Some(target) => target,
+            None => return DbResponse::NotCancelled(server_id, "No query is running".to_string()),
+        };
+        tracing::info!("Cancelling query running in backend {}", backend_pid);
+        match Self::cancel(&database, &url, backend_pid).await {
+            Ok(()) => DbResponse::None,
+            Err(e) => {
+                tracing::error!("Error cancelling query: {:?}", e);
+                DbResponse::Error(server_id, format!("Failed to cancel query: {}", e))
+            }
+        }
+    }
+
+    async fn cancel(database: &str, url: &str, backend_pid: i64) -> Result<(), sqlx::Error> {
+        let mut side_connection = Connection::connect(database, url).await?;
+        side_connection.driver.cancel_query(backend_pid).await?;
+        side_connection.driver.close().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mock::MockServer;
+    use super::*;
+
+    #[test]
+    fn only_running_connection_is_cancelled() {
+        let targets = CancelTargets::default();
+        let key = ConnectionKey {
+            name: "mock".to_string(),
+            server_id: Uuid::nil(),
+        };
+        assert_eq!(targets.request(&key), None);
+
+        targets.register(key.clone(), &MockServer::default().connection(), 42);
+        assert_eq!(targets.request(&key), None);
+        assert!(!targets.take_request(&key));
+
+        targets.set_running(&key, true);
+        assert_eq!(
+            targets.request(&key),
+            Some(("postgres://mock".to_string(), "mock".to_string(), 42))
+        );
+        assert!(targets.take_request(&key));
+        assert!(!targets.take_request(&key));
+
+        targets.set_running(&key, false);
+        assert_eq!(targets.request(&key), None);
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+};
+
+use itertools::Itertools;
+use sqlx::types::{
+    time::{Date, OffsetDateTime, PrimitiveDateTime, Time},
+    BigDecimal, JsonValue,
+};
+
+/// Value of one cell of query result, keeps the type of value,
+/// so that it could be shown, sorted or exported accordingly
+#[derive(Clone, Debug)]
+pub(crate) enum CellValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    // also unsigned integers, which do not fit into i64
+    Decimal(BigDecimal),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    Date(Date),
+    Time(Time),
+    Timestamp(PrimitiveDateTime),
+    TimestampTz(OffsetDateTime),
+    Json(String),
+    Array(Vec<CellValue>),
+    // name of the type, which could not be decoded, if known
+    Unsupported(String),
+}
+
+// floats are compared by total order, so that NaN is equal to itself
+// and values could be Eq, as responses holding them are
+impl PartialEq for CellValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl Eq for CellValue {}
+
+impl PartialOrd for CellValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (CellValue::Bool(a), CellValue::Bool(b)) => a.partial_cmp(b),
+            (CellValue::Int(a), CellValue::Int(b)) => a.partial_cmp(b),
+            (CellValue::Decimal(a), CellValue::Decimal(b)) => a.partial_cmp(b),
+            (CellValue::Float(a), CellValue::Float(b)) => Some(a.total_cmp(b)),
+            (CellValue::Text(a), CellValue::Text(b))
+            | (CellValue::Json(a), CellValue::Json(b))
+            | (CellValue::Unsupported(a), CellValue::Unsupported(b)) => a.partial_cmp(b),
+            (CellValue::Bytes(a), CellValue::Bytes(b)) => a.partial_cmp(b),
+            (CellValue::Date(a), CellValue::Date(b)) => a.partial_cmp(b),
+            (CellValue::Time(a), CellValue::Time(b)) => a.partial_cmp(b),
+            (CellValue::Timestamp(a), CellValue::Timestamp(b)) => a.partial_cmp(b),
+            (CellValue::TimestampTz(a), CellValue::TimestampTz(b)) => a.partial_cmp(b),
+            (CellValue::Array(a), CellValue::Array(b)) => a.partial_cmp(b),
+            // values of different kinds go in order of variants, as derived order would do
+            _ => self.rank().partial_cmp(&other.rank()),
+        }
+    }
+}
+
+impl CellValue {
+    fn rank(&self) -> u8 {
+        match self {
+            CellValue::Null => 0,
+            CellValue::Bool(_) => 1,
+            CellValue::Int(_) => 2,
+            CellValue::Decimal(_) => 3,
+            CellValue::Float(_) => 4,
+            CellValue::Text(_) => 5,
+            CellValue::Bytes(_) => 6,
+            CellValue::Date(_) => 7,
+            CellValue::Time(_) => 8,
+            CellValue::Timestamp(_) => 9,
+            CellValue::TimestampTz(_) => 10,
+            CellValue::Json(_) => 11,
+            CellValue::Array(_) => 12,
+            CellValue::Unsupported(_) => 13,
+        }
+    }
+
+    pub(crate) fn is_null(&self) -> bool {
+        matches!(self, CellValue::Null)
+    }
+
+    pub(crate) fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            CellValue::Int(_) | CellValue::Decimal(_) | CellValue::Float(_)
+        )
+    }
+
+    pub(crate) fn is_unsupported(&self) -> bool {
+        matches!(self, CellValue::Unsupported(_))
+    }
+}
+
+impl Display for CellValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellValue::Null => f.write_str("null"),
+            CellValue::Bool(value) => write!(f, "{}", value),
+            CellValue::Int(value) => write!(f, "{}", value),
+            CellValue::Decimal(value) => write!(f, "{}", value),
+            CellValue::Float(value) => write!(f, "{}", value),
+            CellValue::Text(value) | CellValue::Json(value) => f.write_str(value),
+            CellValue::Bytes(value) => write!(f, "{:?}", value),
+            CellValue::Date(value) => write!(f, "{}", value),
+            CellValue::Time(value) => write!(f, "{}", value),
+            CellValue::Timestamp(value) => write!(f, "{}", value),
+            CellValue::TimestampTz(value) => write!(f, "{}", value),
+            CellValue::Array(values) => write!(f, "[{}]", values.iter().join(",")),
+            CellValue::Unsupported(type_name) if type_name.is_empty() => {
+                f.write_str("not supported")
+            }
+            CellValue::Unsupported(type_name) => write!(f, "{} not supported", type_name),
+        }
+    }
+}
+
+impl<T: Into<CellValue>> From<Option<T>> for CellValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(CellValue::Null, Into::into)
+    }
+}
+
+impl From<Vec<u8>> for CellValue {
+    fn from(value: Vec<u8>) -> Self {
+        CellValue::Bytes(value)
+    }
+}
+
+impl From<bool> for CellValue {
+    fn from(value: bool) -> Self {
+        CellValue::Bool(value)
+    }
+}
+
+impl From<String> for CellValue {
+    fn from(value: String) -> Self {
+        CellValue::Text(value)
+    }
+}
+
+impl From<i8> for CellValue {
+    fn from(value: i8) -> Self {
+        CellValue::Int(value.into())
+    }
+}
+
+impl From<i16> for CellValue {
+    fn from(value: i16) -> Self {
+        CellValue::Int(value.into())
+    }
+}
+
+impl From<i32> for CellValue {
+    fn from(value: i32) -> Self {
+        CellValue::Int(value.into())
+    }
+}
+
+impl From<i64> for CellValue {
+    fn from(value: i64) -> Self {
+        CellValue::Int(value)
+    }
+}
+
+impl From<u64> for CellValue {
+    fn from(value: u64) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => CellValue::Int(value),
+            Err(_) => CellValue::Decimal(value.into()),
+        }
+    }
+}
+
+impl From<f32> for CellValue {
+    fn from(value: f32) -> Self {
+        CellValue::Float(value.into())
+    }
+}
+
+impl From<f64> for CellValue {
+    fn from(value: f64) -> Self {
+        CellValue::Float(value)
+    }
+}
+
+impl From<BigDecimal> for CellValue {
+    fn from(value: BigDecimal) -> Self {
+        CellValue::Decimal(value)
+    }
+}
+
+impl From<Date> for CellValue {
+    fn from(value: Date) -> Self {
+        CellValue::Date(value)
+    }
+}
+
+impl From<Time> for CellValue {
+    fn from(value: Time) -> Self {
+        CellValue::Time(value)
+    }
+}
+
+impl From<PrimitiveDateTime> for CellValue {
+    fn from(value: PrimitiveDateTime) -> Self {
+        CellValue::Timestamp(value)
+    }
+}
+
+impl From<OffsetDateTime> for CellValue {
+    fn from(value: OffsetDateTime) -> Self {
+        CellValue::TimestampTz(value)
+    }
+}
+
+impl From<JsonValue> for CellValue {
+    fn from(value: JsonValue) -> Self {
+        CellValue::Json(value.to_string())
+    }
+}
+
+impl From<sqlx::types::uuid::Uuid> for CellValue {
+    fn from(value: sqlx::types::uuid::Uuid) -> Self {
+        CellValue::Text(value.to_string())
+    }
+}
+
+impl From<sqlx::types::ipnetwork::IpNetwork> for CellValue {
+    fn from(value: sqlx::types::ipnetwork::IpNetwork) -> Self {
+        CellValue::Text(value.to_string())
+    }
+}
+
+impl From<sqlx::types::mac_address::MacAddress> for CellValue {
+    fn from(value: sqlx::types::mac_address::MacAddress) -> Self {
+        CellValue::Text(value.to_string())
+    }
+}
+
+/// Column of query result, type is named as database calls it
+#[derive(PartialEq, PartialOrd, Clone, Eq, Debug)]
+pub(crate) struct ColumnInfo {
+    pub(crate) name: String,
+    pub(crate) type_name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn floats_are_equal_to_themselves() {
+        assert_eq!(CellValue::Float(f64::NAN), CellValue::Float(f64::NAN));
+        assert_ne!(CellValue::Float(0.0), CellValue::Float(-0.0));
+        assert!(CellValue::Float(1.0) < CellValue::Float(f64::NAN));
+        assert_eq!(
+            CellValue::Array(vec![CellValue::Float(f64::NAN), CellValue::Null]),
+            CellValue::Array(vec![CellValue::Float(f64::NAN), CellValue::Null])
+        );
+    }
+
+    #[test]
+    fn values_of_different_kinds_are_ordered_by_kind() {
+        assert_ne!(CellValue::Int(1), CellValue::Float(1.0));
+        assert!(CellValue::Null < CellValue::Int(1));
+        assert!(CellValue::Text("a".to_string()) < CellValue::Json("a".to_string()));
+    }
+}
@@ -0,0 +1,247 @@
+use std::path::PathBuf;
+
+/// Where `\copy` moves the data, the file is on the machine running the app
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub(super) enum CopyDirection {
+    ToFile,
+    FromFile,
+}
+
+/// Meta-command `\copy` working like the one in psql,
+/// it runs `COPY` on the server and moves the data between server and local file
+#[derive(PartialEq, Debug)]
+pub(super) struct CopyCommand {
+    pub(super) direction: CopyDirection,
+    pub(super) path: PathBuf,
+    // `COPY ... TO STDOUT` or `COPY ... FROM STDIN` to be run on the server
+    pub(super) statement: String,
+}
+
+impl CopyCommand {
+    /// Parses `\copy table TO 'file' options` or `\copy (query) TO ...`
+    /// as well as `\copy table FROM 'file' options`,
+    /// returns None if statement is not `\copy` at all
+    pub(super) fn parse(statement: &str) -> Option<Result<Self, String>> {
+        let arguments = statement
+            .strip_prefix('\\')
+            .and_then(|command| {
+                let name_len = command.chars().take_while(|c| c.is_alphabetic()).count();
+                let (name, arguments) = command.split_at(name_len);
+                name.eq_ignore_ascii_case("copy").then_some(arguments)
+            })?
+            .trim()
+            // psql takes the whole line, but semicolon is usually left out of habit
+            .trim_end_matches(';')
+            .trim_end();
+        Some(Self::parse_arguments(arguments))
+    }
+
+    fn parse_arguments(arguments: &str) -> Result<Self, String> {
+        let chars: Vec<char> = arguments.chars().collect();
+        let (keyword, direction) = match find_direction(&chars) {
+            Some(found) => found,
+            None => return Err("\\copy needs FROM or TO with the file".to_string()),
+        };
+        let source: String = chars[..keyword.start].iter().collect();
+        let source = source.trim();
+        if source.is_empty() {
+            return Err("\\copy needs table or query to copy".to_string());
+        }
+
+        let rest: String = chars[keyword.end..].iter().collect();
+        let rest = rest.trim_start();
+        let (file, options) = match rest.strip_prefix('\'') {
+            Some(quoted) => unquote(quoted)?,
+            None => {
+                let file_len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                let (file, options) = rest.split_at(file_len);
+                if matches!(
+                    file.to_lowercase().as_str(),
+                    "stdin" | "stdout" | "pstdin" | "pstdout" | "program"
+                ) {
+                    return Err(format!("\\copy only supports files, not {}", file));
+                }
+                (file.to_string(), options)
+            }
+        };
+        if file.is_empty() {
+            return Err("\\copy needs name of the file".to_string());
+        }
+
+        let target = match direction {
+            CopyDirection::ToFile => "TO STDOUT",
+            CopyDirection::FromFile => "FROM STDIN",
+        };
+        Ok(Self {
+            direction,
+            path: expand_home(&file),
+            statement: format!("COPY {} {} {}", source, target, options.trim())
+                .trim_end()
+                .to_string(),
+        })
+    }
+}
+
+/// Finds FROM or TO keyword, which is not a part of the query in parentheses,
+/// of quoted identifier or of some longer name
+fn find_direction(chars: &[char]) -> Option<(std::ops::Range<usize>, CopyDirection)> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, c) if depth == 0 && c.is_alphabetic() => {
+                let continues_word =
+                    i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_');
+                let word: String = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_')
+                    .collect();
+                let len = word.chars().count();
+                if !continues_word {
+                    let direction = match word.to_lowercase().as_str() {
+                        "to" => Some(CopyDirection::ToFile),
+                        "from" => Some(CopyDirection::FromFile),
+                        _ => None,
+                    };
+                    if let Some(direction) = direction {
+                        return Some((i..i + len, direction));
+                    }
+                }
+                i += len;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Reads quoted file name, opening quote is already stripped,
+/// returns the name and the rest after closing quote
+fn unquote(quoted: &str) -> Result<(String, &str), String> {
+    let mut name = String::new();
+    let mut chars = quoted.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != '\'' {
+            name.push(c);
+            continue;
+        }
+        // doubled quote is an escaped quote
+        if let Some((_, '\'')) = chars.peek() {
+            chars.next();
+            name.push('\'');
+            continue;
+        }
+        return Ok((name, &quoted[i + 1..]));
+    }
+    Err("\\copy has unterminated quoted file name".to_string())
+}
+
+fn expand_home(file: &str) -> PathBuf {
+    match (file.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(relative), Some(home)) => home.join(relative),
+        _ => PathBuf::from(file),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(statement: &str) -> Result<CopyCommand, String> {
+        CopyCommand::parse(statement).unwrap()
+    }
+
+    #[test]
+    fn quoted_file_path_keeps_spaces_and_quotes() {
+        assert_eq!(
+            parse("\\copy items to '/tmp/my items''s.csv' with csv header"),
+            Ok(CopyCommand {
+                direction: CopyDirection::ToFile,
+                path: PathBuf::from("/tmp/my items's.csv"),
+                statement: "COPY items TO STDOUT with csv header".to_string(),
+            })
+        );
+        assert!(parse("\\copy items to '/tmp/items.csv").is_err());
+    }
+
+    #[test]
+    fn keywords_inside_of_query_are_skipped() {
+        assert_eq!(
+            parse("\\COPY (select 'to' as a, b from t where c = 'from') TO out.csv"),
+            Ok(CopyCommand {
+                direction: CopyDirection::ToFile,
+                path: PathBuf::from("out.csv"),
+                statement: "COPY (select 'to' as a, b from t where c = 'from') TO STDOUT"
+                    .to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn column_list_and_quoted_identifiers_belong_to_source() {
+        assert_eq!(
+            parse("\\copy items (id, \"from\", towns) from data.csv csv"),
+            Ok(CopyCommand {
+                direction: CopyDirection::FromFile,
+                path: PathBuf::from("data.csv"),
+                statement: "COPY items (id, \"from\", towns) FROM STDIN csv".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("\\copy \"from\" from 'data.csv'").map(|copy| copy.statement),
+            Ok("COPY \"from\" FROM STDIN".to_string())
+        );
+    }
+
+    #[test]
+    fn standard_streams_are_rejected() {
+        for file in ["stdin", "STDOUT", "pstdin", "program"] {
+            assert_eq!(
+                parse(&format!("\\copy items from {}", file)),
+                Err(format!("\\copy only supports files, not {}", file))
+            );
+        }
+    }
+
+    #[test]
+    fn trailing_semicolon_is_dropped() {
+        assert_eq!(
+            parse("\\copy items to 'items.csv' csv; ").map(|copy| copy.statement),
+            Ok("COPY items TO STDOUT csv".to_string())
+        );
+        assert_eq!(
+            parse("\\copy items to items.csv;").map(|copy| copy.path),
+            Ok(PathBuf::from("items.csv"))
+        );
+    }
+
+    #[test]
+    fn home_is_expanded_in_file_path() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(
+            parse("\\copy items to '~/exports/items.csv'").map(|copy| copy.path),
+            Ok(home.join("exports").join("items.csv"))
+        );
+        assert_eq!(
+            parse("\\copy items to ~user/items.csv").map(|copy| copy.path),
+            Ok(PathBuf::from("~user/items.csv"))
+        );
+    }
+
+    #[test]
+    fn other_statements_are_not_copy() {
+        assert_eq!(CopyCommand::parse("copy items to stdout"), None);
+        assert_eq!(CopyCommand::parse("\\copyx items to 'a'"), None);
+        assert!(parse("\\copy to 'items.csv'").is_err());
+        assert!(parse("\\copy items").is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+
+use super::{
+    cells::{CellValue, ColumnInfo},
+    splitting::SqlDialect,
+    SessionTimeouts,
+};
+
+/// Kind of objects shown in browser tree under the server
+#[derive(PartialEq, PartialOrd, Clone, Copy, Eq, Debug)]
+pub(crate) enum ObjectLevel {
+    Database,
+    Schema,
+    Table,
+    Column,
+}
+
+impl ObjectLevel {
+    pub(crate) fn plural_name(&self) -> &'static str {
+        match self {
+            ObjectLevel::Database => "databases",
+            ObjectLevel::Schema => "schemas",
+            ObjectLevel::Table => "tables",
+            ObjectLevel::Column => "columns",
+        }
+    }
+}
+
+/// What driver is able to do, this is known without connecting
+/// and decides how browser tree and queries are handled
+#[derive(Debug)]
+pub(crate) struct DriverCapabilities {
+    // levels of objects from the top, the first level is always databases
+    pub(crate) levels: &'static [ObjectLevel],
+    // database that exists on every server, used to list other databases
+    pub(crate) management_database: &'static str,
+    pub(crate) query_plan: bool,
+    pub(crate) cancel_query: bool,
+    pub(crate) session_timeouts: bool,
+    // idle_in_transaction_session_timeout, it is not among session timeouts everywhere
+    pub(crate) idle_transaction_timeout: bool,
+    // only `?` placeholders are understood, which are bound by position
+    pub(crate) positional_placeholders: bool,
+    // how statements and placeholders are found in the script
+    pub(crate) dialect: SqlDialect,
+    // LISTEN and NOTIFY
+    pub(crate) notifications: bool,
+    // COPY from and to the client, used by `\copy`
+    pub(crate) copy: bool,
+    // DECLARE and FETCH, so that rows are read from server by pages
+    pub(crate) cursors: bool,
+}
+
+/// Row of query result, the first row also carries columns
+pub(crate) struct FetchedRow {
+    pub(crate) columns: Option<Vec<ColumnInfo>>,
+    pub(crate) data: Vec<CellValue>,
+}
+
+/// Connection to one database of the server, every supported database
+/// implements it, so that the rest of the app does not care which one is used
+#[async_trait]
+pub(crate) trait DatabaseDriver: Send {
+    async fn connect(name: &str, url: &str) -> Result<Self, sqlx::Error>
+    where
+        Self: Sized;
+
+    fn capabilities(&self) -> &'static DriverCapabilities;
+
+    /// Identifies connection on the server, if running query could be cancelled
+    fn backend_pid(&self) -> Option<i64> {
+        None
+    }
+
+    /// Applies timeouts for the session, has to be done again for every new connection
+    async fn set_timeouts(&mut self, _timeouts: &SessionTimeouts) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    /// Asks server which types it expects for parameters of the statement,
+    /// empty list means that server could not tell
+    async fn describe_parameters(&mut self, _statement: &str) -> Result<Vec<String>, sqlx::Error> {
+        Ok(vec![])
+    }
+
+    /// Returns plan of the statement in JSON format, when analyzing
+    /// the statement is actually executed and buffers usage is reported
+    async fn explain(&mut self, _statement: &str, _analyze: bool) -> Result<String, sqlx::Error> {
+        Err(sqlx::Error::Configuration(
+            "Query plan is not supported for this database".into(),
+        ))
+    }
+
+    /// Runs statement that does not return anything interesting
+    async fn execute_command(&mut self, command: &str) -> Result<(), sqlx::Error>;
+
+    /// Lists names of objects of given level, which are inside of the object
+    /// with given path, path starts with database and does not include server
+    async fn list_objects(
+        &mut self,
+        level: ObjectLevel,
+        path: &[String],
+    ) -> Result<Vec<String>, sqlx::Error>;
+
+    /// Asks server to stop query running in another connection
+    async fn cancel_query(&mut self, _backend_pid: i64) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    async fn close(self: Box<Self>) -> Result<(), sqlx::Error>;
+
+    /// Starts `COPY ... TO STDOUT` statement and streams data sent by server
+    async fn copy_out<'e>(
+        &'e mut self,
+        _statement: &'e str,
+    ) -> Result<BoxStream<'e, Result<Vec<u8>, sqlx::Error>>, sqlx::Error> {
+        Err(sqlx::Error::Configuration(
+            "Copying is not supported for this database".into(),
+        ))
+    }
+
+    /// Runs `COPY ... FROM STDIN` statement sending given data to server,
+    /// returns amount of copied rows, error in data aborts copying
+    async fn copy_in(
+        &mut self,
+        _statement: &str,
+        _data: BoxStream<'_, Result<Vec<u8>, sqlx::Error>>,
+    ) -> Result<u64, sqlx::Error> {
+        Err(sqlx::Error::Configuration(
+            "Copying is not supported for this database".into(),
+        ))
+    }
+
+    /// Streams rows of query result, so that caller could decide
+    /// how many of them it actually wants to read,
+    /// parameters are bound as text in order of their positions
+    fn fetch<'e>(
+        &'e mut self,
+        query: &'e str,
+        parameters: Vec<Option<String>>,
+    ) -> BoxStream<'e, Result<FetchedRow, sqlx::Error>>;
+}
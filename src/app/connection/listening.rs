@@ -0,0 +1,149 @@
+use std::sync::mpsc::Sender;
+
+use async_std::{channel, task};
+use futures::future::{self, Either};
+use sqlx::{
+    postgres::{PgListener, PgPool, PgPoolOptions},
+    types::time::OffsetDateTime,
+};
+use uuid::Uuid;
+
+use super::{pgconf::connect_options, ConnectionKey, DbResponse, ServerSettings};
+
+/// Notification received from one of listened channels
+#[derive(PartialEq, PartialOrd, Clone, Eq, Debug)]
+pub(crate) struct Notification {
+    pub(crate) server_id: Uuid,
+    pub(crate) database: String,
+    pub(crate) channel: String,
+    pub(crate) payload: String,
+    // backend of the session, which has sent the notification
+    pub(crate) process_id: u32,
+    pub(crate) received_at: OffsetDateTime,
+}
+
+/// Dedicated connection of the database, which listens to channels and sends
+/// received notifications to main thread until it is dropped
+pub(super) struct ChannelsListener {
+    pub(super) channels: Vec<String>,
+    // one connection listens and another one is used to notify
+    pool: PgPool,
+    // listening task stops once sender is dropped
+    _stop: channel::Sender<()>,
+}
+
+impl ChannelsListener {
+    pub(super) fn start(
+        key: ConnectionKey,
+        settings: &ServerSettings,
+        channels: Vec<String>,
+        tx: Sender<DbResponse>,
+    ) -> Result<Self, sqlx::Error> {
+        let opts = connect_options(&settings.url, &key.name)?;
+        let pool = PgPoolOptions::new()
+            .max_connections(2)
+            .max_lifetime(None)
+            .idle_timeout(None)
+            .connect_lazy_with(opts);
+        let (stop_tx, stop_rx) = channel::bounded(1);
+        task::spawn(listen(key, pool.clone(), channels.clone(), tx, stop_rx));
+        Ok(Self {
+            channels,
+            pool,
+            _stop: stop_tx,
+        })
+    }
+
+    pub(super) fn notify(
+        &self,
+        key: ConnectionKey,
+        channel: String,
+        payload: String,
+        tx: Sender<DbResponse>,
+    ) {
+        let pool = self.pool.clone();
+        task::spawn(async move {
+            let result = sqlx::query("SELECT pg_notify($1, $2);")
+                .bind(&channel)
+                .bind(&payload)
+                .execute(&pool)
+                .await;
+            let response = match result {
+                Ok(_) => DbResponse::Notified {
+                    server_id: key.server_id,
+                    database: key.name,
+                    channel,
+                },
+                Err(e) => DbResponse::Error(key.server_id, e.to_string()),
+            };
+            if let Err(e) = tx.send(response) {
+                tracing::error!("Error sending notify response: {:?}", e);
+            }
+        });
+    }
+}
+
+async fn listen(
+    key: ConnectionKey,
+    pool: PgPool,
+    channels: Vec<String>,
+    tx: Sender<DbResponse>,
+    stop: channel::Receiver<()>,
+) {
+    let mut listener = match connect(&pool, &channels).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            let _ = tx.send(DbResponse::Error(key.server_id, e.to_string()));
+            return;
+        }
+    };
+    let response = DbResponse::Listening {
+        server_id: key.server_id,
+        database: key.name.clone(),
+        channels,
+    };
+    if tx.send(response).is_err() {
+        return;
+    }
+
+    loop {
+        let next = listener.recv();
+        let stopped = stop.recv();
+        futures::pin_mut!(next, stopped);
+        let notification = match future::select(next, stopped).await {
+            Either::Left((Ok(notification), _)) => notification,
+            Either::Left((Err(e), _)) => {
+                let _ = tx.send(DbResponse::ListeningStopped {
+                    server_id: key.server_id,
+                    database: key.name.clone(),
+                    message: e.to_string(),
+                });
+                return;
+            }
+            // nothing is ever sent, so this is only reached once sender is dropped
+            Either::Right(_) => return,
+        };
+        let notification = Notification {
+            server_id: key.server_id,
+            database: key.name.clone(),
+            channel: notification.channel().to_string(),
+            payload: notification.payload().to_string(),
+            process_id: notification.process_id(),
+            received_at: OffsetDateTime::now_utc(),
+        };
+        if tx
+            .send(DbResponse::NotificationReceived(notification))
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+async fn connect(pool: &PgPool, channels: &[String]) -> Result<PgListener, sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener
+        .listen_all(channels.iter().map(String::as_str))
+        .await?;
+    Ok(listener)
+}
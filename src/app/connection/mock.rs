@@ -0,0 +1,214 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    error::Error as StdError,
+    fmt::{Display, Formatter},
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
+use sqlx::error::{DatabaseError, ErrorKind};
+
+use super::{
+    cells::{CellValue, ColumnInfo},
+    driver::{DatabaseDriver, DriverCapabilities, FetchedRow, ObjectLevel},
+    posgres, Connection,
+};
+
+const SYNTAX_ERROR: &str = "42601";
+const IN_FAILED_SQL_TRANSACTION: &str = "25P02";
+
+/// Server kept in memory for tests, it knows results of given statements,
+/// fails the given ones and behaves like Postgres in failed transaction
+#[derive(Clone, Default)]
+pub(super) struct MockServer {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    results: HashMap<String, Vec<Vec<CellValue>>>,
+    failing: Vec<String>,
+    // every statement sent to server, in order
+    log: Vec<String>,
+    in_transaction: bool,
+    aborted: bool,
+    cursor: VecDeque<Vec<CellValue>>,
+}
+
+impl MockServer {
+    pub(super) fn with_result(self, statement: &str, rows: Vec<Vec<CellValue>>) -> Self {
+        self.state
+            .lock()
+            .unwrap()
+            .results
+            .insert(statement.to_string(), rows);
+        self
+    }
+
+    pub(super) fn with_failing(self, statement: &str) -> Self {
+        self.state
+            .lock()
+            .unwrap()
+            .failing
+            .push(statement.to_string());
+        self
+    }
+
+    pub(super) fn log(&self) -> Vec<String> {
+        self.state.lock().unwrap().log.clone()
+    }
+
+    pub(super) fn connection(&self) -> Connection {
+        Connection {
+            name: "mock".to_string(),
+            url: "postgres://mock".to_string(),
+            backend_pid: None,
+            driver: Box::new(MockDriver {
+                server: self.clone(),
+            }),
+        }
+    }
+
+    fn run(&self, statement: &str) -> Result<Vec<Vec<CellValue>>, sqlx::Error> {
+        let mut state = self.state.lock().unwrap();
+        state.log.push(statement.to_string());
+        let command = statement.to_lowercase();
+        if state.aborted && !command.starts_with("rollback") {
+            return Err(database_error(
+                IN_FAILED_SQL_TRANSACTION,
+                "current transaction is aborted",
+            ));
+        }
+        if state.failing.iter().any(|failing| failing == statement) {
+            state.aborted = state.in_transaction;
+            return Err(database_error(SYNTAX_ERROR, "syntax error"));
+        }
+        match command.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["begin", ..] => state.in_transaction = true,
+            ["commit" | "rollback"] => {
+                state.in_transaction = false;
+                state.aborted = false;
+            }
+            ["rollback", "to", ..] => state.aborted = false,
+            ["declare", ..] => {
+                let query = statement.split_once(" FOR ").map_or("", |(_, query)| query);
+                state.cursor = state.results.get(query).cloned().unwrap_or_default().into();
+            }
+            ["fetch", "forward", count, ..] => {
+                let count = count.parse::<usize>().unwrap_or_default();
+                let count = count.min(state.cursor.len());
+                return Ok(state.cursor.drain(..count).collect());
+            }
+            _ => {}
+        }
+        Ok(state.results.get(statement).cloned().unwrap_or_default())
+    }
+}
+
+struct MockDriver {
+    server: MockServer,
+}
+
+#[async_trait]
+impl DatabaseDriver for MockDriver {
+    async fn connect(_name: &str, _url: &str) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            server: MockServer::default(),
+        })
+    }
+
+    fn capabilities(&self) -> &'static DriverCapabilities {
+        &posgres::CAPABILITIES
+    }
+
+    async fn explain(&mut self, statement: &str, _analyze: bool) -> Result<String, sqlx::Error> {
+        self.server.run(statement)?;
+        Ok(format!("[{{\"Plan\": {:?}}}]", statement))
+    }
+
+    async fn execute_command(&mut self, command: &str) -> Result<(), sqlx::Error> {
+        self.server.run(command).map(|_| ())
+    }
+
+    async fn list_objects(
+        &mut self,
+        _level: ObjectLevel,
+        _path: &[String],
+    ) -> Result<Vec<String>, sqlx::Error> {
+        Ok(vec![])
+    }
+
+    async fn close(self: Box<Self>) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    fn fetch<'e>(
+        &'e mut self,
+        query: &'e str,
+        _parameters: Vec<Option<String>>,
+    ) -> BoxStream<'e, Result<FetchedRow, sqlx::Error>> {
+        let rows = match self.server.run(query) {
+            Ok(rows) => rows,
+            Err(e) => return stream::once(async { Err(e) }).boxed(),
+        };
+        let rows = rows.into_iter().enumerate().map(|(index, data)| {
+            let columns = (index == 0).then(|| {
+                (1..=data.len())
+                    .map(|number| ColumnInfo {
+                        name: format!("column{}", number),
+                        type_name: "TEXT".to_string(),
+                    })
+                    .collect()
+            });
+            Ok(FetchedRow { columns, data })
+        });
+        stream::iter(rows).boxed()
+    }
+}
+
+/// Database error with SQLSTATE code, like the one returned by server
+#[derive(Debug)]
+struct MockError {
+    code: &'static str,
+    message: &'static str,
+}
+
+pub(super) fn database_error(code: &'static str, message: &'static str) -> sqlx::Error {
+    sqlx::Error::Database(Box::new(MockError { code, message }))
+}
+
+impl Display for MockError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message)
+    }
+}
+
+impl StdError for MockError {}
+
+impl DatabaseError for MockError {
+    fn message(&self) -> &str {
+        self.message
+    }
+
+    fn code(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self.code))
+    }
+
+    fn as_error(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn as_error_mut(&mut self) -> &mut (dyn StdError + Send + Sync + 'static) {
+        self
+    }
+
+    fn into_error(self: Box<Self>) -> Box<dyn StdError + Send + Sync + 'static> {
+        self
+    }
+
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
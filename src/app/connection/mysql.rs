@@ -0,0 +1,246 @@
+use async_trait::async_trait;
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use sqlx::{
+    error::DatabaseError,
+    mysql::{MySqlArguments, MySqlConnectOptions, MySqlDatabaseError, MySqlRow, MySqlTypeInfo},
+    types::BigDecimal,
+    Arguments, Column, Connection, Executor, MySql, MySqlConnection, Row, TypeInfo, ValueRef,
+};
+
+use super::{
+    cells::{CellValue, ColumnInfo},
+    driver::{DatabaseDriver, DriverCapabilities, FetchedRow, ObjectLevel},
+    splitting::SqlDialect,
+    types::CommonTypeWriter,
+    SessionTimeouts,
+};
+
+// types: https://docs.rs/sqlx-mysql/0.7.2/sqlx_mysql/types/index.html
+
+// https://dev.mysql.com/doc/mysql-errors/8.0/en/server-error-reference.html
+const LOCK_WAIT_TIMEOUT: u16 = 1205;
+const QUERY_TIMEOUT: u16 = 3024;
+// https://mariadb.com/kb/en/mariadb-error-codes/
+const STATEMENT_TIMEOUT: u16 = 1969;
+
+struct MySqlWriter {}
+
+impl CommonTypeWriter<'_, MySqlTypeInfo, MySqlRow, MySql> for MySqlWriter {}
+
+// databases are schemas themselves, so tables go right under database
+pub(super) static CAPABILITIES: DriverCapabilities = DriverCapabilities {
+    levels: &[
+        ObjectLevel::Database,
+        ObjectLevel::Table,
+        ObjectLevel::Column,
+    ],
+    management_database: "information_schema",
+    query_plan: false,
+    cancel_query: true,
+    session_timeouts: true,
+    // wait_timeout closes any idle connection, not only the one in transaction
+    idle_transaction_timeout: false,
+    positional_placeholders: true,
+    dialect: SqlDialect::MySql,
+    notifications: false,
+    copy: false,
+    cursors: false,
+};
+
+pub(super) struct MySqlDriver {
+    connection: MySqlConnection,
+    connection_id: i64,
+    // MariaDB names statement timeout differently
+    mariadb: bool,
+}
+
+#[async_trait]
+impl DatabaseDriver for MySqlDriver {
+    async fn connect(name: &str, url: &str) -> Result<Self, sqlx::Error> {
+        // sqlx only knows mysql scheme, MariaDB speaks the same protocol
+        let url = url.replacen("mariadb:", "mysql:", 1);
+        let opts: MySqlConnectOptions = url.parse()?;
+        let opts = opts.database(name);
+        tracing::info!("Connecting to database: {:?}", opts);
+        let mut connection = MySqlConnection::connect_with(&opts).await?;
+        let row = connection
+            .fetch_one(sqlx::query(
+                "SELECT CAST(CONNECTION_ID() AS SIGNED), CAST(VERSION() AS CHAR);",
+            ))
+            .await?;
+        let version: String = row.get(1);
+        Ok(Self {
+            connection,
+            connection_id: row.get(0),
+            mariadb: version.contains("MariaDB"),
+        })
+    }
+
+    fn capabilities(&self) -> &'static DriverCapabilities {
+        &CAPABILITIES
+    }
+
+    fn backend_pid(&self) -> Option<i64> {
+        Some(self.connection_id)
+    }
+
+    /// Postgres timeouts are mapped to the closest MySQL variables, statement timeout
+    /// only limits reads and idle in transaction timeout has no such variable at all
+    async fn set_timeouts(&mut self, timeouts: &SessionTimeouts) -> Result<(), sqlx::Error> {
+        let millis = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(SessionTimeouts::millis)
+                .transpose()
+                .map_err(|e| sqlx::Error::Configuration(e.into()))
+        };
+        let mut variables = vec![];
+        match millis(&timeouts.statement_timeout)? {
+            Some(millis) if self.mariadb => {
+                variables.push(format!("max_statement_time = {}", millis as f64 / 1000.0))
+            }
+            Some(millis) => variables.push(format!("max_execution_time = {}", millis)),
+            None => {}
+        }
+        // it is in whole seconds and could not be disabled with 0 like in Postgres
+        if let Some(millis) = millis(&timeouts.lock_timeout)?.filter(|millis| *millis > 0) {
+            variables.push(format!(
+                "innodb_lock_wait_timeout = {}",
+                millis.div_ceil(1000)
+            ));
+        }
+        if variables.is_empty() {
+            return Ok(());
+        }
+        self.connection
+            .execute(format!("SET SESSION {};", variables.join(", ")).as_str())
+            .await?;
+        Ok(())
+    }
+
+    async fn execute_command(&mut self, command: &str) -> Result<(), sqlx::Error> {
+        self.connection.execute(command).await?;
+        Ok(())
+    }
+
+    async fn list_objects(
+        &mut self,
+        level: ObjectLevel,
+        path: &[String],
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let mut args = MySqlArguments::default();
+        let query = match (level, path) {
+            (ObjectLevel::Database, _) => {
+                "SELECT CAST(schema_name AS CHAR) FROM information_schema.schemata ORDER BY schema_name;"
+            }
+            (ObjectLevel::Table, [database]) => {
+                args.add(database);
+                "SELECT CAST(table_name AS CHAR) FROM information_schema.tables WHERE table_schema = ? ORDER BY table_name;"
+            }
+            (ObjectLevel::Column, [database, table]) => {
+                args.add(database);
+                args.add(table);
+                "SELECT CAST(column_name AS CHAR) FROM information_schema.columns WHERE table_schema = ? AND table_name = ? ORDER BY ordinal_position;"
+            }
+            _ => return Ok(vec![]),
+        };
+        let rows = self
+            .connection
+            .fetch_all(sqlx::query_with(query, args))
+            .await?;
+        Ok(rows.iter().map(|row| row.get::<String, usize>(0)).collect())
+    }
+
+    async fn cancel_query(&mut self, backend_pid: i64) -> Result<(), sqlx::Error> {
+        self.connection
+            .execute(format!("KILL QUERY {};", backend_pid).as_str())
+            .await?;
+        Ok(())
+    }
+
+    async fn close(self: Box<Self>) -> Result<(), sqlx::Error> {
+        self.connection.close().await
+    }
+
+    fn fetch<'e>(
+        &'e mut self,
+        query: &'e str,
+        parameters: Vec<Option<String>>,
+    ) -> BoxStream<'e, Result<FetchedRow, sqlx::Error>> {
+        let mut columns_sent = false;
+
+        let rows = if parameters.is_empty() {
+            // text protocol is used, as not every statement could be prepared
+            self.connection.fetch(query)
+        } else {
+            let mut args = MySqlArguments::default();
+            for parameter in parameters {
+                args.add(parameter);
+            }
+            sqlx::query_with(query, args)
+                .persistent(false)
+                .fetch(&mut self.connection)
+        };
+
+        rows.map_ok(move |row: MySqlRow| {
+            let mut data: Vec<CellValue> = vec![];
+            let columns = if columns_sent {
+                None
+            } else {
+                columns_sent = true;
+                Some(
+                    row.columns()
+                        .iter()
+                        .map(|col| ColumnInfo {
+                            name: col.name().to_string(),
+                            type_name: col.type_info().name().to_string(),
+                        })
+                        .collect(),
+                )
+            };
+            for i in 0..row.len() {
+                write_cell(&row, i, &mut data);
+            }
+
+            FetchedRow { columns, data }
+        })
+        .boxed()
+    }
+}
+
+/// Tells if statement failed because of one of session timeouts
+pub(super) fn is_timeout(error: &dyn DatabaseError) -> bool {
+    error
+        .try_downcast_ref::<MySqlDatabaseError>()
+        .is_some_and(|error| {
+            matches!(
+                error.number(),
+                LOCK_WAIT_TIMEOUT | QUERY_TIMEOUT | STATEMENT_TIMEOUT
+            )
+        })
+}
+
+/// Unsigned integers and decimals are not shared with other databases,
+/// so they are checked before common types
+fn write_cell(row: &MySqlRow, i: usize, data: &mut Vec<CellValue>) {
+    match row.try_get_raw(i) {
+        Ok(value) if value.is_null() => {
+            data.push(CellValue::Null);
+            return;
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::debug!("Error getting value: {}", e);
+            data.push(CellValue::Unsupported(String::new()));
+            return;
+        }
+    }
+    let type_info = row.column(i).type_info();
+    if MySqlWriter::write_typed::<u64>(type_info, row, i, data) {
+        return;
+    }
+    if MySqlWriter::write_typed::<BigDecimal>(type_info, row, i, data) {
+        return;
+    }
+    MySqlWriter::write_row_cell(type_info, row, i, data);
+}
@@ -0,0 +1,258 @@
+use std::{
+    fmt::{Debug, Display, Formatter},
+    sync::mpsc::Sender,
+};
+
+use sqlx::types::time::OffsetDateTime;
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{
+    filter::filter_fn, layer::Context, registry::LookupSpan, Layer, Registry,
+};
+use uuid::Uuid;
+
+use super::DbResponse;
+
+// sqlx does not return notices sent by Postgres, but logs them with this target,
+// so they are caught by tracing layer while query is executed inside of capturing span,
+// the target is internal to sqlx and has to be checked when sqlx is updated
+const NOTICE_TARGET: &str = "sqlx::postgres::notice";
+const CAPTURE_SPAN: &str = "capture_notices";
+
+#[derive(PartialEq, PartialOrd, Clone, Copy, Eq, Debug)]
+pub(crate) enum NoticeSeverity {
+    Error,
+    Warning,
+    Notice,
+    Debug,
+    Info,
+}
+
+impl NoticeSeverity {
+    // sqlx keeps only the level of log, so INFO and LOG are not told apart
+    fn from_level(level: &Level) -> Self {
+        match *level {
+            Level::ERROR => NoticeSeverity::Error,
+            Level::WARN => NoticeSeverity::Warning,
+            Level::INFO => NoticeSeverity::Notice,
+            Level::DEBUG => NoticeSeverity::Debug,
+            Level::TRACE => NoticeSeverity::Info,
+        }
+    }
+}
+
+impl Display for NoticeSeverity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            NoticeSeverity::Error => "ERROR",
+            NoticeSeverity::Warning => "WARNING",
+            NoticeSeverity::Notice => "NOTICE",
+            NoticeSeverity::Debug => "DEBUG",
+            NoticeSeverity::Info => "INFO",
+        })
+    }
+}
+
+/// Message sent by server while executing query, f.e by RAISE NOTICE
+#[derive(PartialEq, PartialOrd, Clone, Eq, Debug)]
+pub(crate) struct ServerMessage {
+    pub(crate) severity: NoticeSeverity,
+    pub(crate) message: String,
+    pub(crate) received_at: OffsetDateTime,
+}
+
+struct Capture {
+    server_id: Uuid,
+    database: String,
+    tx: Sender<DbResponse>,
+    messages: Vec<ServerMessage>,
+}
+
+/// Collects messages of the server while its span is alive, each new message
+/// is sent to main thread together with all previous ones of the same execution
+pub(super) struct NoticeCapture {
+    span: tracing::Span,
+}
+
+impl NoticeCapture {
+    pub(super) fn start(server_id: Uuid, database: String, tx: Sender<DbResponse>) -> Self {
+        // messages of previous execution are not relevant anymore
+        let _ = tx.send(DbResponse::ServerMessages {
+            server_id,
+            database: database.clone(),
+            messages: vec![],
+        });
+        let capture = Capture {
+            server_id,
+            database,
+            tx,
+            messages: vec![],
+        };
+        // capture is kept in extensions of its own span, so notice only finds
+        // the capture of execution it came from
+        let span = tracing::info_span!(CAPTURE_SPAN);
+        span.with_subscriber(|(id, dispatch)| {
+            let span = dispatch
+                .downcast_ref::<Registry>()
+                .and_then(|registry| registry.span(id));
+            if let Some(span) = span {
+                span.extensions_mut().insert(capture);
+            }
+        });
+        Self { span }
+    }
+
+    /// Notices logged inside of this span are captured
+    pub(super) fn span(&self) -> tracing::Span {
+        self.span.clone()
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0 = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+struct NoticeLayer;
+
+impl<S> Layer<S> for NoticeLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let message = ServerMessage {
+            severity: NoticeSeverity::from_level(event.metadata().level()),
+            message: visitor.0,
+            received_at: OffsetDateTime::now_utc(),
+        };
+
+        // notice outside of executed query has no scope or no capturing span in it
+        for span in ctx.event_scope(event).into_iter().flatten() {
+            let mut extensions = span.extensions_mut();
+            if let Some(capture) = extensions.get_mut::<Capture>() {
+                capture.messages.push(message);
+                let _ = capture.tx.send(DbResponse::ServerMessages {
+                    server_id: capture.server_id,
+                    database: capture.database.clone(),
+                    messages: capture.messages.clone(),
+                });
+                return;
+            }
+        }
+    }
+}
+
+/// Layer of tracing subscriber, which catches notices logged by sqlx,
+/// it has own filter to get them no matter which level is logged to file
+pub(crate) fn notice_layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + 'static,
+{
+    NoticeLayer.with_filter(filter_fn(|metadata| {
+        metadata.target() == NOTICE_TARGET
+            || (metadata.is_span() && metadata.name() == CAPTURE_SPAN)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    fn notice(level: Level, message: &str) {
+        // the same way as sqlx logs notices
+        match level {
+            Level::WARN => tracing::warn!(target: NOTICE_TARGET, message),
+            Level::INFO => tracing::info!(target: NOTICE_TARGET, message),
+            _ => tracing::trace!(target: NOTICE_TARGET, message),
+        }
+    }
+
+    fn received(responses: Vec<DbResponse>) -> Vec<(String, Vec<(NoticeSeverity, String)>)> {
+        responses
+            .into_iter()
+            .map(|response| match response {
+                DbResponse::ServerMessages {
+                    database, messages, ..
+                } => (
+                    database,
+                    messages
+                        .into_iter()
+                        .map(|message| (message.severity, message.message))
+                        .collect(),
+                ),
+                response => panic!("unexpected response {:?}", response),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn notices_go_to_execution_they_came_from() {
+        let subscriber = tracing_subscriber::registry().with(notice_layer());
+        let (tx, rx) = mpsc::channel();
+        tracing::subscriber::with_default(subscriber, || {
+            let first = NoticeCapture::start(Uuid::new_v4(), "first".to_string(), tx.clone());
+            let second = NoticeCapture::start(Uuid::new_v4(), "second".to_string(), tx.clone());
+            first
+                .span()
+                .in_scope(|| notice(Level::INFO, "first notice"));
+            second.span().in_scope(|| {
+                // spans of sqlx could be opened inside of capturing one
+                tracing::info_span!("fetch").in_scope(|| notice(Level::WARN, "second warning"));
+                tracing::info!("not a notice");
+            });
+            first.span().in_scope(|| notice(Level::TRACE, "first log"));
+            drop(second);
+            notice(Level::INFO, "outside of execution");
+            first.span().in_scope(|| notice(Level::INFO, "first again"));
+        });
+
+        let first = |messages: &[(NoticeSeverity, &str)]| {
+            let messages = messages
+                .iter()
+                .map(|(severity, message)| (*severity, message.to_string()))
+                .collect();
+            ("first".to_string(), messages)
+        };
+        assert_eq!(
+            received(rx.try_iter().collect()),
+            vec![
+                ("first".to_string(), vec![]),
+                ("second".to_string(), vec![]),
+                first(&[(NoticeSeverity::Notice, "first notice")]),
+                (
+                    "second".to_string(),
+                    vec![(NoticeSeverity::Warning, "second warning".to_string())]
+                ),
+                first(&[
+                    (NoticeSeverity::Notice, "first notice"),
+                    (NoticeSeverity::Info, "first log"),
+                ]),
+                first(&[
+                    (NoticeSeverity::Notice, "first notice"),
+                    (NoticeSeverity::Info, "first log"),
+                    (NoticeSeverity::Notice, "first again"),
+                ]),
+            ]
+        );
+    }
+}
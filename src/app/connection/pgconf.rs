@@ -0,0 +1,340 @@
+use std::{collections::HashMap, env, fs, path::PathBuf, process::Command};
+
+use once_cell::sync::Lazy;
+use sqlx::postgres::PgConnectOptions;
+
+use super::{decode_url_part, push_url_parameter};
+
+// sysconfdir differs between builds of libpq: Debian and Ubuntu packages,
+// most of the other distributions and Homebrew or BSD ports
+#[cfg(not(windows))]
+const DEFAULT_SYSCONFDIRS: [&str; 3] = [
+    "/etc/postgresql-common",
+    "/etc/postgresql",
+    "/usr/local/etc",
+];
+
+/// Sysconfdir of installed libpq, if pg_config is found in PATH
+static PG_CONFIG_SYSCONFDIR: Lazy<Option<PathBuf>> = Lazy::new(|| {
+    let output = Command::new("pg_config")
+        .arg("--sysconfdir")
+        .output()
+        .ok()?;
+    let dir = String::from_utf8(output.stdout).ok()?;
+    let dir = dir.trim();
+    (output.status.success() && !dir.is_empty()).then(|| PathBuf::from(dir))
+});
+
+/// Connection service defined in pg_service.conf, the same way as for psql
+struct Service {
+    name: String,
+    // libpq parameters like `host` or `dbname` in order of the file
+    parameters: Vec<(String, String)>,
+}
+
+/// Options to connect to Postgres database, password missing in url is taken
+/// from PGPASSWORD or .pgpass, so it is looked up for this database
+pub(super) fn connect_options(url: &str, database: &str) -> Result<PgConnectOptions, sqlx::Error> {
+    let mut url = resolve_service(url)?;
+    // driver looks up .pgpass while parsing url, so database has to be in url
+    push_url_parameter(&mut url, "dbname", database);
+    url.parse()
+}
+
+/// Replaces `service` parameter of url with parameters of the service,
+/// parameters given in url after it override the ones of the service
+pub(super) fn resolve_service(url: &str) -> Result<String, sqlx::Error> {
+    resolve_service_in(url, &service_files())
+}
+
+/// Resolves service of url using the first of the files defining it
+fn resolve_service_in(url: &str, files: &[PathBuf]) -> Result<String, sqlx::Error> {
+    let (base, query) = match url.split_once('?') {
+        Some(split) => split,
+        None => return Ok(url.to_string()),
+    };
+    let mut service = None;
+    let mut parameters = vec![];
+    for parameter in query.split('&').filter(|parameter| !parameter.is_empty()) {
+        match parameter.strip_prefix("service=") {
+            Some(name) => service = Some(decode_url_part(name)),
+            None => parameters.push(parameter),
+        }
+    }
+    let service = match service {
+        Some(service) => service,
+        None => return Ok(url.to_string()),
+    };
+    let service = find_service(&service, files).ok_or_else(|| {
+        sqlx::Error::Configuration(
+            format!("Service {} is not found in pg_service.conf", service).into(),
+        )
+    })?;
+
+    let mut resolved = base.to_string();
+    let url_host = base
+        .split_once("://")
+        .and_then(|(_, rest)| rest.split('/').next())
+        .and_then(|authority| authority.rsplit('@').next());
+    // empty host of url would be used by driver, so the default one is set first
+    if url_host == Some("") {
+        let host = env::var("PGHOST").unwrap_or_else(|_| "localhost".to_string());
+        push_url_parameter(&mut resolved, "host", &host);
+    }
+    for (name, value) in &service.parameters {
+        push_url_parameter(&mut resolved, name, value);
+    }
+    for parameter in parameters {
+        resolved.push(if resolved.contains('?') { '&' } else { '?' });
+        resolved.push_str(parameter);
+    }
+    Ok(resolved)
+}
+
+/// Servers known to psql, which could be imported: services of pg_service.conf
+/// and the server given by PG* environment variables, as names and connection properties
+pub(crate) fn discover_servers() -> Vec<(String, HashMap<String, String>)> {
+    let mut servers: Vec<(String, HashMap<String, String>)> = vec![];
+    for service in service_files().iter().flat_map(read_services) {
+        // services of user's file go first and hide system-wide ones
+        if servers.iter().any(|(name, _)| *name == service.name) {
+            continue;
+        }
+        let properties = HashMap::from([("service".to_string(), service.name.clone())]);
+        servers.push((service.name, properties));
+    }
+    servers.extend(environment_server());
+    servers
+}
+
+/// Server set up with PGHOST, PGUSER and similar variables, if any of them is set
+fn environment_server() -> Option<(String, HashMap<String, String>)> {
+    let mut properties: HashMap<String, String> = [
+        ("host", "PGHOST"),
+        ("port", "PGPORT"),
+        ("user", "PGUSER"),
+        ("database", "PGDATABASE"),
+        ("sslmode", "PGSSLMODE"),
+    ]
+    .into_iter()
+    .filter_map(|(property, variable)| {
+        let value = env::var(variable).ok().filter(|value| !value.is_empty())?;
+        Some((property.to_string(), value))
+    })
+    .collect();
+    if properties.is_empty() {
+        return None;
+    }
+    let host = properties
+        .entry("host".to_string())
+        .or_insert_with(|| "localhost".to_string());
+    Some((format!("{} from environment", host), properties))
+}
+
+/// Parameters of the service, user's file is preferred over system-wide one
+fn find_service(name: &str, files: &[PathBuf]) -> Option<Service> {
+    files
+        .iter()
+        .flat_map(read_services)
+        .find(|service| service.name == name)
+}
+
+/// Files with services in order of lookup, like libpq does it
+fn service_files() -> Vec<PathBuf> {
+    let user_file = match env::var_os("PGSERVICEFILE") {
+        Some(file) => Some(PathBuf::from(file)),
+        #[cfg(windows)]
+        None => dirs::config_dir().map(|dir| dir.join("postgresql").join(".pg_service.conf")),
+        #[cfg(not(windows))]
+        None => dirs::home_dir().map(|home| home.join(".pg_service.conf")),
+    };
+    user_file
+        .into_iter()
+        .chain(
+            system_dirs()
+                .into_iter()
+                .map(|dir| dir.join("pg_service.conf")),
+        )
+        .collect()
+}
+
+/// Directories which could have system-wide pg_service.conf, the one
+/// given by PGSYSCONFDIR or the one libpq is built with, if it is known
+fn system_dirs() -> Vec<PathBuf> {
+    if let Some(dir) = env::var_os("PGSYSCONFDIR") {
+        return vec![PathBuf::from(dir)];
+    }
+    #[cfg(not(windows))]
+    let defaults = DEFAULT_SYSCONFDIRS.map(PathBuf::from);
+    #[cfg(windows)]
+    let defaults: [PathBuf; 0] = [];
+    let pg_config = PG_CONFIG_SYSCONFDIR.as_ref();
+    pg_config
+        .cloned()
+        .into_iter()
+        .chain(defaults.into_iter().filter(|dir| Some(dir) != pg_config))
+        .collect()
+}
+
+/// Reads services from ini-like file, missing file has no services
+fn read_services(path: &PathBuf) -> Vec<Service> {
+    match fs::read_to_string(path) {
+        Ok(content) => parse_services(&content),
+        Err(_) => vec![],
+    }
+}
+
+/// Services in order of the file, when section is repeated the first one is used
+fn parse_services(content: &str) -> Vec<Service> {
+    let mut services: Vec<Service> = vec![];
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            services.push(Service {
+                name: name.trim().to_string(),
+                parameters: vec![],
+            });
+        } else if let (Some(service), Some((name, value))) =
+            (services.last_mut(), line.split_once('='))
+        {
+            service
+                .parameters
+                .push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    services
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    /// Runs test with user's and system service files of given content
+    fn with_service_files(user: &str, system: &str, test: impl FnOnce(&[PathBuf])) {
+        let dir = env::temp_dir().join(format!("tisq-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let files = [dir.join(".pg_service.conf"), dir.join("pg_service.conf")];
+        fs::write(&files[0], user).unwrap();
+        fs::write(&files[1], system).unwrap();
+        test(&files);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    fn connect_options(url: &str, files: &[PathBuf]) -> PgConnectOptions {
+        resolve_service_in(url, files).unwrap().parse().unwrap()
+    }
+
+    fn parameters(service: &Service) -> Vec<(&str, &str)> {
+        service
+            .parameters
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn comments_and_empty_lines_are_skipped() {
+        let services = parse_services(
+            "# services of the team\n\n[main]\n  # host=old\nhost=db\n\n#[disabled]\nport=5433\n",
+        );
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].name, "main");
+        assert_eq!(parameters(&services[0]), [("host", "db"), ("port", "5433")]);
+    }
+
+    #[test]
+    fn whitespace_around_equals_is_trimmed() {
+        let services =
+            parse_services("[ main ]\nhost = db \n\tuser=admin\noptions = -c search_path=app\n");
+        assert_eq!(services[0].name, "main");
+        assert_eq!(
+            parameters(&services[0]),
+            [
+                ("host", "db"),
+                ("user", "admin"),
+                ("options", "-c search_path=app")
+            ]
+        );
+    }
+
+    #[test]
+    fn first_of_duplicate_sections_is_found() {
+        let services =
+            parse_services("[main]\nhost=first\n[other]\nhost=other\n[main]\nhost=second\n");
+        let main = services
+            .iter()
+            .find(|service| service.name == "main")
+            .unwrap();
+        assert_eq!(parameters(main), [("host", "first")]);
+        let names: Vec<_> = services
+            .iter()
+            .map(|service| service.name.as_str())
+            .collect();
+        assert_eq!(names, ["main", "other", "main"]);
+    }
+
+    #[test]
+    fn parameters_before_any_section_are_ignored() {
+        let services = parse_services("host=nowhere\n[main]\ndbname=app\n");
+        assert_eq!(services.len(), 1);
+        assert_eq!(parameters(&services[0]), [("dbname", "app")]);
+    }
+
+    #[test]
+    fn user_service_file_is_preferred_over_system_one() {
+        with_service_files(
+            "[main]\nhost=user-db\n",
+            "[main]\nhost=system-db\nport=5433\n[other]\nhost=other-db\n",
+            |files| {
+                let options = connect_options("postgres://me@db/app?service=main", files);
+                assert_eq!(options.get_host(), "user-db");
+                // parameters of the same service are not merged from both files
+                assert_eq!(options.get_port(), 5432);
+                let options = connect_options("postgres://me@db/app?service=other", files);
+                assert_eq!(options.get_host(), "other-db");
+                assert!(resolve_service_in("postgres://me@db/app?service=none", files).is_err());
+            },
+        );
+    }
+
+    #[test]
+    fn url_parameters_override_service() {
+        with_service_files(
+            "[main]\nhost=service-db\nport=5433\nsslmode=require\n",
+            "",
+            |files| {
+                assert_eq!(
+                    resolve_service_in(
+                        "postgres://me@db/app?port=6000&service=main&sslmode=disable",
+                        files
+                    )
+                    .unwrap(),
+                    "postgres://me@db/app?host=service-db&port=5433&sslmode=require\
+                        &port=6000&sslmode=disable"
+                );
+                let options = connect_options(
+                    "postgres://me@db/app?port=6000&service=main&sslmode=disable",
+                    files,
+                );
+                assert_eq!(options.get_host(), "service-db");
+                assert_eq!(options.get_port(), 6000);
+                assert!(matches!(
+                    options.get_ssl_mode(),
+                    sqlx::postgres::PgSslMode::Disable
+                ));
+                // url without service is left as is
+                assert_eq!(
+                    resolve_service_in("postgres://me@db/app?port=6000", files).unwrap(),
+                    "postgres://me@db/app?port=6000"
+                );
+            },
+        );
+    }
+}
@@ -1,20 +1,23 @@
 use std::fmt::Display;
-use std::sync::Arc;
 
 use async_trait::async_trait;
-use futures::TryStreamExt;
-use itertools::Itertools;
-use sqlx::types::BigDecimal;
+use futures::stream::{BoxStream, StreamExt, TryStreamExt};
+use sqlx::types::{BigDecimal, JsonValue};
 use sqlx::ValueRef;
 use sqlx::{
     error::BoxDynError,
-    postgres::{PgRow, PgTypeInfo, PgTypeKind},
-    Column, PgConnection, Postgres, Row, Type, TypeInfo,
+    postgres::{types::Oid, PgArguments, PgRow, PgTypeInfo, PgTypeKind, PgValueFormat, PgValueRef},
+    Arguments, Column, Connection, Decode, Executor, PgConnection, Postgres, Row, Type, TypeInfo,
 };
 
 use super::{
-    executing::Executing,
+    cells::{CellValue, ColumnInfo},
+    driver::{DatabaseDriver, DriverCapabilities, FetchedRow, ObjectLevel},
+    pgconf::connect_options,
+    ranges::{is_multirange, PgMultirangeLiteral, PgRangeLiteral},
+    splitting::{split_statements, SqlDialect},
     types::{GenericArrayTypeWriter, GenericTypeWriter},
+    SessionTimeouts,
 };
 use sqlx_postgres::types::PgRecordDecoder;
 
@@ -32,79 +35,259 @@ struct PgArrayWriter {}
 
 impl GenericArrayTypeWriter<'_, PgTypeInfo, PgRow, Postgres> for PgArrayWriter {}
 
-#[async_trait]
-impl Executing for PgConnection {
-    async fn execute_sqlx(
+// anonymous records, like the ones made by `row(...)`, are not known as composites
+const RECORD_OID: Oid = Oid(2249);
+
+pub(super) static CAPABILITIES: DriverCapabilities = DriverCapabilities {
+    levels: &[
+        ObjectLevel::Database,
+        ObjectLevel::Schema,
+        ObjectLevel::Table,
+        ObjectLevel::Column,
+    ],
+    management_database: "postgres",
+    query_plan: true,
+    cancel_query: true,
+    session_timeouts: true,
+    idle_transaction_timeout: true,
+    positional_placeholders: false,
+    dialect: SqlDialect::Postgres,
+    notifications: true,
+    copy: true,
+    cursors: true,
+};
+
+pub(super) struct PostgresDriver {
+    connection: PgConnection,
+    backend_pid: i32,
+}
+
+impl PostgresDriver {
+    async fn fetch_names(
         &mut self,
         query: &str,
-    ) -> Result<(Vec<String>, Vec<Vec<String>>), sqlx::Error> {
-        let mut headers: Vec<String> = vec![];
+        args: PgArguments,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let rows = self
+            .connection
+            .fetch_all(sqlx::query_with(query, args))
+            .await?;
+        Ok(rows.iter().map(|row| row.get::<String, usize>(0)).collect())
+    }
+}
 
-        let mut rows = sqlx::query(&query)
-            .persistent(false)
-            .map(|row: PgRow| {
-                let mut data: Vec<String> = vec![];
-                if headers.is_empty() {
-                    headers = row
-                        .columns()
-                        .iter()
-                        .map(|col| col.name().to_string())
-                        .collect();
+#[async_trait]
+impl DatabaseDriver for PostgresDriver {
+    async fn connect(name: &str, url: &str) -> Result<Self, sqlx::Error> {
+        let opts = connect_options(url, name)?;
+        tracing::info!("Connecting to database: {:?}", opts);
+        let mut connection = PgConnection::connect_with(&opts).await?;
+        let backend_pid: i32 = connection
+            .fetch_one(sqlx::query("SELECT pg_backend_pid();"))
+            .await?
+            .get(0);
+        Ok(Self {
+            connection,
+            backend_pid,
+        })
+    }
+
+    fn capabilities(&self) -> &'static DriverCapabilities {
+        &CAPABILITIES
+    }
+
+    fn backend_pid(&self) -> Option<i64> {
+        Some(self.backend_pid.into())
+    }
+
+    async fn set_timeouts(&mut self, timeouts: &SessionTimeouts) -> Result<(), sqlx::Error> {
+        for (name, value) in timeouts.settings() {
+            let mut args = PgArguments::default();
+            args.add(name);
+            args.add(value);
+            self.connection
+                .execute(sqlx::query_with("SELECT set_config($1, $2, false);", args))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn describe_parameters(&mut self, statement: &str) -> Result<Vec<String>, sqlx::Error> {
+        let describe = self.connection.describe(statement).await?;
+        let types = describe
+            .parameters
+            .and_then(|parameters| parameters.left())
+            .unwrap_or_default();
+        Ok(types
+            .iter()
+            .map(|type_info| type_info.to_string())
+            .collect())
+    }
+
+    async fn explain(&mut self, statement: &str, analyze: bool) -> Result<String, sqlx::Error> {
+        let options = if analyze {
+            "FORMAT JSON, ANALYZE, BUFFERS"
+        } else {
+            "FORMAT JSON"
+        };
+        // semicolon and comments after it would otherwise end up inside of EXPLAIN
+        let statement = match split_statements(statement, SqlDialect::Postgres).as_slice() {
+            [statement] => statement.clone(),
+            _ => {
+                return Err(sqlx::Error::Configuration(
+                    "Only one statement could be explained".into(),
+                ))
+            }
+        };
+        let explain = format!("EXPLAIN ({}) {}", options, statement);
+        let plan: JsonValue = self
+            .connection
+            .fetch_one(sqlx::query(&explain).persistent(false))
+            .await?
+            .try_get(0)?;
+        Ok(ToString::to_string(&plan))
+    }
+
+    async fn execute_command(&mut self, command: &str) -> Result<(), sqlx::Error> {
+        self.connection.execute(command).await?;
+        Ok(())
+    }
+
+    async fn list_objects(
+        &mut self,
+        level: ObjectLevel,
+        path: &[String],
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let mut args = PgArguments::default();
+        let query = match (level, path) {
+            (ObjectLevel::Database, _) => {
+                "SELECT datname FROM pg_database WHERE datistemplate = false;"
+            }
+            (ObjectLevel::Schema, _) => "SELECT schema_name FROM information_schema.schemata;",
+            (ObjectLevel::Table, [_, schema]) => {
+                args.add(schema);
+                "SELECT table_name FROM information_schema.tables where table_schema = $1;"
+            }
+            (ObjectLevel::Column, [_, schema, table]) => {
+                args.add(schema);
+                args.add(table);
+                "SELECT column_name FROM information_schema.columns where table_schema = $1 and table_name = $2;"
+            }
+            _ => return Ok(vec![]),
+        };
+        self.fetch_names(query, args).await
+    }
+
+    async fn cancel_query(&mut self, backend_pid: i64) -> Result<(), sqlx::Error> {
+        let backend_pid = i32::try_from(backend_pid).map_err(|_| {
+            sqlx::Error::Configuration(format!("Invalid backend pid {}", backend_pid).into())
+        })?;
+        let mut args = PgArguments::default();
+        args.add(backend_pid);
+        self.connection
+            .execute(sqlx::query_with("SELECT pg_cancel_backend($1);", args))
+            .await?;
+        Ok(())
+    }
+
+    async fn close(self: Box<Self>) -> Result<(), sqlx::Error> {
+        self.connection.close().await
+    }
+
+    async fn copy_out<'e>(
+        &'e mut self,
+        statement: &'e str,
+    ) -> Result<BoxStream<'e, Result<Vec<u8>, sqlx::Error>>, sqlx::Error> {
+        let data = self.connection.copy_out_raw(statement).await?;
+        Ok(data.map_ok(|chunk| chunk.to_vec()).boxed())
+    }
+
+    async fn copy_in(
+        &mut self,
+        statement: &str,
+        mut data: BoxStream<'_, Result<Vec<u8>, sqlx::Error>>,
+    ) -> Result<u64, sqlx::Error> {
+        let mut copy = self.connection.copy_in_raw(statement).await?;
+        loop {
+            match data.try_next().await {
+                Ok(Some(chunk)) => {
+                    copy.send(chunk).await?;
+                }
+                Ok(None) => return copy.finish().await,
+                Err(e) => {
+                    // server has to be told, otherwise connection stays in copy mode
+                    copy.abort(e.to_string()).await?;
+                    return Err(e);
                 }
+            }
+        }
+    }
+
+    fn fetch<'e>(
+        &'e mut self,
+        query: &'e str,
+        parameters: Vec<Option<String>>,
+    ) -> BoxStream<'e, Result<FetchedRow, sqlx::Error>> {
+        let mut columns_sent = false;
+
+        let mut args = PgArguments::default();
+        for parameter in parameters {
+            args.add(parameter);
+        }
+
+        sqlx::query_with(query, args)
+            .persistent(false)
+            .map(move |row: PgRow| {
+                let mut data: Vec<CellValue> = vec![];
+                let columns = if columns_sent {
+                    None
+                } else {
+                    columns_sent = true;
+                    Some(
+                        row.columns()
+                            .iter()
+                            .map(|col| ColumnInfo {
+                                name: col.name().to_string(),
+                                type_name: col.type_info().name().to_string(),
+                            })
+                            .collect(),
+                    )
+                };
                 for (i, col) in row.columns().iter().enumerate() {
-                    let type_info = col.type_info();
+                    let type_info = domain_base_type(col.type_info());
                     // tracing::debug!("checking type: {}", type_info.name());
 
                     match type_info.kind() {
+                        PgTypeKind::Simple if is_compound(type_info) => {
+                            write_decoded::<PgValueCell>(&row, i, &mut data);
+                        }
                         PgTypeKind::Simple => {
                             // tracing::debug!("Simple type: {}", type_info.name());
                             PgWriter::write_row_cell(type_info, &row, i, &mut data);
                         }
+                        PgTypeKind::Array(_) if is_compound(type_info) => {
+                            write_decoded::<PgValueCell>(&row, i, &mut data);
+                        }
                         PgTypeKind::Array(internal_type_info) => {
                             // tracing::debug!("Array type: {}", type_info.name());
-                            PgArrayWriter::write_row_cell(internal_type_info, &row, i, &mut data);
+                            PgArrayWriter::write_row_cell(
+                                domain_base_type(internal_type_info),
+                                &row,
+                                i,
+                                &mut data,
+                            );
                         }
-                        PgTypeKind::Pseudo => {
+                        // domains are already resolved to their base types
+                        PgTypeKind::Pseudo | PgTypeKind::Domain(_) => {
                             tracing::debug!("Pseudo type not supported: {}", type_info.name());
-                            data.push("not supported".to_string());
+                            data.push(CellValue::Unsupported(type_info.name().to_string()));
                         }
-                        PgTypeKind::Domain(_) => {
-                            tracing::debug!("Domain type not supported: {}", type_info.name());
-                            data.push("not supported".to_string());
+                        PgTypeKind::Composite(_) | PgTypeKind::Range(_) => {
+                            write_decoded::<PgValueCell>(&row, i, &mut data);
                         }
-                        PgTypeKind::Composite(type_def) => match row.try_get_raw(i) {
-                            Ok(value) => {
-                                if value.is_null() {
-                                    data.push("null".to_string());
-                                    continue;
-                                }
-                                let decoder = PgRecordDecoder::new(value);
-                                match decoder {
-                                    Ok(mut decoder) => {
-                                        decode_and_write(&mut decoder, type_def.clone(), &mut data);
-                                    }
-                                    Err(e) => {
-                                        tracing::debug!(
-                                            "Composite type not supported: {} {}",
-                                            type_info.name(),
-                                            e
-                                        );
-                                        data.push("not supported".to_string());
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                tracing::debug!(
-                                    "Composite type not supported: {} {}",
-                                    type_info.name(),
-                                    e
-                                );
-                                data.push("not supported".to_string());
-                            }
-                        },
                         PgTypeKind::Enum(enum_values) => match row.try_get_raw(i) {
                             Ok(value) => match value.as_str() {
-                                Ok(value) => data.push(value.to_string()),
+                                Ok(value) => data.push(CellValue::Text(value.to_string())),
                                 Err(e) => {
                                     tracing::debug!(
                                         "Error getting enum value: {} {:?} {}",
@@ -112,7 +295,7 @@ impl Executing for PgConnection {
                                         enum_values,
                                         e
                                     );
-                                    data.push("not supported".to_string());
+                                    data.push(CellValue::Unsupported(String::new()));
                                 }
                             },
                             Err(e) => {
@@ -122,165 +305,219 @@ impl Executing for PgConnection {
                                     enum_values,
                                     e
                                 );
-                                data.push("not supported".to_string());
+                                data.push(CellValue::Unsupported(String::new()));
                             }
                         },
-                        PgTypeKind::Range(_) => {
-                            tracing::debug!("Range type not supported: {}", type_info.name());
-                            data.push("not supported".to_string());
-                        }
                     };
                 }
 
-                data
+                FetchedRow { columns, data }
             })
-            .fetch(self);
-        // .fetch_all(self);
-
-        let mut data: Vec<Vec<String>> = vec![];
+            .fetch(&mut self.connection)
+    }
+}
 
-        // for row in rows.await?.into_iter() {
-        //         data.push(row);
-        // }
+/// Domain is shown as value of its base type, which could be a domain too
+pub(super) fn domain_base_type(type_info: &PgTypeInfo) -> &PgTypeInfo {
+    match type_info.kind() {
+        PgTypeKind::Domain(base_type) => domain_base_type(base_type),
+        _ => type_info,
+    }
+}
 
-        while let Some(row) = rows.try_next().await? {
-            data.push(row);
+/// Writes value of type, which is decoded by this app rather than by sqlx,
+/// unsupported values are reported instead of failing the query,
+/// type is checked by caller, as column could have domain type
+fn write_decoded<K>(row: &PgRow, i: usize, data: &mut Vec<CellValue>)
+where
+    K: for<'r> Decode<'r, Postgres> + Into<CellValue>,
+{
+    match row.try_get_unchecked::<Option<K>, usize>(i) {
+        Ok(Some(value)) => data.push(value.into()),
+        Ok(None) => data.push(CellValue::Null),
+        Err(e) => {
+            tracing::debug!("Type not supported: {}", e);
+            data.push(CellValue::Unsupported(String::new()));
         }
-        drop(rows);
-
-        Ok((headers, data))
     }
 }
 
-fn do_for_known_types(decoder: &mut PgRecordDecoder,
-    field: &(String, PgTypeInfo),
-    result: &mut Vec<(String, String)>,
-    type_info: &PgTypeInfo) -> bool {
-    return do_for_type::<bool>(decoder, field, result, type_info)
-    || do_for_type::<String>(decoder, field, result, type_info)
-    || do_for_type::<i8>(decoder, field, result, type_info)
-    || do_for_type::<i16>(decoder, field, result, type_info)
-    || do_for_type::<i32>(decoder, field, result, type_info)
-    || do_for_type::<i64>(decoder, field, result, type_info)
-    || do_for_type::<f32>(decoder, field, result, type_info)
-    || do_for_type::<f64>(decoder, field, result, type_info)
-    || do_for_type::<BigDecimal>(decoder, field, result, type_info)
-    || do_for_type::<sqlx::types::time::PrimitiveDateTime>(
-        decoder,
-        field,
-        result,
-        type_info,
-    )
-    || do_for_type::<sqlx::types::time::OffsetDateTime>(
-        decoder,
-        field,
-        result,
-        type_info,
-    )
-    || do_for_type::<sqlx::types::time::Date>(decoder, field, result, type_info)
-    || do_for_type::<sqlx::types::time::Time>(decoder, field, result, type_info)
-    || do_for_type::<sqlx::types::uuid::Uuid>(decoder, field, result, type_info)
-    || do_for_type::<sqlx::types::ipnetwork::IpNetwork>(
-        decoder,
-        field,
-        result,
-        type_info,
-    )
-    || do_for_type::<sqlx::types::mac_address::MacAddress>(
-        decoder,
-        field,
-        result,
-        type_info,
-    )
-    || do_for_type::<sqlx::types::JsonValue>(decoder, field, result, type_info)
-    || do_for_type::<sqlx_postgres::types::PgTimeTz>(
-        decoder,
-        field,
-        result,
-        type_info,
-    )
-    || do_for_type::<sqlx::types::BitVec>(decoder, field, result, type_info)
-    || do_for_type::<Vec<u8>>(decoder, field, result, type_info)
-    || do_for_type::<()>(decoder, field, result, type_info);
+fn is_record(type_info: &PgTypeInfo) -> bool {
+    type_info.oid() == Some(RECORD_OID)
 }
 
-fn decode_and_write(
-    decoder: &mut PgRecordDecoder,
-    types: Arc<[(String, PgTypeInfo)]>,
-    data: &mut Vec<String>,
-) {
-    let mut result = vec![];
-    for (_, field) in types.iter().enumerate() {
-        let type_info = &field.1;
-        match type_info.kind()  {
-            PgTypeKind::Simple => {
-                if ! do_for_known_types(decoder, field, &mut result, type_info) {
-                    result.push((field.0.clone(), "not supported".to_string()));
-                }
-            },
-            _ => {
-                // TODO: support nested composites
-                result.push((field.0.clone(), "not supported".to_string()));
-            }
+/// Tells if value is made of other values, so it has to be decoded by `PgValueCell`
+fn is_compound(type_info: &PgTypeInfo) -> bool {
+    let type_info = domain_base_type(type_info);
+    match type_info.kind() {
+        PgTypeKind::Simple => is_record(type_info) || is_multirange(type_info),
+        PgTypeKind::Array(element) => {
+            !matches!(domain_base_type(element).kind(), PgTypeKind::Simple) || is_compound(element)
         }
+        PgTypeKind::Composite(_) | PgTypeKind::Range(_) => true,
+        PgTypeKind::Enum(_) | PgTypeKind::Pseudo | PgTypeKind::Domain(_) => false,
     }
+}
 
-    data.push(format!(
-        "({})",
-        result
-            .iter()
-            .map(|(name, value)| { format!("{}:{}", name, value) })
-            .join(",")
-    ));
+/// Value of any type, values which are made of other values, like composites,
+/// arrays and ranges, are decoded recursively, arrays are kept as arrays of cells
+/// and everything else is rendered as text
+struct PgValueCell(CellValue);
+
+impl Type<Postgres> for PgValueCell {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("anyelement")
+    }
+
+    fn compatible(_ty: &PgTypeInfo) -> bool {
+        true
+    }
 }
 
-fn do_for_type<K>(
-    decoder: &mut PgRecordDecoder,
-    field: &(String, PgTypeInfo),
-    result: &mut Vec<(String, String)>,
-    type_info: &PgTypeInfo,
-) -> bool
-where
-    K: sqlx::Type<sqlx_postgres::Postgres>,
-    K: for<'b> sqlx::Decode<'b, sqlx_postgres::Postgres>,
-    K: PgDisplayComposite,
-{
-    if let Some(value) = decode_for_type::<K>(decoder, type_info) {
-        write_for_type(value, field, result, type_info);
-        return true;
+impl Decode<'_, Postgres> for PgValueCell {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        // text is already printed by Postgres in readable format
+        if let PgValueFormat::Text = value.format() {
+            return Ok(Self(CellValue::Text(value.as_str()?.to_string())));
+        }
+        let type_info = value.type_info().into_owned();
+        let type_info = domain_base_type(&type_info);
+        let decoded = match type_info.kind() {
+            PgTypeKind::Simple if is_record(type_info) => {
+                decode_composite(value, &[]).map(CellValue::Text)
+            }
+            PgTypeKind::Simple if is_multirange(type_info) => {
+                PgMultirangeLiteral::decode(value).map(|value| CellValue::Text(value.to_string()))
+            }
+            PgTypeKind::Simple => decode_known_types(&value)
+                .unwrap_or_else(|| Err(format!("{} is not known", type_info.name()).into()))
+                .map(CellValue::Text),
+            PgTypeKind::Array(_) => decode_array(value),
+            PgTypeKind::Composite(fields) => decode_composite(value, fields).map(CellValue::Text),
+            PgTypeKind::Enum(_) => value
+                .as_str()
+                .map(|value| CellValue::Text(value.to_string())),
+            PgTypeKind::Range(_) => {
+                PgRangeLiteral::decode(value).map(|value| CellValue::Text(value.to_string()))
+            }
+            PgTypeKind::Pseudo | PgTypeKind::Domain(_) => {
+                Err(format!("{} is not known", type_info.name()).into())
+            }
+        };
+        // unsupported value should not break values around it
+        Ok(Self(decoded.unwrap_or_else(|e| {
+            tracing::debug!("Type not supported: {} {}", type_info.name(), e);
+            CellValue::Unsupported(String::new())
+        })))
     }
-    false
 }
 
-fn write_for_type<K>(
-    value: Result<K, BoxDynError>,
-    field: &(String, PgTypeInfo),
-    result: &mut Vec<(String, String)>,
-    type_info: &PgTypeInfo,
-) where
-    K: sqlx::Type<sqlx_postgres::Postgres> + PgDisplayComposite,
-{
-    match value {
-        Ok(value) => {
-            result.push((field.0.clone(), value.to_string()));
+impl From<PgValueCell> for CellValue {
+    fn from(value: PgValueCell) -> Self {
+        value.0
+    }
+}
+
+fn decode_array(value: PgValueRef<'_>) -> Result<CellValue, BoxDynError> {
+    let elements = Vec::<Option<PgValueCell>>::decode(value)?;
+    Ok(CellValue::Array(
+        elements.into_iter().map(CellValue::from).collect(),
+    ))
+}
+
+/// Composite is shown as row literal `(value,...)`, the same way as Postgres prints it
+fn decode_composite(
+    value: PgValueRef<'_>,
+    fields: &[(String, PgTypeInfo)],
+) -> Result<String, BoxDynError> {
+    let count: [u8; 4] = value
+        .as_bytes()?
+        .get(..4)
+        .ok_or("record is empty")?
+        .try_into()?;
+    let count = u32::from_be_bytes(count) as usize;
+    let mut decoder = PgRecordDecoder::new(value)?;
+
+    let mut result = String::from("(");
+    let mut broken = false;
+    for index in 0..count {
+        if index > 0 {
+            result.push(',');
         }
-        Err(e) => {
-            tracing::debug!("Composite type decoding issue: {} {}", type_info.name(), e);
-            result.push((field.0.clone(), "not supported".to_string()));
+        // after failure it is not known where the next field starts
+        if broken {
+            write_field(&mut result, "not supported");
+            continue;
+        }
+        match decoder.try_decode::<Option<PgValueCell>>() {
+            Ok(Some(field)) => write_field(&mut result, &field.0.to_string()),
+            // null is written as nothing at all
+            Ok(None) => {}
+            Err(e) => {
+                let name = fields.get(index).map_or("", |(name, _)| name.as_str());
+                tracing::debug!("Composite field not supported: {} {}", name, e);
+                broken = true;
+                write_field(&mut result, "not supported");
+            }
         }
     }
+    result.push(')');
+    Ok(result)
+}
+
+/// Fields are quoted the same way as in row literal printed by Postgres, f.e `("a,b",2)`
+fn write_field(result: &mut String, field: &str) {
+    let needs_quotes = field.is_empty()
+        || field
+            .chars()
+            .any(|c| matches!(c, '"' | '\\' | '(' | ')' | ',') || c.is_whitespace());
+    if !needs_quotes {
+        result.push_str(field);
+        return;
+    }
+    result.push('"');
+    for c in field.chars() {
+        if c == '"' || c == '\\' {
+            result.push(c);
+        }
+        result.push(c);
+    }
+    result.push('"');
+}
+
+fn decode_known_types(value: &PgValueRef<'_>) -> Option<Result<String, BoxDynError>> {
+    decode_for_type::<bool>(value)
+        .or_else(|| decode_for_type::<String>(value))
+        .or_else(|| decode_for_type::<i8>(value))
+        .or_else(|| decode_for_type::<i16>(value))
+        .or_else(|| decode_for_type::<i32>(value))
+        .or_else(|| decode_for_type::<i64>(value))
+        .or_else(|| decode_for_type::<f32>(value))
+        .or_else(|| decode_for_type::<f64>(value))
+        .or_else(|| decode_for_type::<BigDecimal>(value))
+        .or_else(|| decode_for_type::<sqlx::types::time::PrimitiveDateTime>(value))
+        .or_else(|| decode_for_type::<sqlx::types::time::OffsetDateTime>(value))
+        .or_else(|| decode_for_type::<sqlx::types::time::Date>(value))
+        .or_else(|| decode_for_type::<sqlx::types::time::Time>(value))
+        .or_else(|| decode_for_type::<sqlx::types::uuid::Uuid>(value))
+        .or_else(|| decode_for_type::<sqlx::types::ipnetwork::IpNetwork>(value))
+        .or_else(|| decode_for_type::<sqlx::types::mac_address::MacAddress>(value))
+        .or_else(|| decode_for_type::<sqlx::types::JsonValue>(value))
+        .or_else(|| decode_for_type::<sqlx_postgres::types::PgTimeTz>(value))
+        .or_else(|| decode_for_type::<sqlx::types::BitVec>(value))
+        .or_else(|| decode_for_type::<Vec<u8>>(value))
+        .or_else(|| decode_for_type::<()>(value))
 }
 
-fn decode_for_type<K>(
-    decoder: &mut PgRecordDecoder,
-    type_info: &PgTypeInfo,
-) -> Option<Result<K, BoxDynError>>
+fn decode_for_type<K>(value: &PgValueRef<'_>) -> Option<Result<String, BoxDynError>>
 where
     K: sqlx::Type<sqlx_postgres::Postgres>,
     K: for<'b> sqlx::Decode<'b, sqlx_postgres::Postgres>,
+    K: PgDisplayComposite,
 {
-    if <K as Type<sqlx_postgres::Postgres>>::compatible(type_info) {
-        Some(decoder.try_decode::<K>())
+    let type_info = value.type_info();
+    if <K as Type<sqlx_postgres::Postgres>>::compatible(domain_base_type(&type_info)) {
+        Some(K::decode(value.clone()).map(|value| value.to_string()))
     } else {
         None
     }
@@ -338,3 +575,18 @@ impl<T: Display + UseStdDisplay> PgDisplayComposite for T {
         format!("{}", self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_composite_fields_like_postgres() {
+        let mut result = String::new();
+        for field in ["a,b", "2", "", "say \"hi\"", "(1,2)", "back\\slash"] {
+            write_field(&mut result, field);
+            result.push(',');
+        }
+        assert_eq!(result, r#""a,b",2,"","say ""hi""","(1,2)","back\\slash","#);
+    }
+}
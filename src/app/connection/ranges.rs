@@ -0,0 +1,458 @@
+use std::fmt::{Display, Formatter};
+
+use sqlx::{
+    error::BoxDynError,
+    postgres::{types::Oid, PgTypeInfo, PgTypeKind, PgValueFormat, PgValueRef},
+    types::time::{Date, PrimitiveDateTime, Time},
+    Decode, Postgres, TypeInfo, ValueRef,
+};
+
+use super::posgres::domain_base_type;
+
+// Ranges are decoded here instead of using sqlx PgRange, as the latter
+// shows empty range as unbounded one and does not know about multiranges,
+// binary format is described in rangetypes.c and multirangetypes.c of Postgres
+
+const RANGE_EMPTY: u8 = 0x01;
+const RANGE_LB_INC: u8 = 0x02;
+const RANGE_UB_INC: u8 = 0x04;
+const RANGE_LB_INF: u8 = 0x08;
+const RANGE_UB_INF: u8 = 0x10;
+
+// Julian day of 2000-01-01, which is where Postgres counts dates from
+const POSTGRES_EPOCH_JULIAN_DAY: i64 = 2_451_545;
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+const NUMERIC_NEG: u16 = 0x4000;
+const NUMERIC_NAN: u16 = 0xC000;
+const NUMERIC_PINF: u16 = 0xD000;
+const NUMERIC_NINF: u16 = 0xF000;
+
+// sqlx does not know multiranges, so built-in ones are told by oid from pg_type.dat
+const INT4MULTIRANGE_OID: Oid = Oid(4451);
+const NUMMULTIRANGE_OID: Oid = Oid(4532);
+const TSMULTIRANGE_OID: Oid = Oid(4533);
+const TSTZMULTIRANGE_OID: Oid = Oid(4534);
+const DATEMULTIRANGE_OID: Oid = Oid(4535);
+const INT8MULTIRANGE_OID: Oid = Oid(4536);
+
+/// Range rendered the way Postgres prints it, f.e `[1,10)` or `empty`
+pub(super) struct PgRangeLiteral(String);
+
+/// Multirange rendered the way Postgres prints it, f.e `{[1,3),[5,7)}`
+pub(super) struct PgMultirangeLiteral(String);
+
+/// Types which could be inside of built-in ranges
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RangeElement {
+    Int4,
+    Int8,
+    Numeric,
+    Date,
+    Timestamp,
+    Timestamptz,
+}
+
+impl RangeElement {
+    fn of_range(element: &PgTypeInfo) -> Option<Self> {
+        let name = domain_base_type(element).name();
+        [
+            ("int4", RangeElement::Int4),
+            ("int8", RangeElement::Int8),
+            ("numeric", RangeElement::Numeric),
+            ("date", RangeElement::Date),
+            ("timestamp", RangeElement::Timestamp),
+            ("timestamptz", RangeElement::Timestamptz),
+        ]
+        .into_iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(name))
+        .map(|(_, element)| element)
+    }
+
+    fn of_multirange(multirange: &PgTypeInfo) -> Option<Self> {
+        match multirange.oid()? {
+            INT4MULTIRANGE_OID => Some(RangeElement::Int4),
+            INT8MULTIRANGE_OID => Some(RangeElement::Int8),
+            NUMMULTIRANGE_OID => Some(RangeElement::Numeric),
+            DATEMULTIRANGE_OID => Some(RangeElement::Date),
+            TSMULTIRANGE_OID => Some(RangeElement::Timestamp),
+            TSTZMULTIRANGE_OID => Some(RangeElement::Timestamptz),
+            _ => None,
+        }
+    }
+
+    fn decode(self, buf: &[u8]) -> Result<String, BoxDynError> {
+        Ok(match self {
+            RangeElement::Int4 => i32::from_be_bytes(buf.try_into()?).to_string(),
+            RangeElement::Int8 => i64::from_be_bytes(buf.try_into()?).to_string(),
+            RangeElement::Numeric => decode_numeric(buf)?,
+            RangeElement::Date => match i32::from_be_bytes(buf.try_into()?) {
+                i32::MAX => "infinity".to_string(),
+                i32::MIN => "-infinity".to_string(),
+                days => {
+                    let date = date_from_postgres(days.into())?;
+                    format!("{}{}", format_date(&date), era(&date))
+                }
+            },
+            RangeElement::Timestamp | RangeElement::Timestamptz => {
+                match i64::from_be_bytes(buf.try_into()?) {
+                    i64::MAX => "infinity".to_string(),
+                    i64::MIN => "-infinity".to_string(),
+                    micros => {
+                        let timestamp = timestamp_from_postgres(micros)?;
+                        // timestamptz is always sent in UTC
+                        let offset = match self {
+                            RangeElement::Timestamptz => "+00",
+                            _ => "",
+                        };
+                        format!(
+                            "{} {}{}{}",
+                            format_date(&timestamp.date()),
+                            format_time(&timestamp.time()),
+                            offset,
+                            era(&timestamp.date())
+                        )
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Years before the first one are counted back from it by Postgres, with `BC` at the end
+fn format_date(date: &Date) -> String {
+    let year = if date.year() > 0 {
+        date.year()
+    } else {
+        1 - date.year()
+    };
+    format!(
+        "{:04}-{:02}-{:02}",
+        year,
+        u8::from(date.month()),
+        date.day()
+    )
+}
+
+fn era(date: &Date) -> &'static str {
+    if date.year() > 0 {
+        ""
+    } else {
+        " BC"
+    }
+}
+
+/// Fraction of second is printed without trailing zeros, and not at all when it is zero
+fn format_time(time: &Time) -> String {
+    let mut result = format!(
+        "{:02}:{:02}:{:02}",
+        time.hour(),
+        time.minute(),
+        time.second()
+    );
+    if time.microsecond() > 0 {
+        let fraction = format!("{:06}", time.microsecond());
+        result.push('.');
+        result.push_str(fraction.trim_end_matches('0'));
+    }
+    result
+}
+
+fn date_from_postgres(days: i64) -> Result<Date, BoxDynError> {
+    let julian_day = i32::try_from(POSTGRES_EPOCH_JULIAN_DAY + days)?;
+    Ok(Date::from_julian_day(julian_day)?)
+}
+
+fn timestamp_from_postgres(micros: i64) -> Result<PrimitiveDateTime, BoxDynError> {
+    let date = date_from_postgres(micros.div_euclid(MICROS_PER_DAY))?;
+    let micros = micros.rem_euclid(MICROS_PER_DAY);
+    let seconds = micros / 1_000_000;
+    let time = Time::from_hms_micro(
+        (seconds / 3600) as u8,
+        (seconds / 60 % 60) as u8,
+        (seconds % 60) as u8,
+        (micros % 1_000_000) as u32,
+    )?;
+    Ok(PrimitiveDateTime::new(date, time))
+}
+
+/// Numeric is kept as base 10000 digits, where weight is the power of the first one
+fn decode_numeric(buf: &[u8]) -> Result<String, BoxDynError> {
+    let mut words = buf
+        .chunks_exact(2)
+        .map(|word| u16::from_be_bytes([word[0], word[1]]));
+    let mut header = || words.next().ok_or("numeric is too short");
+    let digits_count = header()? as usize;
+    let weight = header()? as i16 as i64;
+    let sign = header()?;
+    let scale = header()? as usize;
+    let digits: Vec<u16> = words.take(digits_count).collect();
+
+    match sign {
+        NUMERIC_NAN => return Ok("NaN".to_string()),
+        NUMERIC_PINF => return Ok("Infinity".to_string()),
+        NUMERIC_NINF => return Ok("-Infinity".to_string()),
+        _ => {}
+    }
+    let digit_at = |index: i64| {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| digits.get(index).copied())
+            .unwrap_or(0)
+    };
+
+    let mut result = String::new();
+    if sign == NUMERIC_NEG {
+        result.push('-');
+    }
+    if weight < 0 {
+        result.push('0');
+    } else {
+        result.push_str(&digit_at(0).to_string());
+        for index in 1..=weight {
+            result.push_str(&format!("{:04}", digit_at(index)));
+        }
+    }
+    if scale > 0 {
+        let mut fraction = String::new();
+        let mut index = weight + 1;
+        while fraction.len() < scale {
+            fraction.push_str(&format!("{:04}", digit_at(index)));
+            index += 1;
+        }
+        fraction.truncate(scale);
+        result.push('.');
+        result.push_str(&fraction);
+    }
+    Ok(result)
+}
+
+fn take_chunk<'b>(buf: &mut &'b [u8]) -> Result<&'b [u8], BoxDynError> {
+    if buf.len() < 4 {
+        return Err("unexpected end of range".into());
+    }
+    let (length, rest) = buf.split_at(4);
+    let length = i32::from_be_bytes(length.try_into()?);
+    let length = usize::try_from(length).map_err(|_| "range bound can not be null")?;
+    if rest.len() < length {
+        return Err("unexpected end of range".into());
+    }
+    let (chunk, rest) = rest.split_at(length);
+    *buf = rest;
+    Ok(chunk)
+}
+
+/// Bounds are quoted same as Postgres does it, when they have special characters
+fn write_bound(result: &mut String, bound: &str) {
+    let needs_quotes = bound.is_empty()
+        || bound
+            .chars()
+            .any(|c| matches!(c, '"' | '\\' | '(' | ')' | '[' | ']' | ',') || c.is_whitespace());
+    if !needs_quotes {
+        result.push_str(bound);
+        return;
+    }
+    result.push('"');
+    for c in bound.chars() {
+        if c == '"' || c == '\\' {
+            result.push(c);
+        }
+        result.push(c);
+    }
+    result.push('"');
+}
+
+fn decode_range(mut buf: &[u8], element: RangeElement) -> Result<String, BoxDynError> {
+    let (&flags, rest) = buf.split_first().ok_or("range is empty")?;
+    buf = rest;
+    if flags & RANGE_EMPTY != 0 {
+        return Ok("empty".to_string());
+    }
+
+    let mut result = String::new();
+    result.push(if flags & RANGE_LB_INC != 0 { '[' } else { '(' });
+    if flags & RANGE_LB_INF == 0 {
+        write_bound(&mut result, &element.decode(take_chunk(&mut buf)?)?);
+    }
+    result.push(',');
+    if flags & RANGE_UB_INF == 0 {
+        write_bound(&mut result, &element.decode(take_chunk(&mut buf)?)?);
+    }
+    result.push(if flags & RANGE_UB_INC != 0 { ']' } else { ')' });
+    Ok(result)
+}
+
+fn decode_multirange(mut buf: &[u8], element: RangeElement) -> Result<String, BoxDynError> {
+    if buf.len() < 4 {
+        return Err("multirange is empty".into());
+    }
+    let (count, rest) = buf.split_at(4);
+    buf = rest;
+    let count = u32::from_be_bytes(count.try_into()?);
+    let mut ranges = vec![];
+    for _ in 0..count {
+        ranges.push(decode_range(take_chunk(&mut buf)?, element)?);
+    }
+    Ok(format!("{{{}}}", ranges.join(",")))
+}
+
+/// Multiranges are not known to sqlx, so they look like simple types
+pub(super) fn is_multirange(ty: &PgTypeInfo) -> bool {
+    RangeElement::of_multirange(domain_base_type(ty)).is_some()
+}
+
+impl Decode<'_, Postgres> for PgRangeLiteral {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        // text is already printed by Postgres in the same format
+        if let PgValueFormat::Text = value.format() {
+            return Ok(Self(value.as_str()?.to_string()));
+        }
+        let type_info = value.type_info();
+        let type_info = domain_base_type(&type_info);
+        let element = match type_info.kind() {
+            PgTypeKind::Range(element) => RangeElement::of_range(element)
+                .ok_or_else(|| format!("range of {} is not supported", element.name()))?,
+            _ => return Err(format!("{} is not a range", type_info.name()).into()),
+        };
+        Ok(Self(decode_range(value.as_bytes()?, element)?))
+    }
+}
+
+impl Decode<'_, Postgres> for PgMultirangeLiteral {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        if let PgValueFormat::Text = value.format() {
+            return Ok(Self(value.as_str()?.to_string()));
+        }
+        let type_info = value.type_info();
+        let type_info = domain_base_type(&type_info);
+        let element = RangeElement::of_multirange(type_info)
+            .ok_or_else(|| format!("{} is not supported", type_info.name()))?;
+        Ok(Self(decode_multirange(value.as_bytes()?, element)?))
+    }
+}
+
+impl Display for PgRangeLiteral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Display for PgMultirangeLiteral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(bytes: &[u8]) -> Vec<u8> {
+        let mut result = (bytes.len() as i32).to_be_bytes().to_vec();
+        result.extend_from_slice(bytes);
+        result
+    }
+
+    fn range(flags: u8, bounds: &[&[u8]]) -> Vec<u8> {
+        let mut result = vec![flags];
+        for bound in bounds {
+            result.extend(chunk(bound));
+        }
+        result
+    }
+
+    fn numeric(weight: i16, sign: u16, scale: u16, digits: &[u16]) -> Vec<u8> {
+        let mut words = vec![digits.len() as u16, weight as u16, sign, scale];
+        words.extend_from_slice(digits);
+        words.iter().flat_map(|word| word.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn decodes_integer_ranges() {
+        let bytes = range(RANGE_LB_INC, &[&1i32.to_be_bytes(), &10i32.to_be_bytes()]);
+        assert_eq!(decode_range(&bytes, RangeElement::Int4).unwrap(), "[1,10)");
+        let bytes = range(RANGE_LB_INF, &[&10i64.to_be_bytes()]);
+        assert_eq!(decode_range(&bytes, RangeElement::Int8).unwrap(), "(,10)");
+        let bytes = range(RANGE_EMPTY, &[]);
+        assert_eq!(decode_range(&bytes, RangeElement::Int4).unwrap(), "empty");
+    }
+
+    #[test]
+    fn decodes_numeric_bounds() {
+        let lower = numeric(0, NUMERIC_NEG, 1, &[1, 5000]);
+        let upper = numeric(0, 0, 2, &[123, 4500]);
+        let bytes = range(RANGE_LB_INC | RANGE_UB_INC, &[&lower, &upper]);
+        assert_eq!(
+            decode_range(&bytes, RangeElement::Numeric).unwrap(),
+            "[-1.5,123.45]"
+        );
+        assert_eq!(decode_numeric(&numeric(1, 0, 0, &[1, 2])).unwrap(), "10002");
+        assert_eq!(decode_numeric(&numeric(-1, 0, 4, &[5])).unwrap(), "0.0005");
+    }
+
+    #[test]
+    fn prints_dates_like_postgres() {
+        // days since 2000-01-01
+        let bytes = range(
+            RANGE_LB_INC,
+            &[&8766i32.to_be_bytes(), &i32::MAX.to_be_bytes()],
+        );
+        assert_eq!(
+            decode_range(&bytes, RangeElement::Date).unwrap(),
+            "[2024-01-01,infinity)"
+        );
+        let bytes = range(RANGE_LB_INC | RANGE_UB_INF, &[&(-746117i32).to_be_bytes()]);
+        assert_eq!(
+            decode_range(&bytes, RangeElement::Date).unwrap(),
+            "[\"0044-03-15 BC\",)"
+        );
+    }
+
+    #[test]
+    fn prints_timestamps_like_postgres() {
+        // microseconds since 2000-01-01 00:00:00
+        let midnight = 8766i64 * MICROS_PER_DAY;
+        let afternoon = midnight + 45_000_500_000;
+        let bytes = range(
+            RANGE_LB_INC,
+            &[&midnight.to_be_bytes(), &afternoon.to_be_bytes()],
+        );
+        assert_eq!(
+            decode_range(&bytes, RangeElement::Timestamp).unwrap(),
+            "[\"2024-01-01 00:00:00\",\"2024-01-01 12:30:00.5\")"
+        );
+        assert_eq!(
+            decode_range(&bytes, RangeElement::Timestamptz).unwrap(),
+            "[\"2024-01-01 00:00:00+00\",\"2024-01-01 12:30:00.5+00\")"
+        );
+    }
+
+    #[test]
+    fn decodes_multiranges() {
+        let first = range(RANGE_LB_INC, &[&1i32.to_be_bytes(), &3i32.to_be_bytes()]);
+        let second = range(RANGE_LB_INC, &[&5i32.to_be_bytes(), &7i32.to_be_bytes()]);
+        let mut bytes = 2u32.to_be_bytes().to_vec();
+        bytes.extend(chunk(&first));
+        bytes.extend(chunk(&second));
+        assert_eq!(
+            decode_multirange(&bytes, RangeElement::Int4).unwrap(),
+            "{[1,3),[5,7)}"
+        );
+        assert_eq!(
+            decode_multirange(&0u32.to_be_bytes(), RangeElement::Int4).unwrap(),
+            "{}"
+        );
+    }
+
+    #[test]
+    fn tells_multiranges_by_oid() {
+        assert_eq!(
+            RangeElement::of_multirange(&PgTypeInfo::with_oid(TSTZMULTIRANGE_OID)),
+            Some(RangeElement::Timestamptz)
+        );
+        assert_eq!(
+            RangeElement::of_multirange(&PgTypeInfo::with_oid(Oid(2249))),
+            None
+        );
+    }
+}
@@ -0,0 +1,676 @@
+use std::{collections::HashMap, ops::Range};
+
+/// Flavour of SQL understood by the database, it decides how strings,
+/// quoted identifiers, comments and placeholders are found in the script
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub(crate) enum SqlDialect {
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+/// Splits script into separate statements by semicolons, ignoring those
+/// that are inside of strings, quoted identifiers, comments and dollar-quoted bodies.
+/// Meta-commands like `\copy` take the rest of their line, as in psql,
+/// and for MySQL `DELIMITER` changes what ends statements, as in mysql client.
+/// Statements that contain nothing but whitespace and comments are skipped.
+pub(crate) fn split_statements(script: &str, dialect: SqlDialect) -> Vec<String> {
+    let chars: Vec<char> = script.chars().collect();
+    statement_ranges(&chars, false, dialect)
+        .into_iter()
+        .map(|range| text_of(&chars, range))
+        .collect()
+}
+
+/// Finds statement around given character offset, here blank lines
+/// are treated as separators as well as semicolons
+pub(crate) fn statement_at(script: &str, offset: usize, dialect: SqlDialect) -> Option<String> {
+    let chars: Vec<char> = script.chars().collect();
+    // whitespace after semicolon belongs to the next statement, but cursor there means previous one
+    let ranges: Vec<Range<usize>> = statement_ranges(&chars, true, dialect)
+        .into_iter()
+        .map(|range| {
+            let blank = chars[range.clone()]
+                .iter()
+                .take_while(|c| c.is_whitespace())
+                .count();
+            range.start + blank..range.end
+        })
+        .collect();
+    ranges
+        .iter()
+        .find(|range| range.contains(&offset) || range.end == offset)
+        // cursor could be right after the semicolon, then previous statement is meant
+        .or_else(|| ranges.iter().rev().find(|range| range.end <= offset))
+        .map(|range| text_of(&chars, range.clone()))
+}
+
+/// Returns first words of statement in lower case, skipping comments before them
+pub(crate) fn leading_keywords(statement: &str, count: usize, dialect: SqlDialect) -> Vec<String> {
+    let chars: Vec<char> = statement.chars().collect();
+    let mut i = 0;
+    let mut words = vec![];
+    while i < chars.len() && words.len() < count {
+        match (chars[i], skipped_at(&chars, i, dialect)) {
+            (_, Some((Skipped::Comment, len))) => i += len,
+            (c, None) if c.is_alphabetic() || c == '_' => {
+                let word: String = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_')
+                    .collect();
+                i += word.chars().count();
+                words.push(word.to_lowercase());
+            }
+            (c, None) if c.is_whitespace() => i += 1,
+            // anything else ends the keywords
+            _ => break,
+        }
+    }
+    words
+}
+
+/// Tells if statement has given keyword outside of parentheses,
+/// strings, quoted identifiers and comments
+pub(crate) fn has_keyword(statement: &str, keyword: &str, dialect: SqlDialect) -> bool {
+    let chars: Vec<char> = statement.chars().collect();
+    let mut i = 0;
+    let mut depth = 0;
+    while i < chars.len() {
+        match (chars[i], skipped_at(&chars, i, dialect)) {
+            (_, Some((_, len))) => i += len,
+            (c, None) if c.is_alphanumeric() || c == '_' || c == '$' => {
+                let word: String = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_alphanumeric() || **c == '_' || **c == '$')
+                    .collect();
+                i += word.chars().count();
+                if depth == 0 && word.eq_ignore_ascii_case(keyword) {
+                    return true;
+                }
+            }
+            (c, None) => {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                i += 1;
+            }
+        }
+    }
+    false
+}
+
+/// Returns names of placeholders like `$1` or `:name` used in the script,
+/// each name is listed once in order of the first appearance.
+/// For MySQL `?` placeholders are named `?1`, `?2` and so on by their position in the statement.
+pub(crate) fn placeholder_names(script: &str, dialect: SqlDialect) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    for statement in split_statements(script, dialect) {
+        let chars: Vec<char> = statement.chars().collect();
+        for (_, name) in placeholders(&chars, dialect) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// Replaces placeholders in statement with positional parameters `$1`, `$2` and so on,
+/// adding casts from given types. Returns new statement and names of placeholders
+/// in order of their positions, statement without placeholders is returned as is.
+/// When `positional` is set, every placeholder becomes `?` and gets its own position,
+/// so names are repeated as many times as they appear.
+pub(crate) fn number_placeholders(
+    statement: &str,
+    casts: &HashMap<String, String>,
+    positional: bool,
+    dialect: SqlDialect,
+) -> (String, Vec<String>) {
+    let chars: Vec<char> = statement.chars().collect();
+    let mut names: Vec<String> = vec![];
+    let mut numbered = String::new();
+    let mut copied = 0;
+    for (range, name) in placeholders(&chars, dialect) {
+        let parameter = if positional {
+            names.push(name.clone());
+            "?".to_string()
+        } else {
+            let position = match names.iter().position(|known| *known == name) {
+                Some(position) => position + 1,
+                None => {
+                    names.push(name.clone());
+                    names.len()
+                }
+            };
+            format!("${}", position)
+        };
+        numbered.extend(&chars[copied..range.start]);
+        // standard cast is used, as `::` is only understood by Postgres
+        match casts.get(&name) {
+            Some(cast) => numbered.push_str(&format!("CAST({} AS {})", parameter, cast)),
+            None => numbered.push_str(&parameter),
+        }
+        copied = range.end;
+    }
+    numbered.extend(&chars[copied..]);
+    (numbered, names)
+}
+
+fn placeholders(chars: &[char], dialect: SqlDialect) -> Vec<(Range<usize>, String)> {
+    let mut found = vec![];
+    let mut question_marks = 0;
+    // inside of brackets colon separates bounds of array slice, like in `a[:n]`
+    let mut brackets = 0usize;
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some((_, len)) = skipped_at(chars, i, dialect) {
+            i = (i + len).min(chars.len());
+            continue;
+        }
+        let next = chars.get(i + 1).copied();
+        let consumed = match chars[i] {
+            // `::` is a cast, not a placeholder, and neither is `:` right after it
+            ':' if next == Some(':') => {
+                2 + chars[i + 2..].iter().take_while(|c| **c == ':').count()
+            }
+            '[' if dialect == SqlDialect::Postgres => {
+                brackets += 1;
+                1
+            }
+            ']' if dialect == SqlDialect::Postgres => {
+                brackets = brackets.saturating_sub(1);
+                1
+            }
+            ':' if brackets > 0 => 1,
+            '?' if dialect == SqlDialect::MySql => {
+                question_marks += 1;
+                found.push((i..i + 1, format!("?{}", question_marks)));
+                1
+            }
+            '$' | ':' if !is_word(chars, i) => match placeholder_len(chars, i) {
+                Some(len) => {
+                    found.push((i..i + len, chars[i..i + len].iter().collect()));
+                    len
+                }
+                None => 1,
+            },
+            _ => 1,
+        };
+        i = (i + consumed).min(chars.len());
+    }
+    found
+}
+
+fn placeholder_len(chars: &[char], start: usize) -> Option<usize> {
+    let name_len = if chars[start] == '$' {
+        chars[start + 1..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count()
+    } else {
+        match chars.get(start + 1) {
+            Some(c) if c.is_alphabetic() || *c == '_' => chars[start + 1..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric() || **c == '_')
+                .count(),
+            _ => 0,
+        }
+    };
+    if name_len == 0 {
+        None
+    } else {
+        Some(name_len + 1)
+    }
+}
+
+fn text_of(chars: &[char], range: Range<usize>) -> String {
+    chars[range].iter().collect::<String>().trim().to_string()
+}
+
+fn statement_ranges(chars: &[char], blank_lines: bool, dialect: SqlDialect) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut start = 0;
+    let mut has_code = false;
+    let mut delimiter = vec![';'];
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i..].starts_with(&delimiter) {
+            if has_code {
+                ranges.push(start..i);
+            }
+            has_code = false;
+            i += delimiter.len();
+            start = i;
+            continue;
+        }
+        if let Some((skipped, len)) = skipped_at(chars, i, dialect) {
+            // line break after line comment is left for blank lines check
+            if skipped == Skipped::Quoted {
+                has_code = true;
+            }
+            i = (i + len).min(chars.len());
+            continue;
+        }
+        if dialect == SqlDialect::MySql && !has_code {
+            if let Some((new_delimiter, end)) = delimiter_command(chars, i) {
+                delimiter = new_delimiter;
+                i = end;
+                start = i;
+                continue;
+            }
+        }
+        match chars[i] {
+            '\n' if blank_lines && is_blank_line_after(chars, i) => {
+                if has_code {
+                    ranges.push(start..i);
+                }
+                has_code = false;
+                start = i + 1;
+            }
+            '\\' if dialect == SqlDialect::Postgres && !has_code => {
+                // comments before meta-command are left out of it
+                let end = find_from(chars, i, "\n").unwrap_or(chars.len());
+                ranges.push(i..end);
+                i = end;
+                start = i;
+                continue;
+            }
+            c if !c.is_whitespace() => has_code = true,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if has_code {
+        ranges.push(start..chars.len());
+    }
+    ranges
+}
+
+/// Part of the script taken as a whole, so that nothing inside of it
+/// is mistaken for end of statement or placeholder
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Skipped {
+    Comment,
+    // strings, quoted identifiers and dollar-quoted bodies
+    Quoted,
+}
+
+/// Returns what is skipped at given position and its length
+fn skipped_at(chars: &[char], start: usize, dialect: SqlDialect) -> Option<(Skipped, usize)> {
+    let next = chars.get(start + 1).copied();
+    let skipped = match (dialect, chars[start]) {
+        // in MySQL `--` starts comment only when followed by whitespace
+        (SqlDialect::MySql, '-')
+            if next == Some('-') && chars.get(start + 2).map_or(true, |c| c.is_whitespace()) =>
+        {
+            (Skipped::Comment, line_comment_len(chars, start))
+        }
+        (SqlDialect::Postgres | SqlDialect::Sqlite, '-') if next == Some('-') => {
+            (Skipped::Comment, line_comment_len(chars, start))
+        }
+        (SqlDialect::MySql, '#') => (Skipped::Comment, line_comment_len(chars, start)),
+        // block comments could be nested only in Postgres
+        (_, '/') if next == Some('*') => (
+            Skipped::Comment,
+            block_comment_len(chars, start, dialect == SqlDialect::Postgres),
+        ),
+        (SqlDialect::Postgres, '\'') => {
+            // E'...' strings allow escaping quotes with backslash
+            let escapes =
+                start > 0 && matches!(chars[start - 1], 'e' | 'E') && !is_word(chars, start - 1);
+            (Skipped::Quoted, quoted_len(chars, start, '\'', escapes))
+        }
+        (SqlDialect::MySql, quote @ ('\'' | '"')) => {
+            (Skipped::Quoted, quoted_len(chars, start, quote, true))
+        }
+        (_, quote @ ('\'' | '"')) => (Skipped::Quoted, quoted_len(chars, start, quote, false)),
+        (SqlDialect::MySql | SqlDialect::Sqlite, '`') => {
+            (Skipped::Quoted, quoted_len(chars, start, '`', false))
+        }
+        (SqlDialect::Sqlite, '[') => (
+            Skipped::Quoted,
+            find_from(chars, start, "]").map_or(chars.len(), |end| end + 1) - start,
+        ),
+        (SqlDialect::Postgres, '$') => {
+            let tag = dollar_tag(chars, start)?;
+            let body_start = start + tag.len();
+            let end = find_from(chars, body_start, &tag).map_or(chars.len(), |end| end + tag.len());
+            (Skipped::Quoted, end - start)
+        }
+        _ => return None,
+    };
+    Some(skipped)
+}
+
+/// Reads `DELIMITER //` line of MySQL script, returns new delimiter and end of the line
+fn delimiter_command(chars: &[char], start: usize) -> Option<(Vec<char>, usize)> {
+    const COMMAND: &str = "delimiter";
+    let command_end = start + COMMAND.len();
+    if is_word(chars, start)
+        || chars.len() <= command_end
+        || !matches!(chars[command_end], ' ' | '\t')
+    {
+        return None;
+    }
+    let command: String = chars[start..command_end].iter().collect();
+    if !command.eq_ignore_ascii_case(COMMAND) {
+        return None;
+    }
+    let end = find_from(chars, start, "\n").unwrap_or(chars.len());
+    let delimiter: Vec<char> = chars[command_end..end]
+        .iter()
+        .skip_while(|c| c.is_whitespace())
+        .take_while(|c| !c.is_whitespace())
+        .copied()
+        .collect();
+    if delimiter.is_empty() {
+        return None;
+    }
+    Some((delimiter, end))
+}
+
+fn is_blank_line_after(chars: &[char], line_break: usize) -> bool {
+    chars[line_break + 1..]
+        .iter()
+        .take_while(|c| **c != '\n')
+        .all(|c| c.is_whitespace())
+}
+
+// tells if character before given position continues some word,
+// so that f.e `type'...'` is not mistaken for E'...' string
+fn is_word(chars: &[char], position: usize) -> bool {
+    position > 0 && (chars[position - 1].is_alphanumeric() || chars[position - 1] == '_')
+}
+
+fn find_from(chars: &[char], start: usize, pattern: &str) -> Option<usize> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    if start >= chars.len() {
+        return None;
+    }
+    chars[start..]
+        .windows(pattern.len())
+        .position(|window| window == pattern.as_slice())
+        .map(|position| start + position)
+}
+
+fn line_comment_len(chars: &[char], start: usize) -> usize {
+    find_from(chars, start, "\n").unwrap_or(chars.len()) - start
+}
+
+fn block_comment_len(chars: &[char], start: usize, nested: bool) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i < chars.len() {
+        match (chars[i], chars.get(i + 1)) {
+            ('/', Some('*')) if nested || depth == 0 => {
+                depth += 1;
+                i += 2;
+            }
+            ('*', Some('/')) => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return i - start;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    chars.len() - start
+}
+
+fn quoted_len(chars: &[char], start: usize, quote: char, escapes: bool) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() {
+        if escapes && chars[i] == '\\' {
+            i += 2;
+            continue;
+        }
+        if chars[i] == quote {
+            // doubled quote is an escaped quote
+            if chars.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1 - start;
+        }
+        i += 1;
+    }
+    chars.len() - start
+}
+
+/// Returns tag like `$body$` or `$$` if dollar quote starts at given position
+fn dollar_tag(chars: &[char], start: usize) -> Option<String> {
+    // $1 is a parameter and a$b is an identifier, not a quote
+    if is_word(chars, start) {
+        return None;
+    }
+    let mut i = start + 1;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '$' {
+            return Some(chars[start..=i].iter().collect());
+        }
+        let allowed = c == '_' || c.is_alphabetic() || (i > start + 1 && c.is_ascii_digit());
+        if !allowed {
+            return None;
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use SqlDialect::{MySql, Postgres, Sqlite};
+
+    #[test]
+    fn postgres_statements_are_split_by_semicolons_outside_of_quotes() {
+        let script = "select 'a;b', \"c;d\" from t; -- comment;\n\
+            select $body$ x; $body$; /* a /* nested; */ still comment; */ select E'\\';'";
+        assert_eq!(
+            split_statements(script, Postgres),
+            vec![
+                "select 'a;b', \"c;d\" from t",
+                "-- comment;\nselect $body$ x; $body$",
+                "/* a /* nested; */ still comment; */ select E'\\';'",
+            ]
+        );
+    }
+
+    #[test]
+    fn postgres_meta_command_takes_the_rest_of_line() {
+        assert_eq!(
+            split_statements("\\copy t to 'f;g'\nselect 1;", Postgres),
+            vec!["\\copy t to 'f;g'", "select 1"]
+        );
+    }
+
+    #[test]
+    fn mysql_strings_allow_backslash_escapes() {
+        assert_eq!(
+            split_statements("select 'It\\'s; x'; select \"a\\\"; b\"", MySql),
+            vec!["select 'It\\'s; x'", "select \"a\\\"; b\""]
+        );
+        // the same string is not escaped in Postgres
+        assert_eq!(
+            split_statements("select 'It\\'s; x'", Postgres),
+            vec!["select 'It\\'s", "x'"]
+        );
+    }
+
+    #[test]
+    fn mysql_comments_are_skipped() {
+        assert_eq!(
+            split_statements(
+                "# hash; comment\nselect 1; -- dash; comment\nselect 2",
+                MySql
+            ),
+            vec!["# hash; comment\nselect 1", "-- dash; comment\nselect 2"]
+        );
+        // without whitespace after dashes it is subtraction of negative number
+        assert_eq!(
+            split_statements("select 1--1; select 2", MySql),
+            vec!["select 1--1", "select 2"]
+        );
+        // block comments are not nested
+        assert_eq!(
+            split_statements("/* a /* b */ select 1; c */", MySql),
+            vec!["/* a /* b */ select 1", "c */"]
+        );
+    }
+
+    #[test]
+    fn backtick_identifiers_are_quoted() {
+        assert_eq!(
+            split_statements("select `a;b` from t; select 2", MySql),
+            vec!["select `a;b` from t", "select 2"]
+        );
+        assert_eq!(
+            split_statements("select `a;b`, [c;d] from t", Sqlite),
+            vec!["select `a;b`, [c;d] from t"]
+        );
+    }
+
+    #[test]
+    fn mysql_delimiter_changes_end_of_statement() {
+        let script = "DELIMITER //\n\
+            create procedure p() begin select 1; select 2; end//\n\
+            delimiter ;\n\
+            call p();";
+        assert_eq!(
+            split_statements(script, MySql),
+            vec![
+                "create procedure p() begin select 1; select 2; end",
+                "call p()",
+            ]
+        );
+    }
+
+    #[test]
+    fn dollar_quotes_are_only_known_to_postgres() {
+        assert_eq!(
+            split_statements("select $$a;b$$", Postgres),
+            vec!["select $$a;b$$"]
+        );
+        assert_eq!(
+            split_statements("select $$a;b$$", MySql),
+            vec!["select $$a", "b$$"]
+        );
+    }
+
+    #[test]
+    fn mysql_question_marks_are_placeholders() {
+        assert_eq!(
+            placeholder_names("select ?, '?', `?` from t where a = ? and b = :b", MySql),
+            vec!["?1", "?2", ":b"]
+        );
+        // every statement has its own positions
+        assert_eq!(placeholder_names("select ?; select ?", MySql), vec!["?1"]);
+        // in Postgres it is an operator
+        assert!(placeholder_names("select '{}'::jsonb ? 'a'", Postgres).is_empty());
+        assert_eq!(
+            number_placeholders("select ? + ?", &HashMap::new(), true, MySql),
+            (
+                "select ? + ?".to_string(),
+                vec!["?1".to_string(), "?2".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn placeholders_in_quotes_and_comments_are_ignored() {
+        assert_eq!(
+            placeholder_names(
+                "select :a, ':b', $1 -- :c\n/* $2 */ from t where x = $$ :d $$ and y::int = :a",
+                Postgres
+            ),
+            vec![":a", "$1"]
+        );
+        assert_eq!(
+            placeholder_names("select \"b:c\", $q$ :d $q$, E'\\' :e', :f", Postgres),
+            vec![":f"]
+        );
+        assert_eq!(
+            placeholder_names("select :a # :b\nfrom t", MySql),
+            vec![":a"]
+        );
+    }
+
+    #[test]
+    fn leading_keywords_skip_dialect_comments() {
+        assert_eq!(
+            leading_keywords("# note\n/* x */ START transaction", 2, MySql),
+            vec!["start", "transaction"]
+        );
+        assert_eq!(
+            leading_keywords("-- note\nBegin", 2, Postgres),
+            vec!["begin"]
+        );
+    }
+
+    #[test]
+    fn keyword_is_found_outside_of_parentheses_and_quotes() {
+        assert!(has_keyword("SELECT a INTO t FROM s", "into", Postgres));
+        assert!(!has_keyword(
+            "select 'into', \"into\", into_x, $into$ into $into$ -- into\nfrom t",
+            "into",
+            Postgres
+        ));
+        assert!(!has_keyword(
+            "select * from t where a in (select b into c from d)",
+            "into",
+            Postgres
+        ));
+    }
+
+    #[test]
+    fn colons_of_casts_and_slices_are_not_placeholders() {
+        assert_eq!(
+            placeholder_names(
+                "select a[:n], a[1:n], a[:n][m:], (b::int[])[:$1], :n::int, x:::y from t",
+                Postgres
+            ),
+            vec!["$1", ":n"]
+        );
+        assert_eq!(
+            placeholder_names("select a[ :n ], f(x := :v) from t", Postgres),
+            vec![":v"]
+        );
+        // brackets are identifier quotes in SQLite
+        assert_eq!(
+            placeholder_names("select [a:b], :n from t", Sqlite),
+            vec![":n"]
+        );
+    }
+
+    #[test]
+    fn statement_is_found_around_offset() {
+        let script = "select 1;\nselect ';'\n\nselect $$\n\n$$";
+        // right after the semicolon previous statement is meant
+        assert_eq!(
+            statement_at(script, 9, Postgres).as_deref(),
+            Some("select 1")
+        );
+        assert_eq!(
+            statement_at(script, 0, Postgres).as_deref(),
+            Some("select 1")
+        );
+        assert_eq!(
+            statement_at(script, 15, Postgres).as_deref(),
+            Some("select ';'")
+        );
+        // blank lines inside of dollar quotes do not separate statements
+        assert_eq!(
+            statement_at(script, script.chars().count(), Postgres).as_deref(),
+            Some("select $$\n\n$$")
+        );
+        assert_eq!(statement_at("", 0, Postgres), None);
+    }
+}
@@ -0,0 +1,268 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use sqlx::{
+    sqlite::{SqliteArguments, SqliteConnectOptions, SqliteRow, SqliteTypeInfo},
+    Arguments, Column, Connection, Executor, Row, Sqlite, SqliteConnection, TypeInfo, ValueRef,
+};
+
+use super::{
+    cells::{CellValue, ColumnInfo},
+    driver::{DatabaseDriver, DriverCapabilities, FetchedRow, ObjectLevel},
+    splitting::SqlDialect,
+    types::CommonTypeWriter,
+};
+
+// types: https://docs.rs/sqlx-sqlite/0.7.2/sqlx_sqlite/types/index.html
+
+struct SqliteWriter {}
+
+impl CommonTypeWriter<'_, SqliteTypeInfo, SqliteRow, Sqlite> for SqliteWriter {}
+
+// attached databases, like `main` and `temp`, serve as schemas
+pub(super) static CAPABILITIES: DriverCapabilities = DriverCapabilities {
+    levels: &[
+        ObjectLevel::Database,
+        ObjectLevel::Schema,
+        ObjectLevel::Table,
+        ObjectLevel::Column,
+    ],
+    management_database: "main",
+    query_plan: false,
+    cancel_query: false,
+    session_timeouts: false,
+    idle_transaction_timeout: false,
+    positional_placeholders: false,
+    dialect: SqlDialect::Sqlite,
+    notifications: false,
+    copy: false,
+    cursors: false,
+};
+
+pub(super) struct SqliteDriver {
+    connection: SqliteConnection,
+    url: String,
+}
+
+impl SqliteDriver {
+    /// The file itself is the only database
+    fn file_name(&self) -> String {
+        let path = self.url.trim_start_matches("sqlite:");
+        let path = path.split('?').next().unwrap_or_default();
+        Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "main".to_string())
+    }
+}
+
+#[async_trait]
+impl DatabaseDriver for SqliteDriver {
+    async fn connect(_name: &str, url: &str) -> Result<Self, sqlx::Error> {
+        // the whole file is one database, so name is not needed to connect
+        let opts: SqliteConnectOptions = url.parse()?;
+        tracing::info!("Opening database file: {}", url);
+        let connection = SqliteConnection::connect_with(&opts).await?;
+        Ok(Self {
+            connection,
+            url: url.to_string(),
+        })
+    }
+
+    fn capabilities(&self) -> &'static DriverCapabilities {
+        &CAPABILITIES
+    }
+
+    async fn execute_command(&mut self, command: &str) -> Result<(), sqlx::Error> {
+        self.connection.execute(command).await?;
+        Ok(())
+    }
+
+    async fn list_objects(
+        &mut self,
+        level: ObjectLevel,
+        path: &[String],
+    ) -> Result<Vec<String>, sqlx::Error> {
+        let mut args = SqliteArguments::default();
+        let query = match (level, path) {
+            (ObjectLevel::Database, _) => return Ok(vec![self.file_name()]),
+            (ObjectLevel::Schema, _) => "SELECT name FROM pragma_database_list;".to_string(),
+            (ObjectLevel::Table, [_, schema]) => format!(
+                "SELECT name FROM \"{}\".sqlite_master WHERE type IN ('table', 'view') ORDER BY name;",
+                schema.replace('"', "\"\"")
+            ),
+            (ObjectLevel::Column, [_, schema, table]) => {
+                args.add(table);
+                args.add(schema);
+                "SELECT name FROM pragma_table_info($1, $2) ORDER BY cid;".to_string()
+            }
+            _ => return Ok(vec![]),
+        };
+        let rows = self
+            .connection
+            .fetch_all(sqlx::query_with(&query, args))
+            .await?;
+        Ok(rows.iter().map(|row| row.get::<String, usize>(0)).collect())
+    }
+
+    async fn close(self: Box<Self>) -> Result<(), sqlx::Error> {
+        self.connection.close().await
+    }
+
+    fn fetch<'e>(
+        &'e mut self,
+        query: &'e str,
+        parameters: Vec<Option<String>>,
+    ) -> BoxStream<'e, Result<FetchedRow, sqlx::Error>> {
+        let mut columns_sent = false;
+
+        let mut args = SqliteArguments::default();
+        for parameter in parameters {
+            args.add(parameter);
+        }
+
+        sqlx::query_with(query, args)
+            .persistent(false)
+            .map(move |row: SqliteRow| {
+                let mut data: Vec<CellValue> = vec![];
+                let columns = if columns_sent {
+                    None
+                } else {
+                    columns_sent = true;
+                    Some(
+                        row.columns()
+                            .iter()
+                            .map(|col| ColumnInfo {
+                                name: col.name().to_string(),
+                                type_name: col.type_info().name().to_string(),
+                            })
+                            .collect(),
+                    )
+                };
+                for i in 0..row.len() {
+                    write_cell(&row, i, &mut data);
+                }
+
+                FetchedRow { columns, data }
+            })
+            .fetch(&mut self.connection)
+    }
+}
+
+/// Column in SQLite could hold values of any type, so the value is written
+/// according to its own type, declared type only tells if integer is boolean
+fn write_cell(row: &SqliteRow, i: usize, data: &mut Vec<CellValue>) {
+    let type_info = match row.try_get_raw(i) {
+        Ok(value) if value.is_null() => {
+            data.push(CellValue::Null);
+            return;
+        }
+        Ok(value) => value.type_info().into_owned(),
+        Err(e) => {
+            tracing::debug!("Error getting value: {}", e);
+            data.push(CellValue::Unsupported(String::new()));
+            return;
+        }
+    };
+    if row.column(i).type_info().name() == "BOOLEAN"
+        && SqliteWriter::write_typed::<bool>(&type_info, row, i, data)
+    {
+        return;
+    }
+    SqliteWriter::write_row_cell(&type_info, row, i, data);
+}
+
+#[cfg(test)]
+mod tests {
+    use async_std::task;
+    use futures::TryStreamExt;
+    use uuid::Uuid;
+
+    use super::*;
+
+    /// Runs test with connection to new database file, which is removed afterwards
+    fn with_database(test: impl FnOnce(&mut SqliteDriver, &str)) {
+        let file_name = format!("tisq-{}.sqlite", Uuid::new_v4());
+        let path = std::env::temp_dir().join(&file_name);
+        let url = format!("sqlite:{}?mode=rwc", path.display());
+        let mut driver = task::block_on(SqliteDriver::connect("", &url)).unwrap();
+        test(&mut driver, &file_name);
+        task::block_on(Box::new(driver).close()).unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn lists_objects_of_database_file() {
+        with_database(|driver, file_name| {
+            task::block_on(async {
+                driver
+                    .execute_command("CREATE TABLE items (id INTEGER, name TEXT);")
+                    .await
+                    .unwrap();
+                let database = file_name.to_string();
+                let path = [database.clone(), "main".to_string()];
+                assert_eq!(
+                    driver
+                        .list_objects(ObjectLevel::Database, &[])
+                        .await
+                        .unwrap(),
+                    [file_name]
+                );
+                assert_eq!(
+                    driver
+                        .list_objects(ObjectLevel::Schema, &path[..1])
+                        .await
+                        .unwrap(),
+                    ["main"]
+                );
+                assert_eq!(
+                    driver
+                        .list_objects(ObjectLevel::Table, &path)
+                        .await
+                        .unwrap(),
+                    ["items"]
+                );
+                let path = [database, "main".to_string(), "items".to_string()];
+                assert_eq!(
+                    driver
+                        .list_objects(ObjectLevel::Column, &path)
+                        .await
+                        .unwrap(),
+                    ["id", "name"]
+                );
+            })
+        });
+    }
+
+    #[test]
+    fn fetches_rows_with_columns() {
+        with_database(|driver, _| {
+            let rows: Vec<FetchedRow> = task::block_on(
+                driver
+                    .fetch("SELECT 1 AS one, $1 AS two;", vec![Some("x".to_string())])
+                    .try_collect(),
+            )
+            .unwrap();
+            let columns: Vec<String> = rows[0]
+                .columns
+                .iter()
+                .flatten()
+                .map(|column| column.name.clone())
+                .collect();
+            assert_eq!(columns, ["one", "two"]);
+            assert_eq!(
+                rows[0].data,
+                [CellValue::Int(1), CellValue::Text("x".to_string())]
+            );
+        });
+    }
+
+    #[test]
+    fn running_query_could_not_be_cancelled() {
+        with_database(|driver, _| {
+            assert!(!driver.capabilities().cancel_query);
+            assert_eq!(driver.backend_pid(), None);
+        });
+    }
+}
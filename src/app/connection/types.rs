@@ -1,7 +1,11 @@
-use std::fmt::{Debug, Display};
+use std::fmt::Debug;
 
 use sqlx::{types::BigDecimal, ColumnIndex, Row, Type, TypeInfo};
 
+use super::cells::CellValue;
+
+/// Writer for Postgres arrays, given type info is of the array element,
+/// which could be the base type of domain, so column type is not checked again
 pub(super) trait GenericArrayTypeWriter<'a, T, R, D>
 where
     R: Row<Database = D>,
@@ -54,74 +58,72 @@ where
 
     Vec<sqlx_postgres::types::PgTimeTz>: sqlx::Type<D> + sqlx::Decode<'a, D>,
 {
-    fn write_row_cell(type_info: &T, row: &'a R, i: usize, data: &mut Vec<String>) {
-        if Self::write_via_display::<bool>(type_info, row, i, data) {
+    fn write_row_cell(type_info: &T, row: &'a R, i: usize, data: &mut Vec<CellValue>) {
+        if Self::write_typed::<bool>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<String>(type_info, row, i, data) {
+        if Self::write_typed::<String>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<i64>(type_info, row, i, data) {
+        if Self::write_typed::<i64>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<i32>(type_info, row, i, data) {
+        if Self::write_typed::<i32>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<i16>(type_info, row, i, data) {
+        if Self::write_typed::<i16>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<i8>(type_info, row, i, data) {
+        if Self::write_typed::<i8>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<f32>(type_info, row, i, data) {
+        if Self::write_typed::<f32>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<f64>(type_info, row, i, data) {
+        if Self::write_typed::<f64>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<BigDecimal>(type_info, row, i, data) {
+        if Self::write_typed::<BigDecimal>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_debug::<Vec<u8>>(type_info, row, i, data) {
+        if Self::write_typed::<Vec<u8>>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::time::PrimitiveDateTime>(type_info, row, i, data)
-        {
+        if Self::write_typed::<sqlx::types::time::PrimitiveDateTime>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::time::OffsetDateTime>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::time::OffsetDateTime>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::time::Date>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::time::Date>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::time::Time>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::time::Time>(type_info, row, i, data) {
             return;
         }
         if Self::write_via_custom_display::<sqlx_postgres::types::PgTimeTz>(type_info, row, i, data)
         {
             return;
         }
-        if Self::write_via_display::<sqlx::types::uuid::Uuid>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::uuid::Uuid>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::ipnetwork::IpNetwork>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::ipnetwork::IpNetwork>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::mac_address::MacAddress>(type_info, row, i, data)
-        {
+        if Self::write_typed::<sqlx::types::mac_address::MacAddress>(type_info, row, i, data) {
             return;
         }
         if Self::write_via_debug::<sqlx::types::BitVec>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::JsonValue>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::JsonValue>(type_info, row, i, data) {
             return;
         }
-        data.push("not supported".to_string());
+        data.push(CellValue::Unsupported(String::new()));
     }
 
-    fn write_via_debug<K>(type_info: &T, row: &'a R, i: usize, data: &mut Vec<String>) -> bool
+    fn write_via_debug<K>(type_info: &T, row: &'a R, i: usize, data: &mut Vec<CellValue>) -> bool
     where
         K: sqlx::Type<D>,
         Vec<K>: sqlx::Type<D>,
@@ -129,11 +131,8 @@ where
         K: Debug,
     {
         if <K as Type<D>>::compatible(type_info) {
-            let val: Option<Vec<K>> = row.get::<Option<Vec<K>>, usize>(i);
-            let val = val
-                .map(|val| format!("{:?}", val))
-                .unwrap_or_else(|| "null".to_string());
-            data.push(val);
+            let val: Option<Vec<K>> = row.get_unchecked::<Option<Vec<K>>, usize>(i);
+            data.push(val.map_or(CellValue::Null, |val| CellValue::Text(format!("{:?}", val))));
             return true;
         }
         false
@@ -143,7 +142,7 @@ where
         type_info: &T,
         row: &'a R,
         i: usize,
-        data: &mut Vec<String>,
+        data: &mut Vec<CellValue>,
     ) -> bool
     where
         Vec<K>: sqlx::Type<D>,
@@ -152,51 +151,39 @@ where
         K: CustomDisplay,
     {
         if <K as Type<D>>::compatible(type_info) {
-            let val: Option<Vec<K>> = row.get::<Option<Vec<K>>, usize>(i);
-            let val = val
-                .map(|val| {
-                    "[".to_string()
-                        + &val
-                            .iter()
-                            .map(|val_item| val_item.to_string())
-                            .collect::<Vec<String>>()
-                            .join(",")
-                        + "]"
-                })
-                .unwrap_or_else(|| "null".to_string());
-            data.push(val);
+            let val: Option<Vec<K>> = row.get_unchecked::<Option<Vec<K>>, usize>(i);
+            data.push(val.map_or(CellValue::Null, |val| {
+                CellValue::Array(
+                    val.iter()
+                        .map(|val_item| CellValue::Text(val_item.to_string()))
+                        .collect(),
+                )
+            }));
             return true;
         }
         false
     }
 
-    fn write_via_display<K>(type_info: &T, row: &'a R, i: usize, data: &mut Vec<String>) -> bool
+    fn write_typed<K>(type_info: &T, row: &'a R, i: usize, data: &mut Vec<CellValue>) -> bool
     where
         Vec<K>: sqlx::Type<D>,
         K: sqlx::Type<D>,
         Vec<K>: sqlx::Decode<'a, D>,
-        K: Display,
+        K: Into<CellValue>,
     {
         if <K as Type<D>>::compatible(type_info) {
-            let val: Option<Vec<K>> = row.get::<Option<Vec<K>>, usize>(i);
-            let val = val
-                .map(|val| {
-                    "[".to_string()
-                        + &val
-                            .iter()
-                            .map(|val_item| val_item.to_string())
-                            .collect::<Vec<String>>()
-                            .join(",")
-                        + "]"
-                })
-                .unwrap_or_else(|| "null".to_string());
-            data.push(val);
+            let val: Option<Vec<K>> = row.get_unchecked::<Option<Vec<K>>, usize>(i);
+            data.push(val.map_or(CellValue::Null, |val| {
+                CellValue::Array(val.into_iter().map(Into::into).collect())
+            }));
             return true;
         }
         false
     }
 }
 
+/// Writer for Postgres types, given type info could be the base type of domain,
+/// so column type is not checked again
 pub(super) trait GenericTypeWriter<'a, T, R, D>
 where
     R: Row<Database = D>,
@@ -225,79 +212,76 @@ where
     (): sqlx::Type<D> + sqlx::Decode<'a, D>,
     usize: ColumnIndex<R>,
 {
-    fn write_row_cell(type_info: &T, row: &'a R, i: usize, data: &mut Vec<String>) {
-        if Self::write_via_display::<bool>(type_info, row, i, data) {
+    fn write_row_cell(type_info: &T, row: &'a R, i: usize, data: &mut Vec<CellValue>) {
+        if Self::write_typed::<bool>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<String>(type_info, row, i, data) {
+        if Self::write_typed::<String>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<i64>(type_info, row, i, data) {
+        if Self::write_typed::<i64>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<i32>(type_info, row, i, data) {
+        if Self::write_typed::<i32>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<i16>(type_info, row, i, data) {
+        if Self::write_typed::<i16>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<i8>(type_info, row, i, data) {
+        if Self::write_typed::<i8>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<f32>(type_info, row, i, data) {
+        if Self::write_typed::<f32>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<f64>(type_info, row, i, data) {
+        if Self::write_typed::<f64>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<BigDecimal>(type_info, row, i, data) {
+        if Self::write_typed::<BigDecimal>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_debug::<Vec<u8>>(type_info, row, i, data) {
+        if Self::write_typed::<Vec<u8>>(type_info, row, i, data) {
             return;
         }
         if Self::write_via_debug::<()>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::time::PrimitiveDateTime>(type_info, row, i, data)
-        {
+        if Self::write_typed::<sqlx::types::time::PrimitiveDateTime>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::time::OffsetDateTime>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::time::OffsetDateTime>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::time::Date>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::time::Date>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::time::Time>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::time::Time>(type_info, row, i, data) {
             return;
         }
         if Self::write_via_custom_display::<sqlx_postgres::types::PgTimeTz>(type_info, row, i, data)
         {
             return;
         }
-        if Self::write_via_display::<sqlx::types::uuid::Uuid>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::uuid::Uuid>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::ipnetwork::IpNetwork>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::ipnetwork::IpNetwork>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::mac_address::MacAddress>(type_info, row, i, data)
-        {
+        if Self::write_typed::<sqlx::types::mac_address::MacAddress>(type_info, row, i, data) {
             return;
         }
         if Self::write_via_debug::<sqlx::types::BitVec>(type_info, row, i, data) {
             return;
         }
-        if Self::write_via_display::<sqlx::types::JsonValue>(type_info, row, i, data) {
+        if Self::write_typed::<sqlx::types::JsonValue>(type_info, row, i, data) {
             return;
         }
         tracing::debug!("Type not supported: {:?}", type_info);
-        data.push(format!("{} not supported", type_info.name()));
+        data.push(CellValue::Unsupported(type_info.name().to_string()));
 
         // TODO: Add support for other types
         // PgInterval	INTERVAL
-        // PgRange<T>	INT8RANGE, INT4RANGE, TSRANGE, TSTZRANGE, DATERANGE, NUMRANGE
         // PgMoney	MONEY
         // PgLTree	LTREE
         // PgLQuery	LQUERY
@@ -309,7 +293,7 @@ where
         type_info: &T,
         row: &'a R,
         i: usize,
-        data: &mut Vec<String>,
+        data: &mut Vec<CellValue>,
     ) -> bool
     where
         K: sqlx::Type<D>,
@@ -317,51 +301,131 @@ where
         K: CustomDisplay,
     {
         if <K as Type<D>>::compatible(type_info) {
-            let val: Option<K> = row.get::<Option<K>, usize>(i);
-            let val = val
-                .map(|val| val.to_string())
-                .unwrap_or_else(|| "null".to_string());
-            data.push(val);
+            let val: Option<K> = row.get_unchecked::<Option<K>, usize>(i);
+            data.push(val.map_or(CellValue::Null, |val| CellValue::Text(val.to_string())));
             return true;
         }
         false
     }
 
-    fn write_via_debug<K>(type_info: &T, row: &'a R, i: usize, data: &mut Vec<String>) -> bool
+    fn write_via_debug<K>(type_info: &T, row: &'a R, i: usize, data: &mut Vec<CellValue>) -> bool
     where
         K: sqlx::Type<D>,
         K: sqlx::Decode<'a, D>,
         K: Debug,
     {
         if <K as Type<D>>::compatible(type_info) {
-            let val: Option<K> = row.get::<Option<K>, usize>(i);
-            let val = val
-                .map(|val| format!("{:?}", val))
-                .unwrap_or_else(|| "null".to_string());
-            data.push(val);
+            let val: Option<K> = row.get_unchecked::<Option<K>, usize>(i);
+            data.push(val.map_or(CellValue::Null, |val| CellValue::Text(format!("{:?}", val))));
             return true;
         }
         false
     }
 
-    fn write_via_display<K>(type_info: &T, row: &'a R, i: usize, data: &mut Vec<String>) -> bool
+    fn write_typed<K>(type_info: &T, row: &'a R, i: usize, data: &mut Vec<CellValue>) -> bool
     where
         K: sqlx::Type<D>,
         K: sqlx::Decode<'a, D>,
-        K: Display,
+        K: Into<CellValue>,
     {
         if <K as Type<D>>::compatible(type_info) {
-            let val: Option<K> = row.get::<Option<K>, usize>(i);
-            let val = val
-                .map(|val| val.to_string())
-                .unwrap_or_else(|| "null".to_string());
-            data.push(val);
+            let val: Option<K> = row.get_unchecked::<Option<K>, usize>(i);
+            data.push(val.into());
             return true;
         }
         false
     }
 }
 
+/// Writer for types which are supported by every database, unlike
+/// `GenericTypeWriter`, which also requires Postgres specific types
+pub(super) trait CommonTypeWriter<'a, T, R, D>
+where
+    R: Row<Database = D>,
+    D: sqlx::Database<TypeInfo = T>,
+    T: Debug + TypeInfo,
+    bool: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    String: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    i8: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    i16: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    i32: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    i64: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    f32: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    f64: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    Vec<u8>: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    sqlx::types::time::PrimitiveDateTime: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    sqlx::types::time::OffsetDateTime: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    sqlx::types::time::Date: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    sqlx::types::time::Time: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    sqlx::types::uuid::Uuid: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    sqlx::types::JsonValue: sqlx::Type<D> + sqlx::Decode<'a, D>,
+    usize: ColumnIndex<R>,
+{
+    // integers go before booleans, as some databases keep booleans as integers
+    fn write_row_cell(type_info: &T, row: &'a R, i: usize, data: &mut Vec<CellValue>) {
+        if Self::write_typed::<String>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<i64>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<i32>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<i16>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<i8>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<f64>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<f32>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<bool>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<Vec<u8>>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<sqlx::types::time::PrimitiveDateTime>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<sqlx::types::time::OffsetDateTime>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<sqlx::types::time::Date>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<sqlx::types::time::Time>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<sqlx::types::uuid::Uuid>(type_info, row, i, data) {
+            return;
+        }
+        if Self::write_typed::<sqlx::types::JsonValue>(type_info, row, i, data) {
+            return;
+        }
+        tracing::debug!("Type not supported: {:?}", type_info);
+        data.push(CellValue::Unsupported(type_info.name().to_string()));
+    }
+
+    fn write_typed<K>(type_info: &T, row: &'a R, i: usize, data: &mut Vec<CellValue>) -> bool
+    where
+        K: sqlx::Type<D>,
+        K: sqlx::Decode<'a, D>,
+        K: Into<CellValue>,
+    {
+        if <K as Type<D>>::compatible(type_info) {
+            let val: Option<K> = row.get::<Option<K>, usize>(i);
+            data.push(val.into());
+            return true;
+        }
+        false
+    }
+}
 
 pub(super) trait CustomDisplay {
     fn to_string(&self) -> String;
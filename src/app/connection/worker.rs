@@ -0,0 +1,1453 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::mpsc::Sender,
+    time::{Duration, Instant},
+};
+
+use async_std::{
+    channel::Receiver,
+    fs::File,
+    future,
+    io::{ReadExt, WriteExt},
+};
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt, TryStreamExt,
+};
+use tracing::Instrument;
+
+use super::copying::{CopyCommand, CopyDirection};
+use super::mysql;
+use super::notices::NoticeCapture;
+use super::splitting::{
+    has_keyword, leading_keywords, number_placeholders, placeholder_names, split_statements,
+};
+use super::{
+    cells::{CellValue, ColumnInfo},
+    driver::{DatabaseDriver, DriverCapabilities, FetchedRow},
+    BindParameter, CancelTargets, Connection, ConnectionKey, ConnectionPurpose, DatabaseKind,
+    DbRequest, DbResponse, DownConnectionReason, ExecutionSummary, FetchLimits, FetchStatus,
+    ServerSettings, StatementPosition, TransactionState, TransactionStatus,
+    IDLE_IN_TRANSACTION_SESSION_TIMEOUT, INVALID_AUTHORIZATION, LOCK_NOT_AVAILABLE, QUERY_CANCELED,
+    QUERY_INTERRUPTED,
+};
+use uuid::Uuid;
+
+pub(super) struct WorkerRequest {
+    pub(super) settings: Option<ServerSettings>,
+    pub(super) request: DbRequest,
+}
+
+/// Owns one connection and serves requests for it one by one,
+/// so that slow request only blocks other requests to the same connection
+pub(super) struct ConnectionWorker {
+    key: ConnectionKey,
+    purpose: ConnectionPurpose,
+    limits: FetchLimits,
+    stop_on_error: bool,
+    manual_transactions: bool,
+    transaction: TransactionState,
+    settings: Option<ServerSettings>,
+    connection: Option<Connection>,
+    tx: Sender<DbResponse>,
+    cancel_targets: CancelTargets,
+    // how long transaction opened for cursor waits for the next page to be asked
+    pages_wait: Duration,
+}
+
+impl ConnectionWorker {
+    pub(super) fn new(
+        key: ConnectionKey,
+        purpose: ConnectionPurpose,
+        limits: FetchLimits,
+        stop_on_error: bool,
+        tx: Sender<DbResponse>,
+        cancel_targets: CancelTargets,
+    ) -> Self {
+        Self {
+            key,
+            purpose,
+            limits,
+            stop_on_error,
+            manual_transactions: false,
+            transaction: TransactionState::Idle,
+            settings: None,
+            connection: None,
+            tx,
+            cancel_targets,
+            pages_wait: PAGES_WAIT,
+        }
+    }
+
+    pub(super) async fn run(mut self, rx: Receiver<WorkerRequest>) {
+        let mut interrupting = None;
+        loop {
+            let WorkerRequest { settings, request } = match interrupting.take() {
+                Some(request) => request,
+                None => match rx.recv().await {
+                    Ok(request) => request,
+                    Err(_) => break,
+                },
+            };
+            if settings.is_some() && settings != self.settings {
+                // server was changed, so the old connection is not valid anymore
+                self.drop_connection();
+                self.settings = settings;
+            }
+            // cancel asked for previous request could arrive after it was done
+            self.cancel_targets.take_request(&self.key);
+            let transaction_before = self.transaction_status();
+            let cancellable = self.purpose == ConnectionPurpose::Queries;
+            if cancellable {
+                self.cancel_targets.set_running(&self.key, true);
+            }
+            if let DbRequest::Execute(id, database, query, parameters, retries) = request {
+                // rows are sent page by page until all are read
+                // or until some other request comes instead of asking for more
+                let capture = NoticeCapture::start(id, database.clone(), self.tx.clone());
+                interrupting = self
+                    .execute(id, database, query, parameters, retries, &rx)
+                    .instrument(capture.span())
+                    .await;
+            } else {
+                let response = self.process_request(request).await;
+                if self.tx.send(response).is_err() {
+                    // main thread is gone, so there is no one to serve anymore
+                    break;
+                }
+            }
+            if cancellable {
+                self.cancel_targets.set_running(&self.key, false);
+            }
+            if self.transaction_status() != transaction_before {
+                let response = DbResponse::TransactionStatus {
+                    server_id: self.key.server_id,
+                    database: self.key.name.clone(),
+                    status: self.transaction_status(),
+                };
+                Self::send(&self.tx, response);
+            }
+        }
+        self.drop_connection();
+    }
+
+    /// Returns existing connection or tries to open new one
+    /// if settings of the server are already known
+    async fn connection(&mut self) -> Result<Option<&mut Connection>, sqlx::Error> {
+        if self.connection.is_none() {
+            let settings = match &self.settings {
+                Some(settings) => settings.clone(),
+                None => return Ok(None),
+            };
+            let mut connection = Connection::connect(&self.key.name, &settings.url).await?;
+            let capabilities = connection.driver.capabilities();
+            if capabilities.session_timeouts {
+                if settings
+                    .timeouts
+                    .idle_in_transaction_session_timeout
+                    .is_some()
+                    && !capabilities.idle_transaction_timeout
+                {
+                    tracing::warn!("idle in transaction timeout is not supported, so not set");
+                }
+                connection.driver.set_timeouts(&settings.timeouts).await?;
+            } else if !settings.timeouts.is_empty() {
+                tracing::warn!("timeouts are not supported for this database, so not set");
+            }
+            match connection.backend_pid {
+                Some(backend_pid)
+                    if capabilities.cancel_query && self.purpose == ConnectionPurpose::Queries =>
+                {
+                    self.cancel_targets
+                        .register(self.key.clone(), &connection, backend_pid);
+                }
+                _ => {}
+            }
+            self.connection = Some(connection);
+        }
+        Ok(self.connection.as_mut())
+    }
+
+    /// Capabilities of the driver, which are known even before connecting
+    fn capabilities(&self) -> &'static DriverCapabilities {
+        self.settings
+            .as_ref()
+            .map(|settings| DatabaseKind::from_url(&settings.url))
+            .unwrap_or(DatabaseKind::Postgres)
+            .capabilities()
+    }
+
+    fn drop_connection(&mut self) {
+        if self.purpose == ConnectionPurpose::Queries {
+            self.cancel_targets.unregister(&self.key);
+        }
+        // transaction is rolled back by server once connection is gone
+        self.transaction = TransactionState::Idle;
+        self.connection = None;
+    }
+
+    fn process_db_error(
+        &mut self,
+        error: sqlx::Error,
+        id: Uuid,
+        repeat: Option<DbRequest>,
+    ) -> DbResponse {
+        tracing::error!("Error executing query: {:?}", error);
+        match error {
+            // MySQL uses generic codes for timeouts, so they are told by error number
+            sqlx::Error::Database(db_error) if mysql::is_timeout(db_error.as_ref()) => {
+                DbResponse::TimedOut(id, db_error.message().to_string())
+            }
+            // query_canceled is also used for timeouts, so it is only treated as cancel
+            // when user asked for it, MySQL reports killed query in the same way
+            sqlx::Error::Database(db_error)
+                if matches!(
+                    db_error.code().as_deref(),
+                    Some(QUERY_CANCELED | QUERY_INTERRUPTED)
+                ) && self.cancel_targets.take_request(&self.key) =>
+            {
+                DbResponse::Cancelled(id)
+            }
+            sqlx::Error::Database(db_error)
+                if matches!(
+                    db_error.code().as_deref(),
+                    Some(QUERY_CANCELED | LOCK_NOT_AVAILABLE)
+                ) =>
+            {
+                DbResponse::TimedOut(id, db_error.message().to_string())
+            }
+            sqlx::Error::Database(db_error)
+                if db_error.code().as_deref() == Some(IDLE_IN_TRANSACTION_SESSION_TIMEOUT) =>
+            {
+                // server terminates such sessions
+                self.drop_connection();
+                DbResponse::TimedOut(id, db_error.message().to_string())
+            }
+            sqlx::Error::Database(db_error)
+                if db_error
+                    .code()
+                    .is_some_and(|code| code.starts_with(INVALID_AUTHORIZATION)) =>
+            {
+                Self::send(&self.tx, DbResponse::PasswordRejected(id));
+                DbResponse::Error(id, db_error.message().to_string())
+            }
+            sqlx::Error::Database(db_error) => {
+                return DbResponse::Error(id, db_error.message().to_string())
+            }
+            sqlx::Error::Io(io_error) => {
+                self.drop_connection();
+                if let Some(repeat) = repeat {
+                    DbResponse::ConnectionIsDown {
+                        original_request: repeat,
+                        reason: DownConnectionReason::IoError(format!("IO Error: {:?}", io_error)),
+                    }
+                } else {
+                    DbResponse::Error(id, format!("IO Error: {:?}", io_error))
+                }
+            }
+            // used for things that are not supported by the server or failed locally
+            sqlx::Error::Configuration(e) => DbResponse::Error(id, e.to_string()),
+            _ => DbResponse::Error(id, format!("unknown db error: {:?}", error)),
+        }
+    }
+
+    async fn process_request(&mut self, request: DbRequest) -> DbResponse {
+        match request {
+            DbRequest::ListObjects {
+                server_id,
+                path,
+                retries,
+            } => {
+                let repeat = DbRequest::ListObjects {
+                    server_id,
+                    path: path.clone(),
+                    retries: retries + 1,
+                };
+                let result = match self.connection().await {
+                    Ok(Some(connection)) => {
+                        let levels = connection.driver.capabilities().levels;
+                        let level = match levels.get(path.len()) {
+                            Some(level) => *level,
+                            None => {
+                                return DbResponse::Error(
+                                    server_id,
+                                    "There is nothing inside".to_string(),
+                                )
+                            }
+                        };
+                        let inner_level = levels.get(path.len() + 1).copied();
+                        connection
+                            .driver
+                            .list_objects(level, &path)
+                            .await
+                            .map(|names| (names, inner_level))
+                    }
+                    Ok(None) if path.is_empty() => {
+                        return DbResponse::Error(
+                            server_id,
+                            "No connection to management database".to_string(),
+                        )
+                    }
+                    Ok(None) => {
+                        return DbResponse::ConnectionIsDown {
+                            original_request: repeat,
+                            reason: DownConnectionReason::MissingConnection,
+                        }
+                    }
+                    Err(e) => Err(e),
+                };
+                // databases are only listed after connecting, so there is nothing to repeat
+                let repeat = if path.is_empty() { None } else { Some(repeat) };
+                match result {
+                    Ok((names, inner_level)) => DbResponse::ObjectsListed {
+                        server_id,
+                        path,
+                        names,
+                        inner_level,
+                    },
+                    Err(e) => self.process_db_error(e, server_id, repeat),
+                }
+            }
+            DbRequest::ConnectToServer(id, _settings) => match self.connection().await {
+                Ok(_) => DbResponse::Connected(id),
+                Err(e) => self.process_db_error(e, id, None),
+            },
+            DbRequest::ConnectToDatabase(id, _name, _settings) => match self.connection().await {
+                Ok(_) => DbResponse::None,
+                Err(e) => self.process_db_error(e, id, None),
+            },
+            DbRequest::DescribeParameters {
+                server_id,
+                database,
+                query,
+            } => {
+                let mut parameters: Vec<(String, Option<String>)> =
+                    placeholder_names(&query, self.capabilities().dialect)
+                        .into_iter()
+                        .map(|name| (name, None))
+                        .collect();
+                // statement failing to parse would abort the open transaction
+                if self.transaction == TransactionState::Idle {
+                    let dialect = self.capabilities().dialect;
+                    for statement in split_statements(&query, dialect) {
+                        let (statement, names) =
+                            number_placeholders(&statement, &HashMap::new(), false, dialect);
+                        if names.is_empty() {
+                            continue;
+                        }
+                        let result = match self.connection().await {
+                            Ok(Some(connection)) => {
+                                connection.driver.describe_parameters(&statement).await
+                            }
+                            Ok(None) => break,
+                            Err(e) => Err(e),
+                        };
+                        let types = match result {
+                            Ok(types) => types,
+                            // statement could depend on objects created earlier in the script
+                            Err(sqlx::Error::Database(_)) => continue,
+                            Err(e) => return self.process_db_error(e, server_id, None),
+                        };
+                        for (name, type_name) in names.iter().zip(types) {
+                            if let Some((_, known)) =
+                                parameters.iter_mut().find(|(known, _)| known == name)
+                            {
+                                known.get_or_insert(type_name);
+                            }
+                        }
+                    }
+                }
+                DbResponse::ParametersDescribed {
+                    server_id,
+                    database,
+                    query,
+                    parameters,
+                }
+            }
+            DbRequest::Execute(id, ..) => {
+                // executing is handled by run loop, this is not supposed to happen
+                tracing::error!("Execute request was processed as a single response");
+                DbResponse::Error(id, "Query could not be executed".to_string())
+            }
+            DbRequest::Explain {
+                server_id,
+                database,
+                query,
+                analyze,
+            } => {
+                if !self.capabilities().query_plan {
+                    return DbResponse::Error(
+                        server_id,
+                        "Query plan is not supported for this database".to_string(),
+                    );
+                }
+                // analyzed statement is executed, so its changes are rolled back,
+                // open transaction is kept as it was with the help of savepoint
+                let (begin, undo): (&str, &[&str]) = match self.transaction {
+                    TransactionState::Idle if analyze => ("BEGIN", &["ROLLBACK"]),
+                    TransactionState::Active => (
+                        "SAVEPOINT tisq_explain",
+                        &[
+                            "ROLLBACK TO SAVEPOINT tisq_explain",
+                            "RELEASE SAVEPOINT tisq_explain",
+                        ],
+                    ),
+                    _ => ("", &[]),
+                };
+                let mut undone = false;
+                let result = match self.connection().await {
+                    Ok(Some(connection)) if !begin.is_empty() => {
+                        match connection.driver.execute_command(begin).await {
+                            Ok(()) => {
+                                let mut result = connection.driver.explain(&query, analyze).await;
+                                undone = true;
+                                for command in undo {
+                                    if let Err(e) = connection.driver.execute_command(command).await
+                                    {
+                                        undone = false;
+                                        result = result.and(Err(e));
+                                        break;
+                                    }
+                                }
+                                result
+                            }
+                            Err(e) => Err(e),
+                        }
+                    }
+                    Ok(Some(connection)) => connection.driver.explain(&query, analyze).await,
+                    Ok(None) => {
+                        return DbResponse::Error(
+                            server_id,
+                            "No connection to database".to_string(),
+                        )
+                    }
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(plan) => DbResponse::Explained {
+                        server_id,
+                        database,
+                        plan,
+                        analyzed: analyze,
+                    },
+                    Err(e) => {
+                        // failed statement aborts transaction, unless it was rolled back to savepoint
+                        if self.transaction == TransactionState::Active && !undone {
+                            self.transaction = TransactionState::Aborted;
+                        }
+                        self.process_db_error(e, server_id, None)
+                    }
+                }
+            }
+            DbRequest::FetchMore { .. } => {
+                // rows of previous result were already dropped, so nothing to send
+                DbResponse::None
+            }
+            DbRequest::SetTransactionMode {
+                server_id, manual, ..
+            } => {
+                if !manual && self.transaction != TransactionState::Idle {
+                    return DbResponse::Error(
+                        server_id,
+                        "Commit or rollback current transaction first".to_string(),
+                    );
+                }
+                self.manual_transactions = manual;
+                DbResponse::None
+            }
+            DbRequest::Commit { server_id, .. } => {
+                self.finish_transaction(server_id, "COMMIT").await
+            }
+            DbRequest::Rollback { server_id, .. } => {
+                self.finish_transaction(server_id, "ROLLBACK").await
+            }
+            DbRequest::CancelQuery { server_id, .. } => {
+                // cancelling is handled by manager, this is not supposed to happen
+                tracing::error!("Cancel request was routed to connection worker");
+                DbResponse::Error(server_id, "Query could not be cancelled".to_string())
+            }
+            DbRequest::Listen { server_id, .. }
+            | DbRequest::Unlisten { server_id, .. }
+            | DbRequest::Notify { server_id, .. } => {
+                // channels are listened by manager, this is not supposed to happen
+                tracing::error!("Listen request was routed to connection worker");
+                DbResponse::Error(server_id, "Channels could not be listened".to_string())
+            }
+            DbRequest::TestConnection(_) => {
+                // testing is handled by manager, this is not supposed to happen
+                tracing::error!("Test connection request was routed to connection worker");
+                DbResponse::ConnectionTested {
+                    error: Some("Connection could not be tested".to_string()),
+                }
+            }
+            DbRequest::DisconnectServer(server_id) => {
+                // disconnecting is handled by manager, this is not supposed to happen
+                tracing::error!("Disconnect request was routed to connection worker");
+                DbResponse::Error(server_id, "Server could not be disconnected".to_string())
+            }
+        }
+    }
+
+    async fn execute(
+        &mut self,
+        id: Uuid,
+        database: String,
+        query: String,
+        parameters: Vec<BindParameter>,
+        retries: i32,
+        rx: &Receiver<WorkerRequest>,
+    ) -> Option<WorkerRequest> {
+        tracing::info!("Executing query: {}", query);
+        let mut statements = split_statements(&query, self.capabilities().dialect);
+        if statements.is_empty() {
+            // let database decide what to do with empty query
+            statements.push(query.clone());
+        }
+        let total = statements.len();
+        let limits = self.limits;
+        let tx = self.tx.clone();
+        let repeat_all = DbRequest::Execute(
+            id,
+            database.clone(),
+            query.clone(),
+            parameters.clone(),
+            retries + 1,
+        );
+        // statements executed earlier in the transaction would be lost with connection
+        let in_transaction = self.transaction != TransactionState::Idle;
+        let capabilities = self.capabilities();
+        let mut record = Some(ExecutionRecord {
+            server_id: id,
+            database: database.clone(),
+            query: query.clone(),
+            started: Instant::now(),
+            error: None,
+        });
+
+        if self.manual_transactions && !in_transaction {
+            let result = match self.connection().await {
+                Ok(Some(connection)) => connection.driver.execute_command("BEGIN").await,
+                Ok(None) => {
+                    let response = DbResponse::ConnectionIsDown {
+                        original_request: repeat_all,
+                        reason: DownConnectionReason::MissingConnection,
+                    };
+                    Self::send(&tx, response);
+                    return None;
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                let response = self.process_db_error(e, id, Some(repeat_all));
+                Self::send(&tx, response.clone());
+                if let Some(record) = record.take() {
+                    record.fail(&tx, &response);
+                }
+                return None;
+            }
+            self.transaction = TransactionState::Active;
+        }
+
+        for (index, statement) in statements.iter().enumerate() {
+            let position = StatementPosition { index, total };
+            // only the last statement keeps its rows for fetching more later
+            let pages_rx = if position.is_last() { Some(rx) } else { None };
+            // repeating is only safe while nothing in the script was executed
+            let repeat = if index == 0 && !in_transaction {
+                Some(repeat_all.clone())
+            } else {
+                None
+            };
+            let transaction = self.transaction;
+            let pages_wait = self.pages_wait;
+            // server would end the session while transaction of cursor waits for the next page
+            let idle_timeout = self.settings.as_ref().is_some_and(|settings| {
+                settings
+                    .timeouts
+                    .idle_in_transaction_session_timeout
+                    .is_some()
+            });
+
+            let result = match self.connection().await {
+                Ok(Some(connection)) => match CopyCommand::parse(statement) {
+                    Some(copy) => {
+                        copy_file(connection, copy, id, &database, position, &tx, &mut record)
+                            .await
+                            .map(|_| None)
+                    }
+                    None => {
+                        let (bound_statement, values) =
+                            bind_parameters(statement, &parameters, capabilities);
+                        let pages = Pages {
+                            server_id: id,
+                            database: &database,
+                            position,
+                            limits,
+                            tx: &tx,
+                            rx: pages_rx,
+                            wait: None,
+                        };
+                        let own_transaction = transaction == TransactionState::Idle;
+                        if uses_cursor(statement, capabilities, transaction)
+                            && !(own_transaction && idle_timeout)
+                        {
+                            let pages = Pages {
+                                wait: own_transaction.then_some(pages_wait),
+                                ..pages
+                            };
+                            fetch_by_cursor(
+                                connection,
+                                &bound_statement,
+                                values,
+                                own_transaction,
+                                pages,
+                                &mut record,
+                            )
+                            .await
+                        } else {
+                            let rows = connection.driver.fetch(&bound_statement, values);
+                            send_pages(Rows::Stream(rows), pages, &mut record).await
+                        }
+                    }
+                },
+                Ok(None) => {
+                    let response = match repeat {
+                        Some(repeat) => DbResponse::ConnectionIsDown {
+                            original_request: repeat,
+                            reason: DownConnectionReason::MissingConnection,
+                        },
+                        None => DbResponse::Error(id, "No connection to database".to_string()),
+                    };
+                    Self::send(&tx, response.clone());
+                    if let Some(record) = record.take() {
+                        record.fail(&tx, &response);
+                    }
+                    return None;
+                }
+                Err(e) => Err(e),
+            };
+
+            let error = match result {
+                Ok(interrupting) => {
+                    self.track_transaction(statement);
+                    if position.is_last() {
+                        return interrupting;
+                    }
+                    continue;
+                }
+                Err(e) => e,
+            };
+            if self.transaction == TransactionState::Active {
+                self.transaction = TransactionState::Aborted;
+            }
+
+            let response = match self.process_db_error(error, id, repeat) {
+                DbResponse::Error(_, message) if total > 1 => {
+                    // statements after failed one could not run in aborted transaction anyway
+                    let stopped = (self.stop_on_error
+                        || self.transaction == TransactionState::Aborted)
+                        && !position.is_last();
+                    if let Some(record) = record.as_mut() {
+                        record.error.get_or_insert_with(|| message.clone());
+                    }
+                    Self::send(
+                        &tx,
+                        DbResponse::StatementFailed {
+                            server_id: id,
+                            database: database.clone(),
+                            position,
+                            message,
+                            stopped,
+                        },
+                    );
+                    if stopped {
+                        if let Some(record) = record.take() {
+                            record.finish(&tx, 0, false);
+                        }
+                        return None;
+                    }
+                    continue;
+                }
+                response => response,
+            };
+            // cancelled, timed out or lost connection, nothing else could be done with the script
+            Self::send(&tx, response.clone());
+            if let Some(record) = record.take() {
+                record.fail(&tx, &response);
+            }
+            return None;
+        }
+        // the last statement has failed
+        if let Some(record) = record.take() {
+            record.finish(&tx, 0, false);
+        }
+        None
+    }
+
+    /// Follows transaction control statements executed by user
+    fn track_transaction(&mut self, statement: &str) {
+        let keywords = leading_keywords(statement, 2, self.capabilities().dialect);
+        let keywords: Vec<&str> = keywords.iter().map(|word| word.as_str()).collect();
+        self.transaction = match keywords.as_slice() {
+            ["begin", ..] | ["start", "transaction"] => TransactionState::Active,
+            // savepoint makes transaction usable again
+            ["rollback", "to"] => TransactionState::Active,
+            ["commit" | "end" | "rollback" | "abort", ..] => TransactionState::Idle,
+            _ => self.transaction,
+        };
+    }
+
+    async fn finish_transaction(&mut self, id: Uuid, command: &str) -> DbResponse {
+        if self.transaction == TransactionState::Idle {
+            return DbResponse::Error(id, "There is no open transaction".to_string());
+        }
+        let result = match self.connection().await {
+            Ok(Some(connection)) => connection.driver.execute_command(command).await,
+            Ok(None) => return DbResponse::Error(id, "No connection to database".to_string()),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => {
+                self.transaction = TransactionState::Idle;
+                DbResponse::None
+            }
+            Err(e) => self.process_db_error(e, id, None),
+        }
+    }
+
+    fn transaction_status(&self) -> TransactionStatus {
+        TransactionStatus {
+            manual: self.manual_transactions,
+            state: self.transaction,
+        }
+    }
+
+    fn send(tx: &Sender<DbResponse>, response: DbResponse) {
+        if let Err(e) = tx.send(response) {
+            tracing::error!("Error sending response: {:?}", e);
+        }
+    }
+}
+
+/// Numbers placeholders of statement and picks values for them,
+/// placeholders without value are bound as NULL
+fn bind_parameters(
+    statement: &str,
+    parameters: &[BindParameter],
+    capabilities: &DriverCapabilities,
+) -> (String, Vec<Option<String>>) {
+    let casts: HashMap<String, String> = parameters
+        .iter()
+        .filter_map(|parameter| {
+            let type_name = parameter.type_name.clone()?;
+            Some((parameter.name.clone(), type_name))
+        })
+        .collect();
+    let (statement, names) = number_placeholders(
+        statement,
+        &casts,
+        capabilities.positional_placeholders,
+        capabilities.dialect,
+    );
+    let values = names
+        .iter()
+        .map(|name| {
+            parameters
+                .iter()
+                .find(|parameter| parameter.name == *name)
+                .and_then(|parameter| parameter.value.clone())
+        })
+        .collect();
+    (statement, values)
+}
+
+/// Collects outcome of executed script to report it for query history
+struct ExecutionRecord {
+    server_id: Uuid,
+    database: String,
+    query: String,
+    started: Instant,
+    error: Option<String>,
+}
+
+impl ExecutionRecord {
+    fn finish(self, tx: &Sender<DbResponse>, rows: usize, more_rows: bool) {
+        let summary = ExecutionSummary {
+            query: self.query,
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            rows,
+            more_rows,
+            error: self.error,
+        };
+        ConnectionWorker::send(
+            tx,
+            DbResponse::QueryExecuted {
+                server_id: self.server_id,
+                database: self.database,
+                summary,
+            },
+        );
+    }
+
+    /// Reports script stopped by the response,
+    /// unless it is going to be repeated after reconnecting
+    fn fail(mut self, tx: &Sender<DbResponse>, response: &DbResponse) {
+        let message = match response {
+            DbResponse::ConnectionIsDown { .. } => return,
+            DbResponse::Error(_, message) | DbResponse::TimedOut(_, message) => message.clone(),
+            DbResponse::Cancelled(_) => "Cancelled".to_string(),
+            _ => return,
+        };
+        self.error.get_or_insert(message);
+        self.finish(tx, 0, false);
+    }
+}
+
+// name of the cursor used to read rows by pages, only one is open at a time
+const PAGES_CURSOR: &str = "tisq_pages";
+// transaction opened only for cursor is not kept longer than this without asking for rows,
+// so that it does not hold snapshot and locks once the result is abandoned
+const PAGES_WAIT: Duration = Duration::from_secs(60);
+
+/// Tells if rows of statement could be read through cursor, which is only
+/// allowed for queries and could not be opened in failed transaction,
+/// `SELECT ... INTO` creates table, so it is not a query for cursor
+fn uses_cursor(
+    statement: &str,
+    capabilities: &DriverCapabilities,
+    transaction: TransactionState,
+) -> bool {
+    let keywords = leading_keywords(statement, 1, capabilities.dialect);
+    capabilities.cursors
+        && transaction != TransactionState::Aborted
+        && matches!(
+            keywords.first().map(|word| word.as_str()),
+            Some("select" | "values" | "table")
+        )
+        && !has_keyword(statement, "into", capabilities.dialect)
+}
+
+/// Reads rows through cursor, so that server only sends rows of pages that were asked for
+/// and the rest is discarded on server once fetching stops. Cursor only lives within
+/// transaction, so if there is none, it is opened for the time of fetching
+async fn fetch_by_cursor(
+    connection: &mut Connection,
+    statement: &str,
+    values: Vec<Option<String>>,
+    own_transaction: bool,
+    pages: Pages<'_>,
+    record: &mut Option<ExecutionRecord>,
+) -> Result<Option<WorkerRequest>, sqlx::Error> {
+    let driver = connection.driver.as_mut();
+    if own_transaction {
+        driver.execute_command("BEGIN").await?;
+    }
+    let declare = format!(
+        "DECLARE {} NO SCROLL CURSOR FOR {}",
+        PAGES_CURSOR, statement
+    );
+    let result = match driver.fetch(&declare, values).try_collect::<Vec<_>>().await {
+        Ok(_) => {
+            let rows = Rows::Cursor {
+                driver: &mut *driver,
+                page_size: pages.limits.page_size,
+                batch: VecDeque::new(),
+                done: false,
+            };
+            send_pages(rows, pages, record).await
+        }
+        Err(e) => Err(e),
+    };
+    let finish = match (&result, own_transaction) {
+        // query could call functions changing something, so it is committed as without cursor
+        (Ok(_), true) => "COMMIT".to_string(),
+        (Err(_), true) => "ROLLBACK".to_string(),
+        (Ok(_), false) => format!("CLOSE {}", PAGES_CURSOR),
+        // failed statement aborts transaction, which then has to be rolled back by user
+        (Err(_), false) => return result,
+    };
+    match driver.execute_command(&finish).await {
+        Ok(()) => result,
+        Err(e) => result.and(Err(e)),
+    }
+}
+
+/// Source of result rows, either all rows sent by server at once or cursor read by pages
+enum Rows<'a> {
+    Stream(BoxStream<'a, Result<FetchedRow, sqlx::Error>>),
+    Cursor {
+        driver: &'a mut dyn DatabaseDriver,
+        page_size: usize,
+        batch: VecDeque<FetchedRow>,
+        // the last fetched batch was not full, so there is nothing more
+        done: bool,
+    },
+}
+
+impl Rows<'_> {
+    async fn next(&mut self) -> Result<Option<FetchedRow>, sqlx::Error> {
+        match self {
+            Rows::Stream(rows) => rows.try_next().await,
+            Rows::Cursor {
+                driver,
+                page_size,
+                batch,
+                done,
+            } => {
+                if batch.is_empty() && !*done {
+                    let fetch = format!("FETCH FORWARD {} FROM {}", page_size, PAGES_CURSOR);
+                    let fetched: Vec<FetchedRow> =
+                        driver.fetch(&fetch, vec![]).try_collect().await?;
+                    *done = fetched.len() < *page_size;
+                    batch.extend(fetched);
+                }
+                Ok(batch.pop_front())
+            }
+        }
+    }
+}
+
+/// Where and how rows of one statement are sent
+struct Pages<'a> {
+    server_id: Uuid,
+    database: &'a str,
+    position: StatementPosition,
+    limits: FetchLimits,
+    tx: &'a Sender<DbResponse>,
+    rx: Option<&'a Receiver<WorkerRequest>>,
+    // how long to wait for the next page to be asked, then the rest of rows is dropped
+    wait: Option<Duration>,
+}
+
+/// Sends result rows by pages, waiting for the next request after each page.
+/// Returns request that came instead of asking for more rows, if any.
+/// Without receiver only the first page is sent and the rest is dropped,
+/// the same happens once waiting for the next page takes too long
+async fn send_pages(
+    mut rows: Rows<'_>,
+    pages: Pages<'_>,
+    record: &mut Option<ExecutionRecord>,
+) -> Result<Option<WorkerRequest>, sqlx::Error> {
+    let Pages {
+        server_id,
+        database,
+        position,
+        limits,
+        tx,
+        rx,
+        wait,
+    } = pages;
+    // one row is always read ahead to know if there are more of them
+    let mut next = rows.next().await?;
+    let mut columns = next
+        .as_mut()
+        .and_then(|row| row.columns.take())
+        .unwrap_or_default();
+    let mut fetched = 0;
+    let mut first_page = true;
+
+    loop {
+        let mut data = vec![];
+        while let Some(row) = next.take() {
+            if data.len() >= limits.page_size || limits.reached(fetched) {
+                next = Some(row);
+                break;
+            }
+            data.push(row.data);
+            fetched += 1;
+            next = rows.next().await?;
+        }
+
+        let status = match next {
+            None => FetchStatus::Done,
+            Some(_) if limits.reached(fetched) || rx.is_none() => FetchStatus::LimitReached,
+            Some(_) => FetchStatus::More,
+        };
+
+        let response = if first_page {
+            DbResponse::Executed {
+                server_id,
+                database: database.to_string(),
+                position,
+                columns: std::mem::take(&mut columns),
+                data,
+                status,
+            }
+        } else {
+            DbResponse::RowsFetched {
+                server_id,
+                database: database.to_string(),
+                data,
+                status,
+            }
+        };
+        let sent = tx.send(response).is_ok();
+        // script is done from user perspective once the last statement shows its rows
+        if first_page && position.is_last() {
+            if let Some(record) = record.take() {
+                record.finish(tx, fetched, status != FetchStatus::Done);
+            }
+        }
+        if !sent || status != FetchStatus::More {
+            return Ok(None);
+        }
+        first_page = false;
+
+        let rx = match rx {
+            Some(rx) => rx,
+            None => return Ok(None),
+        };
+        let request = match wait {
+            Some(wait) => match future::timeout(wait, rx.recv()).await {
+                Ok(request) => request,
+                Err(_) => {
+                    // table stops asking for more, as there is nothing to read them from
+                    let response = DbResponse::RowsFetched {
+                        server_id,
+                        database: database.to_string(),
+                        data: vec![],
+                        status: FetchStatus::LimitReached,
+                    };
+                    let _ = tx.send(response);
+                    return Ok(None);
+                }
+            },
+            None => rx.recv().await,
+        };
+        match request {
+            Ok(WorkerRequest {
+                request: DbRequest::FetchMore { .. },
+                ..
+            }) => continue,
+            Ok(request) => return Ok(Some(request)),
+            Err(_) => return Ok(None),
+        }
+    }
+}
+
+// file is read and sent to server by chunks of this size
+const COPY_CHUNK_SIZE: usize = 64 * 1024;
+// progress of copying is not sent more often than this
+const COPY_PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Runs `\copy`, moving data between local file and the server,
+/// progress is sent while copying and the summary is sent as result of the statement
+async fn copy_file(
+    connection: &mut Connection,
+    copy: Result<CopyCommand, String>,
+    server_id: Uuid,
+    database: &str,
+    position: StatementPosition,
+    tx: &Sender<DbResponse>,
+    record: &mut Option<ExecutionRecord>,
+) -> Result<(), sqlx::Error> {
+    let copy = copy.map_err(|e| sqlx::Error::Configuration(e.into()))?;
+    if !connection.driver.capabilities().copy {
+        return Err(sqlx::Error::Configuration(
+            "Copying is not supported for this database".into(),
+        ));
+    }
+    let path = copy.path.clone();
+    let file_error = move |e: std::io::Error| {
+        sqlx::Error::Configuration(format!("{}: {}", path.display(), e).into())
+    };
+
+    let (bytes, rows) = match copy.direction {
+        CopyDirection::ToFile => {
+            let mut file = File::create(&copy.path).await.map_err(&file_error)?;
+            let mut progress = CopyProgress::new(server_id, database, tx, None);
+            let mut data = connection.driver.copy_out(&copy.statement).await?;
+            while let Some(chunk) = data.try_next().await? {
+                file.write_all(&chunk).await.map_err(&file_error)?;
+                progress.add(chunk.len());
+            }
+            file.flush().await.map_err(&file_error)?;
+            // server does not tell how many rows were sent
+            (progress.bytes, None)
+        }
+        CopyDirection::FromFile => {
+            let file = File::open(&copy.path).await.map_err(&file_error)?;
+            let total = file.metadata().await.map_err(&file_error)?.len();
+            let progress = CopyProgress::new(server_id, database, tx, Some(total));
+            let file_error = &file_error;
+            let data =
+                stream::try_unfold((file, progress), |(mut file, mut progress)| async move {
+                    let mut chunk = vec![0; COPY_CHUNK_SIZE];
+                    let read = file.read(&mut chunk).await.map_err(file_error)?;
+                    if read == 0 {
+                        return Ok(None);
+                    }
+                    chunk.truncate(read);
+                    progress.add(read);
+                    Ok(Some((chunk, (file, progress))))
+                });
+            let rows = connection
+                .driver
+                .copy_in(&copy.statement, data.boxed())
+                .await?;
+            (total, Some(rows))
+        }
+    };
+
+    let column = |name: &str, type_name: &str| ColumnInfo {
+        name: name.to_string(),
+        type_name: type_name.to_string(),
+    };
+    let response = DbResponse::Executed {
+        server_id,
+        database: database.to_string(),
+        position,
+        columns: vec![
+            column("file", "TEXT"),
+            column("bytes", "INT8"),
+            column("rows", "INT8"),
+        ],
+        data: vec![vec![
+            CellValue::Text(copy.path.display().to_string()),
+            CellValue::from(bytes),
+            CellValue::from(rows),
+        ]],
+        status: FetchStatus::Done,
+    };
+    ConnectionWorker::send(tx, response);
+    if position.is_last() {
+        if let Some(record) = record.take() {
+            record.finish(tx, rows.unwrap_or_default() as usize, false);
+        }
+    }
+    Ok(())
+}
+
+/// Counts copied bytes and sends them to main thread from time to time
+struct CopyProgress<'a> {
+    server_id: Uuid,
+    database: &'a str,
+    tx: &'a Sender<DbResponse>,
+    total: Option<u64>,
+    bytes: u64,
+    reported_at: Instant,
+}
+
+impl<'a> CopyProgress<'a> {
+    fn new(
+        server_id: Uuid,
+        database: &'a str,
+        tx: &'a Sender<DbResponse>,
+        total: Option<u64>,
+    ) -> Self {
+        Self {
+            server_id,
+            database,
+            tx,
+            total,
+            bytes: 0,
+            reported_at: Instant::now(),
+        }
+    }
+
+    fn add(&mut self, bytes: usize) {
+        self.bytes += bytes as u64;
+        if self.reported_at.elapsed() < COPY_PROGRESS_INTERVAL {
+            return;
+        }
+        self.reported_at = Instant::now();
+        ConnectionWorker::send(
+            self.tx,
+            DbResponse::CopyProgress {
+                server_id: self.server_id,
+                database: self.database.to_string(),
+                bytes: self.bytes,
+                total: self.total,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use async_std::{channel, task};
+
+    use super::super::mock::{database_error, MockServer};
+    use super::*;
+
+    fn new_worker(tx: Sender<DbResponse>) -> ConnectionWorker {
+        let key = ConnectionKey {
+            name: "mock".to_string(),
+            server_id: Uuid::nil(),
+        };
+        let limits = FetchLimits {
+            page_size: 2,
+            max_rows: None,
+        };
+        ConnectionWorker::new(
+            key,
+            ConnectionPurpose::Queries,
+            limits,
+            false,
+            tx,
+            CancelTargets::default(),
+        )
+    }
+
+    /// Runs worker with connection to mock server until all requests are served
+    fn serve(server: &MockServer, requests: Vec<DbRequest>) -> Vec<DbResponse> {
+        serve_idle(server, requests, PAGES_WAIT, Duration::ZERO)
+    }
+
+    /// Runs worker, which gets no more requests after the given ones,
+    /// but its channel is only closed once `idle` passes
+    fn serve_idle(
+        server: &MockServer,
+        requests: Vec<DbRequest>,
+        pages_wait: Duration,
+        idle: Duration,
+    ) -> Vec<DbResponse> {
+        let (tx, responses) = mpsc::channel();
+        let mut worker = new_worker(tx);
+        worker.connection = Some(server.connection());
+        worker.pages_wait = pages_wait;
+        let (requests_tx, requests_rx) = channel::unbounded();
+        for request in requests {
+            let request = WorkerRequest {
+                settings: None,
+                request,
+            };
+            requests_tx.try_send(request).unwrap();
+        }
+        task::block_on(async {
+            let close = async {
+                task::sleep(idle).await;
+                drop(requests_tx);
+            };
+            futures::join!(worker.run(requests_rx), close);
+        });
+        responses.try_iter().collect()
+    }
+
+    fn execute(query: &str) -> DbRequest {
+        DbRequest::Execute(
+            Uuid::nil(),
+            "mock".to_string(),
+            query.to_string(),
+            vec![],
+            0,
+        )
+    }
+
+    fn manual_transactions() -> DbRequest {
+        DbRequest::SetTransactionMode {
+            server_id: Uuid::nil(),
+            database: "mock".to_string(),
+            manual: true,
+        }
+    }
+
+    fn rows(values: &[i64]) -> Vec<Vec<CellValue>> {
+        values
+            .iter()
+            .map(|value| vec![CellValue::Int(*value)])
+            .collect()
+    }
+
+    fn transaction_states(responses: &[DbResponse]) -> Vec<TransactionState> {
+        responses
+            .iter()
+            .filter_map(|response| match response {
+                DbResponse::TransactionStatus { status, .. } => Some(status.state),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pages_query_through_cursor_in_own_transaction() {
+        let server = MockServer::default().with_result("select * from t", rows(&[1, 2, 3]));
+        let fetch_more = DbRequest::FetchMore {
+            server_id: Uuid::nil(),
+            database: "mock".to_string(),
+        };
+        let responses = serve(&server, vec![execute("select * from t"), fetch_more]);
+
+        assert!(responses.iter().any(|response| matches!(
+            response,
+            DbResponse::Executed { data, status: FetchStatus::More, .. } if *data == rows(&[1, 2])
+        )));
+        assert!(responses.iter().any(|response| matches!(
+            response,
+            DbResponse::RowsFetched { data, status: FetchStatus::Done, .. } if *data == rows(&[3])
+        )));
+        assert_eq!(
+            server.log(),
+            [
+                "BEGIN",
+                "DECLARE tisq_pages NO SCROLL CURSOR FOR select * from t",
+                "FETCH FORWARD 2 FROM tisq_pages",
+                "FETCH FORWARD 2 FROM tisq_pages",
+                "COMMIT",
+            ]
+        );
+    }
+
+    #[test]
+    fn abandoned_result_does_not_keep_transaction_open() {
+        let server = MockServer::default().with_result("select * from t", rows(&[1, 2, 3]));
+        let responses = serve_idle(
+            &server,
+            vec![execute("select * from t")],
+            Duration::from_millis(10),
+            Duration::from_millis(500),
+        );
+
+        assert!(responses.iter().any(|response| matches!(
+            response,
+            DbResponse::RowsFetched { data, status: FetchStatus::LimitReached, .. } if data.is_empty()
+        )));
+        assert!(transaction_states(&responses).is_empty());
+        assert_eq!(
+            server.log(),
+            [
+                "BEGIN",
+                "DECLARE tisq_pages NO SCROLL CURSOR FOR select * from t",
+                "FETCH FORWARD 2 FROM tisq_pages",
+                "FETCH FORWARD 2 FROM tisq_pages",
+                "COMMIT",
+            ]
+        );
+    }
+
+    #[test]
+    fn select_into_is_not_read_through_cursor() {
+        let server = MockServer::default();
+        serve(&server, vec![execute("select * into t2 from t")]);
+
+        assert_eq!(server.log(), ["select * into t2 from t"]);
+    }
+
+    #[test]
+    fn query_canceled_means_cancel_only_when_user_asked() {
+        let (tx, _responses) = mpsc::channel();
+        let mut worker = new_worker(tx);
+        let connection = MockServer::default().connection();
+        worker
+            .cancel_targets
+            .register(worker.key.clone(), &connection, 42);
+        worker.cancel_targets.set_running(&worker.key, true);
+
+        let timeout = database_error(
+            QUERY_CANCELED,
+            "canceling statement due to statement timeout",
+        );
+        assert_eq!(
+            worker.process_db_error(timeout, Uuid::nil(), None),
+            DbResponse::TimedOut(
+                Uuid::nil(),
+                "canceling statement due to statement timeout".to_string()
+            )
+        );
+
+        assert!(worker.cancel_targets.request(&worker.key).is_some());
+        let cancel = database_error(QUERY_CANCELED, "canceling statement due to user request");
+        assert_eq!(
+            worker.process_db_error(cancel, Uuid::nil(), None),
+            DbResponse::Cancelled(Uuid::nil())
+        );
+    }
+
+    #[test]
+    fn failed_statement_aborts_manual_transaction() {
+        let server = MockServer::default().with_failing("insert into t values ('x')");
+        let rollback = DbRequest::Rollback {
+            server_id: Uuid::nil(),
+            database: "mock".to_string(),
+        };
+        let responses = serve(
+            &server,
+            vec![
+                manual_transactions(),
+                execute("insert into t values ('x')"),
+                rollback,
+            ],
+        );
+
+        assert!(responses
+            .iter()
+            .any(|response| matches!(response, DbResponse::Error(_, message) if message == "syntax error")));
+        assert_eq!(
+            transaction_states(&responses),
+            [
+                TransactionState::Idle,
+                TransactionState::Aborted,
+                TransactionState::Idle
+            ]
+        );
+        assert_eq!(
+            server.log(),
+            ["BEGIN", "insert into t values ('x')", "ROLLBACK"]
+        );
+    }
+
+    #[test]
+    fn script_stops_once_transaction_is_aborted() {
+        let server = MockServer::default().with_failing("insert into t values ('x')");
+        let responses = serve(
+            &server,
+            vec![
+                manual_transactions(),
+                execute("insert into t values (1); insert into t values ('x'); select 3"),
+            ],
+        );
+
+        assert!(responses.iter().any(|response| matches!(
+            response,
+            DbResponse::StatementFailed { position, stopped: true, .. } if position.index == 1
+        )));
+        assert_eq!(
+            server.log(),
+            [
+                "BEGIN",
+                "insert into t values (1)",
+                "insert into t values ('x')"
+            ]
+        );
+    }
+
+    #[test]
+    fn explain_keeps_open_transaction() {
+        let server = MockServer::default();
+        let explain = DbRequest::Explain {
+            server_id: Uuid::nil(),
+            database: "mock".to_string(),
+            query: "delete from t".to_string(),
+            analyze: true,
+        };
+        let responses = serve(
+            &server,
+            vec![
+                manual_transactions(),
+                execute("insert into t values (1)"),
+                explain,
+            ],
+        );
+
+        assert!(responses
+            .iter()
+            .any(|response| matches!(response, DbResponse::Explained { analyzed: true, .. })));
+        assert_eq!(
+            transaction_states(&responses),
+            [TransactionState::Idle, TransactionState::Active]
+        );
+        assert_eq!(
+            server.log(),
+            [
+                "BEGIN",
+                "insert into t values (1)",
+                "SAVEPOINT tisq_explain",
+                "delete from t",
+                "ROLLBACK TO SAVEPOINT tisq_explain",
+                "RELEASE SAVEPOINT tisq_explain",
+            ]
+        );
+    }
+}
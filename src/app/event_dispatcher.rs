@@ -1,4 +1,7 @@
-use std::{sync::{Arc, Mutex}, fmt::Debug};
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
 
 use tuirealm::{
     listener::{ListenerResult, Poll},
@@ -46,7 +49,7 @@ where
         let mut holder = self.holder.lock().unwrap();
         let event = holder.events.pop();
         // if event.is_some() {
-            // println!("EventDispatcherPort::poll: {:?}", event);
+        // println!("EventDispatcherPort::poll: {:?}", event);
         // }
         Ok(event)
     }
@@ -21,6 +21,16 @@ pub(crate) enum TisqKeyboundAction {
     EditorMoveTabLeft,
     EditorMoveTabRight,
     EditorExecute,
+    EditorExecuteStatement,
+    EditorExecuteSelection,
+    EditorToggleSelection,
+    EditorCancelQuery,
+    EditorToggleTransactionMode,
+    EditorCommit,
+    EditorRollback,
+    EditorExplain,
+    EditorExplainAnalyze,
+    EditorShowHistory,
     EditorPaste,
     EditorDeleteWord,
     EditorDeleteNextWord,
@@ -31,10 +41,17 @@ pub(crate) enum TisqKeyboundAction {
     EditorToggleComment,
 
     BrowserAddServer,
+    BrowserEditServer,
+    BrowserCreateGroup,
+    BrowserRenameGroup,
+    BrowserMoveToGroup,
+    BrowserImportServers,
     BrowserDatabaseOpenQueryEditor,
 
     ResultOffsetColumnRight,
     ResultOffsetColumnLeft,
+    ResultNextStatement,
+    ResultPrevStatement,
 }
 
 pub(crate) const GLOBAL_SECTION: &str = "globals";
@@ -71,6 +88,16 @@ impl KeyboundAction for TisqKeyboundAction {
                 &TisqKeyboundAction::EditorMoveTabRight,
                 // &TisqKeyboundAction::EditorBackspace,
                 &TisqKeyboundAction::EditorExecute,
+                &TisqKeyboundAction::EditorExecuteStatement,
+                &TisqKeyboundAction::EditorExecuteSelection,
+                &TisqKeyboundAction::EditorToggleSelection,
+                &TisqKeyboundAction::EditorCancelQuery,
+                &TisqKeyboundAction::EditorToggleTransactionMode,
+                &TisqKeyboundAction::EditorCommit,
+                &TisqKeyboundAction::EditorRollback,
+                &TisqKeyboundAction::EditorExplain,
+                &TisqKeyboundAction::EditorExplainAnalyze,
+                &TisqKeyboundAction::EditorShowHistory,
                 &TisqKeyboundAction::EditorPaste,
                 &TisqKeyboundAction::EditorDeleteWord,
                 &TisqKeyboundAction::EditorDeleteNextWord,
@@ -82,11 +109,18 @@ impl KeyboundAction for TisqKeyboundAction {
             ],
             BROWSER_SECTION => vec![
                 &TisqKeyboundAction::BrowserAddServer,
+                &TisqKeyboundAction::BrowserEditServer,
+                &TisqKeyboundAction::BrowserCreateGroup,
+                &TisqKeyboundAction::BrowserRenameGroup,
+                &TisqKeyboundAction::BrowserMoveToGroup,
+                &TisqKeyboundAction::BrowserImportServers,
                 &TisqKeyboundAction::BrowserDatabaseOpenQueryEditor,
             ],
             QUERY_RESULT_SECTION => vec![
                 &TisqKeyboundAction::ResultOffsetColumnLeft,
                 &TisqKeyboundAction::ResultOffsetColumnRight,
+                &TisqKeyboundAction::ResultNextStatement,
+                &TisqKeyboundAction::ResultPrevStatement,
             ],
             _ => vec![],
         }
@@ -219,6 +253,82 @@ impl KeyboundAction for TisqKeyboundAction {
                 ]
             }
 
+            &TisqKeyboundAction::EditorExecuteStatement => {
+                vec![
+                    KeybindingKeyPress {
+                        key: Key::Char('e'),
+                        modifiers: KeyModifiers::ALT,
+                    },
+                    KeybindingKeyPress {
+                        key: Key::Enter,
+                        modifiers: KeyModifiers::ALT,
+                    },
+                ]
+            }
+
+            &TisqKeyboundAction::EditorExecuteSelection => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('r'),
+                    modifiers: KeyModifiers::ALT,
+                }]
+            }
+
+            &TisqKeyboundAction::EditorToggleSelection => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('m'),
+                    modifiers: KeyModifiers::ALT,
+                }]
+            }
+
+            &TisqKeyboundAction::EditorCancelQuery => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('k'),
+                    modifiers: KeyModifiers::CONTROL,
+                }]
+            }
+
+            &TisqKeyboundAction::EditorToggleTransactionMode => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('t'),
+                    modifiers: KeyModifiers::ALT,
+                }]
+            }
+
+            &TisqKeyboundAction::EditorCommit => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('c'),
+                    modifiers: KeyModifiers::ALT,
+                }]
+            }
+
+            &TisqKeyboundAction::EditorRollback => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('z'),
+                    modifiers: KeyModifiers::ALT,
+                }]
+            }
+
+            &TisqKeyboundAction::EditorExplain => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('x'),
+                    modifiers: KeyModifiers::ALT,
+                }]
+            }
+
+            &TisqKeyboundAction::EditorExplainAnalyze => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('X'),
+                    modifiers: KeyModifiers::ALT | KeyModifiers::SHIFT,
+                }]
+            }
+
+            &TisqKeyboundAction::EditorShowHistory => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('h'),
+                    modifiers: KeyModifiers::ALT,
+                }]
+            }
+
             &TisqKeyboundAction::EditorPaste => {
                 vec![KeybindingKeyPress {
                     key: Key::Char('v'),
@@ -282,6 +392,41 @@ impl KeyboundAction for TisqKeyboundAction {
                 }]
             }
 
+            &TisqKeyboundAction::BrowserEditServer => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('e'),
+                    modifiers: KeyModifiers::NONE,
+                }]
+            }
+
+            &TisqKeyboundAction::BrowserCreateGroup => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('g'),
+                    modifiers: KeyModifiers::NONE,
+                }]
+            }
+
+            &TisqKeyboundAction::BrowserRenameGroup => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('r'),
+                    modifiers: KeyModifiers::NONE,
+                }]
+            }
+
+            &TisqKeyboundAction::BrowserMoveToGroup => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('m'),
+                    modifiers: KeyModifiers::NONE,
+                }]
+            }
+
+            &TisqKeyboundAction::BrowserImportServers => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('i'),
+                    modifiers: KeyModifiers::NONE,
+                }]
+            }
+
             &TisqKeyboundAction::BrowserDatabaseOpenQueryEditor => {
                 vec![KeybindingKeyPress {
                     key: Key::Char('q'),
@@ -303,6 +448,20 @@ impl KeyboundAction for TisqKeyboundAction {
                 }]
             }
 
+            &TisqKeyboundAction::ResultNextStatement => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char(']'),
+                    modifiers: KeyModifiers::NONE,
+                }]
+            }
+
+            &TisqKeyboundAction::ResultPrevStatement => {
+                vec![KeybindingKeyPress {
+                    key: Key::Char('['),
+                    modifiers: KeyModifiers::NONE,
+                }]
+            }
+
             _ => {
                 vec![]
             }
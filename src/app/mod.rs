@@ -8,20 +8,45 @@ mod connection;
 mod event_dispatcher;
 mod keybindings;
 pub mod model;
+mod secrets;
 mod snippets;
 mod spinner_ticking_port;
 mod status;
 pub(crate) mod storage;
 mod user_event;
 
+pub(crate) use connection::discover_servers;
+pub(crate) use connection::notice_layer;
+pub(crate) use connection::placeholder_names;
+pub(crate) use connection::split_url_password;
+pub(crate) use connection::statement_at;
+pub(crate) use connection::BindParameter;
+pub(crate) use connection::CellValue;
+pub(crate) use connection::ColumnInfo;
+pub(crate) use connection::DatabaseKind;
 pub(crate) use connection::DbRequest;
 pub(crate) use connection::DbResponse;
+pub(crate) use connection::ExecutionSummary;
+pub(crate) use connection::FetchLimits;
+pub(crate) use connection::FetchStatus;
+pub(crate) use connection::NoticeSeverity;
+pub(crate) use connection::Notification;
+pub(crate) use connection::ObjectLevel;
+pub(crate) use connection::ServerMessage;
+pub(crate) use connection::ServerSettings;
+pub(crate) use connection::SessionTimeouts;
+pub(crate) use connection::SqlDialect;
+pub(crate) use connection::TransactionState;
+pub(crate) use connection::TransactionStatus;
 pub(crate) use keybindings::KeySubClause;
 pub(crate) use keybindings::KeybindingKeyPress;
 pub(crate) use keybindings::KeybindingsConfig;
 pub(crate) use keybindings::SectionKeybindings;
 pub(crate) use keybindings::TisqKeyboundAction;
 pub(crate) use model::EditorId;
+pub(crate) use secrets::ask_for_password;
+pub(crate) use secrets::PasswordStorage;
+pub(crate) use secrets::SecretPrompt;
 pub(crate) use snippets::Snippet;
 pub(crate) use snippets::SnippetsConfig;
 pub(crate) use user_event::TisqEvent;
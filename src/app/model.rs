@@ -6,19 +6,29 @@ use crate::app::event_dispatcher::EventDispatcherPort;
 use crate::app::keybindings::{BROWSER_SECTION, GLOBAL_SECTION, QUERY_RESULT_SECTION};
 use crate::app::spinner_ticking_port::SpinnerTickingPort;
 use crate::components::{
-    AddServerForm, BrowserTree, CommandLine, DbResponseStatusListener, Editor, EditorTabs,
-    ErrorResult, ExecuteResultTable, FormSubmitListener, GlobalListener, InputText, SentTree,
-    SettingsForm, SnippetsTable, ACTIVE_TAB_INDEX,
+    AddServerForm, AddServerFormStatus, BindParametersForm, BrowsedItem, BrowserTree, ChoiceInput,
+    CommandLine, DbResponseStatusListener, Editor, EditorTabs, ErrorResult, ExecuteResultTable,
+    ExplainPlanTree, FormSubmitListener, GlobalListener, GroupAction, GroupPrompt, HistoryTable,
+    InputText, NotificationsTable, PasswordPrompt, SentTree, ServerMessagesTable, SettingsForm,
+    SnippetsTable, TestConnectionButton, ACTIVE_TAB_INDEX, PASSWORD_STORAGES, SSL_MODES,
 };
 
 use super::config::TisqConfig;
-use super::connection::{self, DbRequest, DbResponse};
+use super::connection::{self, DbRequest, DbResponse, ServerSettings};
 use super::keybindings::{Keybindings, EDITOR_SECTION};
-use super::snippets::{self, standard_postgres_snippets, Snippet};
+use super::secrets::{
+    has_plain_password, keep_encrypted_password, take_password, PasswordStorage, SecretPrompt,
+    Secrets,
+};
+use super::snippets::{standard_snippets, Snippet, SnippetDatabase};
 use super::status::AppStatus;
-use super::storage::{NewServer, Storage, StoredServer};
-use super::{storage, Id, Msg, SectionKeybindings, TisqEvent, TisqKeyboundAction};
+use super::storage::{HistoryEntry, NewServer, Storage, StoredGroup, StoredServer};
+use super::{
+    discover_servers, placeholder_names, storage, BindParameter, DatabaseKind, Id, Msg,
+    Notification, SectionKeybindings, TisqEvent, TisqKeyboundAction,
+};
 use ordered_hash_map::OrderedHashMap;
+use sqlx::types::time::OffsetDateTime;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, Sender};
@@ -27,7 +37,7 @@ use std::time::Duration;
 use std::{thread, vec};
 use tui_realm_treeview::{Node, Tree};
 
-use tuirealm::props::{PropPayload, PropValue, TextSpan};
+use tuirealm::props::{Color, PropPayload, PropValue, TextSpan};
 use tuirealm::terminal::TerminalBridge;
 use tuirealm::tui::layout::{Constraint, Direction, Layout};
 use tuirealm::{
@@ -41,6 +51,7 @@ pub(crate) type TisqApplication = Application<Id, Msg, TisqEvent>;
 enum ExecuteResultState {
     FetchedTable,
     Error,
+    Plan,
 }
 
 pub struct Model {
@@ -67,9 +78,23 @@ pub struct Model {
     shown_editor: Option<EditorId>,
     // connection_manager_rx: Receiver<DbResponse>,
     // connections: HashMap<Uuid, Connection>,
-    snippets_library: HashMap<String, Snippet>,
+    snippets_library: HashMap<SnippetDatabase, HashMap<String, Snippet>>,
     showing_snippets: bool,
+    // editor for which query history is shown
+    showing_history: Option<EditorId>,
+    // database, channels of which are listened, notifications are shown in place of result
+    showing_notifications: Option<EditorId>,
+    bind_parameters_form: Option<BindParametersForm>,
+    // last values of placeholders used in each editor
+    bind_parameters: HashMap<EditorId, Vec<BindParameter>>,
+    // asks for password, which is needed to connect
+    password_prompt: Option<PasswordPrompt>,
+    secrets: Secrets,
+    // asks for name or path of the group in browser
+    group_prompt: Option<GroupPrompt>,
     execute_result_state: ExecuteResultState,
+    // messages of server are shown next to the result, if there were any
+    showing_server_messages: bool,
 
     showing_command_line: bool,
 
@@ -103,6 +128,20 @@ struct EditorMetadata {
     name: String,
 }
 
+/// Why server could not be connected to yet
+enum SettingsError {
+    // password or master passphrase has to be asked first
+    Prompt(SecretPrompt),
+    // stored properties do not make connection settings
+    Invalid(String),
+}
+
+impl From<SecretPrompt> for SettingsError {
+    fn from(prompt: SecretPrompt) -> Self {
+        SettingsError::Prompt(prompt)
+    }
+}
+
 impl Model {
     pub(crate) fn new(files_root: &PathBuf, config: TisqConfig) -> Self {
         let storage = storage::Storage::open(files_root).unwrap();
@@ -112,8 +151,15 @@ impl Model {
         let (tx, rx): (Sender<DbRequest>, Receiver<DbRequest>) = mpsc::channel();
         let (back_tx, back_rx): (Sender<DbResponse>, Receiver<DbResponse>) = mpsc::channel();
 
-        thread::spawn(|| {
-            let connections_manager = connection::ConnectionsManager::new(back_tx);
+        let fetch_limits = config.results.unwrap_or_default().fetch_limits();
+        let stop_on_error = config
+            .scripts
+            .and_then(|scripts| scripts.stop_on_error)
+            .unwrap_or(true);
+
+        thread::spawn(move || {
+            let connections_manager =
+                connection::ConnectionsManager::new(back_tx, fetch_limits, stop_on_error);
             connections_manager.requests_loop(rx);
         });
 
@@ -123,6 +169,10 @@ impl Model {
         thread::spawn(move || loop {
             let response = back_rx.lock().unwrap().recv();
             match response {
+                Ok(DbResponse::NotificationReceived(notification)) => {
+                    back_dispatcher
+                        .dispatch(Event::User(TisqEvent::NotificationReceived(notification)));
+                }
                 Ok(response) => {
                     back_dispatcher.dispatch(Event::User(TisqEvent::DbResponse(response)));
                 }
@@ -136,16 +186,22 @@ impl Model {
 
         let spinner_ticking = SpinnerTickingPort::new();
 
-        let mut snippets_library = standard_postgres_snippets();
-
-        if let Some(mut snippets) = config.snippets {
-            snippets
-                .remove(&snippets::SnippetDatabase::Postgres)
-                .map(|snippets| {
-                    snippets.into_iter().for_each(|snippet| {
-                        snippets_library.insert(snippet.shortcut.clone(), snippet);
-                    });
+        let mut snippets_library: HashMap<SnippetDatabase, HashMap<String, Snippet>> = [
+            SnippetDatabase::Postgres,
+            SnippetDatabase::Sqlite,
+            SnippetDatabase::MySql,
+        ]
+        .into_iter()
+        .map(|database| (database, standard_snippets(database)))
+        .collect();
+
+        if let Some(snippets) = config.snippets {
+            for (database, snippets) in snippets {
+                let library = snippets_library.entry(database).or_default();
+                snippets.into_iter().for_each(|snippet| {
+                    library.insert(snippet.shortcut.clone(), snippet);
                 });
+            }
         }
 
         let enabled_showing_pressed_key =
@@ -177,9 +233,18 @@ impl Model {
             // connection_manager_rx: back_rx,
             // connections: HashMap::new(),
             execute_result_state: ExecuteResultState::FetchedTable,
+            showing_server_messages: false,
 
             showing_snippets: false,
             snippets_library,
+            showing_history: None,
+            showing_notifications: None,
+
+            bind_parameters_form: None,
+            bind_parameters: HashMap::new(),
+            password_prompt: None,
+            secrets: Secrets::default(),
+            group_prompt: None,
 
             keybindings,
             spinner_ticking,
@@ -200,9 +265,12 @@ impl Model {
         );
         AppStatus::mount_spinner(&mut model.app);
         AppStatus::mount_span(&mut model.app);
+        AppStatus::mount_transaction(&mut model.app);
+        AppStatus::mount_error(&mut model.app);
         AppStatus::mount_pressed_key(&mut model.app, enabled_showing_pressed_key);
         SettingsForm::mount(&mut model.app, enabled_showing_pressed_key);
         model.mount_command_line();
+        model.offer_encrypting_plain_passwords();
         model
     }
 
@@ -234,7 +302,8 @@ impl Model {
 
                 let status_line = cli_content_and_status[2];
 
-                self.app_status.view(status_line, f, &mut self.app);
+                self.app_status
+                    .view(status_line, f, &mut self.app, self.shown_editor.as_ref());
 
                 let content = cli_content_and_status[1];
 
@@ -267,7 +336,11 @@ impl Model {
                 // self.app.view(&Id::Label, f, chunks[3]);
                 self.app.view(&Id::Tree, f, left);
 
-                if self.showing_settings {
+                if let Some(prompt) = &self.password_prompt {
+                    prompt.view(right, &mut self.app, f);
+                } else if let Some(prompt) = &self.group_prompt {
+                    prompt.view(right, &mut self.app, f);
+                } else if self.showing_settings {
                     self.settings_form.view(right, &mut self.app, f);
                 } else if self.adding_server {
                     // self.app.view(&Id::AddServerForm, f, right);
@@ -290,15 +363,39 @@ impl Model {
                     if let Some(id) = active_editor_id {
                         self.app.view(&id, f, chunks[1]);
                     }
-                    if self.showing_snippets {
+                    if let Some(form) = &self.bind_parameters_form {
+                        form.view(chunks[2], &mut self.app, f);
+                    } else if self.showing_snippets {
                         self.app.view(&Id::SnippetsTable, f, chunks[2]);
+                    } else if self.showing_history.is_some() {
+                        self.app.view(&Id::HistoryTable, f, chunks[2]);
+                    } else if self.showing_notifications.is_some() {
+                        self.app.view(&Id::NotificationsTable, f, chunks[2]);
                     } else {
+                        let mut result_area = chunks[2];
+                        if self.showing_server_messages {
+                            let result_chunks = Layout::default()
+                                .direction(Direction::Horizontal)
+                                .constraints(
+                                    [
+                                        Constraint::Percentage(65), // Result
+                                        Constraint::Min(0),         // Messages
+                                    ]
+                                    .as_ref(),
+                                )
+                                .split(chunks[2]);
+                            result_area = result_chunks[0];
+                            self.app.view(&Id::ServerMessagesTable, f, result_chunks[1]);
+                        }
                         match self.execute_result_state {
                             ExecuteResultState::Error => {
-                                self.app.view(&Id::ExecuteErrorResult, f, chunks[2]);
+                                self.app.view(&Id::ExecuteErrorResult, f, result_area);
                             }
                             ExecuteResultState::FetchedTable => {
-                                self.app.view(&Id::QueryResultTable, f, chunks[2]);
+                                self.app.view(&Id::QueryResultTable, f, result_area);
+                            }
+                            ExecuteResultState::Plan => {
+                                self.app.view(&Id::ExplainPlanTree, f, result_area);
                             }
                         }
                     }
@@ -309,8 +406,31 @@ impl Model {
 
     fn browser_tree(storage: &storage::Storage) -> eyre::Result<Node> {
         let mut node: Node = Node::new("root".to_string(), "servers".to_string());
-        storage.read_servers()?.into_iter().for_each(|server| {
-            let mut server_node: Node = Node::new(format!("server:{}", server.id), server.name);
+        let mut groups = storage.read_groups()?;
+        groups.sort_by_key(|group| group.name.to_lowercase());
+        let mut servers = storage.read_servers()?;
+        servers.sort_by_key(|server| server.name.to_lowercase());
+        Self::add_group_nodes(&mut node, None, &groups, &servers);
+        Ok(node)
+    }
+
+    /// Adds nodes of groups and servers inside of the group, groups go first
+    fn add_group_nodes(
+        node: &mut Node,
+        group_id: Option<Uuid>,
+        groups: &[StoredGroup],
+        servers: &[StoredServer],
+    ) {
+        for group in groups.iter().filter(|group| group.parent_id == group_id) {
+            let mut group_node: Node =
+                Node::new(format!("group:{}", group.id), format!("{}/", group.name));
+            Self::add_group_nodes(&mut group_node, Some(group.id), groups, servers);
+            node.add_child(group_node);
+        }
+
+        for server in servers.iter().filter(|server| server.group_id == group_id) {
+            let mut server_node: Node =
+                Node::new(format!("server:{}", server.id), server.name.clone());
 
             // dummy is created to make the server node expandable
             // it is a workaround for the limitation of the treeview component
@@ -321,8 +441,7 @@ impl Model {
             server_node.add_child(dummy);
 
             node.add_child(server_node);
-        });
-        Ok(node)
+        }
     }
 
     fn update_browser(&mut self) {
@@ -359,8 +478,33 @@ impl Model {
             .is_ok());
     }
 
+    /// Kind of database the editor is connected to, Postgres if server is not known
+    fn editor_database_kind(&self, editor_id: &EditorId) -> DatabaseKind {
+        self.storage
+            .get_server(editor_id.server_id)
+            .ok()
+            .flatten()
+            .and_then(|server| ServerSettings::from_properties(&server.connection_properties))
+            .map_or(DatabaseKind::Postgres, |settings| {
+                DatabaseKind::from_url(&settings.url)
+            })
+    }
+
+    /// Snippets for the kind of database the editor is connected to
+    fn editor_snippets(&self, editor_id: &EditorId) -> Option<&HashMap<String, Snippet>> {
+        let server = self.storage.get_server(editor_id.server_id).ok()??;
+        let url = ServerSettings::from_properties(&server.connection_properties)?.url;
+        self.snippets_library
+            .get(&SnippetDatabase::from(DatabaseKind::from_url(&url)))
+    }
+
     fn mount_snippets_table(&mut self) {
-        let snippets = self.snippets_library.values().collect();
+        let snippets = self
+            .shown_editor
+            .as_ref()
+            .and_then(|editor_id| self.editor_snippets(editor_id))
+            .map(|snippets| snippets.values().collect())
+            .unwrap_or_default();
         assert!(self
             .app
             .mount(
@@ -372,11 +516,12 @@ impl Model {
     }
 
     fn mount_editor(&mut self, id: EditorId, keybindings: SectionKeybindings<TisqKeyboundAction>) {
+        let dialect = self.editor_database_kind(&id).capabilities().dialect;
         assert!(self
             .app
             .mount(
                 Id::Editor(id.clone()),
-                Box::new(Editor::new(id.clone(), keybindings)),
+                Box::new(Editor::new(id.clone(), keybindings, dialect)),
                 vec![
                     Sub::new(
                         SubEventClause::User(TisqEvent::EditorContentAdd(
@@ -414,7 +559,7 @@ impl Model {
                     Sub::new(
                         SubEventClause::User(TisqEvent::DbResponse(
                             // the content does not matter due to the PartialEq implementation
-                            DbResponse::Executed(Uuid::new_v4(), vec![], vec![]) // <- this is dummy
+                            DbResponse::None // <- this is dummy
                         )),
                         SubClause::Always
                     ),
@@ -445,6 +590,34 @@ impl Model {
                 ]
             )
             .is_ok());
+
+        assert!(app
+            .mount(
+                Id::ServerMessagesTable,
+                Box::new(ServerMessagesTable::default()),
+                vec![Sub::new(
+                    SubEventClause::User(TisqEvent::DbResponse(
+                        // the content does not matter due to the PartialEq implementation
+                        DbResponse::None // <- this is dummy
+                    )),
+                    SubClause::Always
+                )]
+            )
+            .is_ok());
+
+        assert!(app
+            .mount(
+                Id::ExplainPlanTree,
+                Box::new(ExplainPlanTree::default()),
+                vec![Sub::new(
+                    SubEventClause::User(TisqEvent::DbResponse(
+                        // the content does not matter due to the PartialEq implementation
+                        DbResponse::None // <- this is dummy
+                    )),
+                    SubClause::Always
+                )]
+            )
+            .is_ok());
     }
 
     fn mount_server_add_form(app: &mut TisqApplication) {
@@ -458,10 +631,111 @@ impl Model {
             )
             .is_ok());
 
+        assert!(app
+            .mount(
+                Id::ServerHostInput,
+                Box::new(InputText::new("Host", "")),
+                vec![]
+            )
+            .is_ok());
+
+        assert!(app
+            .mount(
+                Id::ServerPortInput,
+                Box::new(InputText::new("Port", "")),
+                vec![]
+            )
+            .is_ok());
+
+        assert!(app
+            .mount(
+                Id::ServerUserInput,
+                Box::new(InputText::new("User", "")),
+                vec![]
+            )
+            .is_ok());
+
+        assert!(app
+            .mount(
+                Id::ServerPasswordInput,
+                Box::new(InputText::new("Password", "").masked()),
+                vec![]
+            )
+            .is_ok());
+
+        assert!(app
+            .mount(
+                Id::PasswordStorageInput,
+                Box::new(ChoiceInput::new("Password Storage", &PASSWORD_STORAGES)),
+                vec![]
+            )
+            .is_ok());
+
+        assert!(app
+            .mount(
+                Id::DefaultDatabaseInput,
+                Box::new(InputText::new("Default Database", "")),
+                vec![]
+            )
+            .is_ok());
+
+        assert!(app
+            .mount(
+                Id::SslModeInput,
+                Box::new(ChoiceInput::new("SSL Mode", &SSL_MODES)),
+                vec![]
+            )
+            .is_ok());
+
+        // MySQL and SQLite servers are only added with url
         assert!(app
             .mount(
                 Id::ConnectionUrlInput,
-                Box::new(InputText::new("Connection URL", "")),
+                Box::new(InputText::new(
+                    "Connection URL (instead of fields above, e.g. for MySQL or SQLite)",
+                    ""
+                )),
+                vec![]
+            )
+            .is_ok());
+
+        // timeouts are optional, empty means server default
+        assert!(app
+            .mount(
+                Id::StatementTimeoutInput,
+                Box::new(InputText::new("Statement Timeout (e.g. 30s)", "")),
+                vec![]
+            )
+            .is_ok());
+
+        assert!(app
+            .mount(
+                Id::LockTimeoutInput,
+                Box::new(InputText::new("Lock Timeout", "")),
+                vec![]
+            )
+            .is_ok());
+
+        assert!(app
+            .mount(
+                Id::IdleTimeoutInput,
+                Box::new(InputText::new("Idle In Transaction Session Timeout", "")),
+                vec![]
+            )
+            .is_ok());
+
+        assert!(app
+            .mount(
+                Id::TestConnectionButton,
+                Box::new(TestConnectionButton::default()),
+                vec![]
+            )
+            .is_ok());
+
+        assert!(app
+            .mount(
+                Id::AddServerFormStatus,
+                Box::new(AddServerFormStatus::default()),
                 vec![]
             )
             .is_ok());
@@ -469,7 +743,19 @@ impl Model {
 
     fn unmount_server_add_form(&mut self) {
         assert!(self.app.umount(&Id::ServerNameInput).is_ok());
+        assert!(self.app.umount(&Id::ServerHostInput).is_ok());
+        assert!(self.app.umount(&Id::ServerPortInput).is_ok());
+        assert!(self.app.umount(&Id::ServerUserInput).is_ok());
+        assert!(self.app.umount(&Id::ServerPasswordInput).is_ok());
+        assert!(self.app.umount(&Id::PasswordStorageInput).is_ok());
+        assert!(self.app.umount(&Id::DefaultDatabaseInput).is_ok());
+        assert!(self.app.umount(&Id::SslModeInput).is_ok());
         assert!(self.app.umount(&Id::ConnectionUrlInput).is_ok());
+        assert!(self.app.umount(&Id::StatementTimeoutInput).is_ok());
+        assert!(self.app.umount(&Id::LockTimeoutInput).is_ok());
+        assert!(self.app.umount(&Id::IdleTimeoutInput).is_ok());
+        assert!(self.app.umount(&Id::TestConnectionButton).is_ok());
+        assert!(self.app.umount(&Id::AddServerFormStatus).is_ok());
     }
 
     // fn mount_add_server_form(app: &mut TisqApplication) {
@@ -597,8 +883,8 @@ impl Model {
         assert!(app
             .mount(
                 Id::FormSubmitListener,
-                Box::new(FormSubmitListener::default()),
-                vec![FormSubmitListener::get_subscription()]
+                Box::new(FormSubmitListener::new(Id::AddServerForm)),
+                vec![FormSubmitListener::get_subscription(Id::ConnectionUrlInput)]
             )
             .is_ok());
 
@@ -810,38 +1096,316 @@ impl Model {
         Ok(())
     }
 
-    fn connect_to_server(&mut self, server: &StoredServer) {
-        let connection_url = server
-            .connection_properties
-            .get("url")
-            .expect("connection url not found")
-            .clone();
-        self.send_db_request(DbRequest::ConnectToServer(server.id, connection_url))
+    /// Settings to connect to the server, None if its password is not known yet,
+    /// then it is asked and `retry` is sent again once it is entered
+    fn server_settings(&mut self, server: &StoredServer, retry: Msg) -> Option<ServerSettings> {
+        match self.try_server_settings(server) {
+            Ok(settings) => Some(settings),
+            Err(SettingsError::Prompt(prompt)) => {
+                self.show_password_prompt(prompt, &server.name, retry);
+                None
+            }
+            Err(SettingsError::Invalid(message)) => {
+                self.app_status.show_error(&message);
+                None
+            }
+        }
+    }
+
+    /// Settings to connect to the server, error with what has to be asked first,
+    /// if its password is not known yet
+    fn try_server_settings(
+        &mut self,
+        server: &StoredServer,
+    ) -> Result<ServerSettings, SettingsError> {
+        let mut properties = server.connection_properties.clone();
+        if let Some(password) = self.secrets.password(server)? {
+            properties.insert("password".to_string(), password);
+        }
+        ServerSettings::from_properties(&properties).ok_or_else(|| {
+            SettingsError::Invalid(format!("Neither url nor host is set for {}", server.name))
+        })
+    }
+
+    /// Server with given id, failure to read it is shown in status bar
+    fn stored_server(&mut self, server_id: Uuid) -> Option<StoredServer> {
+        match self.storage.get_server(server_id) {
+            Ok(Some(server)) => Some(server),
+            Ok(None) => {
+                self.app_status.show_error("Server is not found");
+                None
+            }
+            Err(e) => {
+                self.app_status
+                    .show_error(&format!("Failed to read server: {}", e));
+                None
+            }
+        }
+    }
+
+    /// Group with given id, failure to read it is shown in status bar
+    fn stored_group(&mut self, group_id: Uuid) -> Option<StoredGroup> {
+        match self.storage.get_group(group_id) {
+            Ok(Some(group)) => Some(group),
+            Ok(None) => {
+                self.app_status.show_error("Group is not found");
+                None
+            }
+            Err(e) => {
+                self.app_status
+                    .show_error(&format!("Failed to read group: {}", e));
+                None
+            }
+        }
+    }
+
+    fn show_password_prompt(&mut self, prompt: SecretPrompt, server_name: &str, retry: Msg) {
+        // the other prompt is answered first
+        if self.password_prompt.is_some() {
+            return;
+        }
+        let title = match prompt {
+            SecretPrompt::ServerPassword(_) => format!("Password of {}", server_name),
+            SecretPrompt::MasterPassphrase => match self.storage.get_master_key() {
+                Ok(Some(_)) => "Master passphrase to decrypt stored passwords".to_string(),
+                _ => "New master passphrase to encrypt stored passwords".to_string(),
+            },
+        };
+        self.show_prompt_with_title(prompt, &title, retry);
+    }
+
+    /// Asks for master passphrase to encrypt passwords stored as is by previous versions,
+    /// if prompt is cancelled, they are kept and still used to connect
+    fn offer_encrypting_plain_passwords(&mut self) {
+        let count = match self.storage.read_servers() {
+            Ok(servers) => servers
+                .iter()
+                .filter(|server| has_plain_password(&server.connection_properties))
+                .count(),
+            Err(e) => {
+                tracing::error!("servers could not be read: {}", e);
+                return;
+            }
+        };
+        if count == 0 {
+            return;
+        }
+        let passphrase = match self.storage.get_master_key() {
+            Ok(Some(_)) => "Master passphrase",
+            _ => "New master passphrase",
+        };
+        let title = format!(
+            "{} to encrypt {} passwords stored as plain text, Esc to keep them",
+            passphrase, count
+        );
+        self.show_prompt_with_title(
+            SecretPrompt::MasterPassphrase,
+            &title,
+            Msg::EncryptPlainPasswords,
+        );
+    }
+
+    fn show_prompt_with_title(&mut self, prompt: SecretPrompt, title: &str, retry: Msg) {
+        let prompt = PasswordPrompt::mount(&mut self.app, prompt, title, retry).unwrap();
+        self.password_prompt = Some(prompt);
+    }
+
+    fn close_password_prompt(&mut self) -> Option<PasswordPrompt> {
+        let prompt = self.password_prompt.take()?;
+        prompt.unmount(&mut self.app).unwrap();
+        Some(prompt)
+    }
+
+    fn show_group_prompt(&mut self, action: GroupAction, title: &str, value: &str) {
+        self.close_group_prompt();
+        let prompt = GroupPrompt::mount(&mut self.app, action, title, value).unwrap();
+        self.group_prompt = Some(prompt);
+    }
+
+    fn close_group_prompt(&mut self) -> Option<GroupPrompt> {
+        let prompt = self.group_prompt.take()?;
+        prompt.unmount(&mut self.app).unwrap();
+        Some(prompt)
+    }
+
+    /// Names of the group and groups it is inside of, separated with `/`,
+    /// empty for the top level, failure to read groups is shown in status bar
+    fn group_path(&mut self, group_id: Option<Uuid>) -> Option<String> {
+        let groups = match self.storage.read_groups() {
+            Ok(groups) => groups,
+            Err(e) => {
+                self.app_status
+                    .show_error(&format!("Failed to read groups: {}", e));
+                return None;
+            }
+        };
+        let mut names = vec![];
+        let mut current_id = group_id;
+        while let Some(group) = current_id.and_then(|id| groups.iter().find(|g| g.id == id)) {
+            names.push(group.name.as_str());
+            current_id = group.parent_id;
+        }
+        names.reverse();
+        Some(names.join("/"))
+    }
+
+    /// Group of the server or group itself, None for the top level
+    fn item_group(&self, item: BrowsedItem) -> Option<Uuid> {
+        match item {
+            BrowsedItem::Group(group_id) => Some(group_id),
+            BrowsedItem::Server(server_id) => self
+                .storage
+                .get_server(server_id)
+                .ok()
+                .flatten()
+                .and_then(|server| server.group_id),
+        }
+    }
+
+    fn connect_to_server(&mut self, server_id: Uuid, settings: ServerSettings) {
+        self.send_db_request(DbRequest::ConnectToServer(server_id, settings))
             .unwrap();
     }
 
-    fn connect_to_database(&mut self, server: &StoredServer, database: String) {
-        let connection_url = server
-            .connection_properties
-            .get("url")
-            .expect("connection url not found")
-            .clone();
-        self.send_db_request(DbRequest::ConnectToDatabase(
-            server.id,
-            database,
-            connection_url,
-        ))
+    fn connect_to_database(&mut self, server_id: Uuid, database: String, settings: ServerSettings) {
+        self.send_db_request(DbRequest::ConnectToDatabase(server_id, database, settings))
+            .unwrap();
+    }
+
+    /// Connects again to the server and database of the editor,
+    /// returns None if password has to be asked first
+    fn reconnect(&mut self, editor_id: &EditorId, retry: Msg) -> Option<()> {
+        let server = self.stored_server(editor_id.server_id)?;
+        let settings = self.server_settings(&server, retry)?;
+        self.connect_to_server(server.id, settings.clone());
+        self.connect_to_database(server.id, editor_id.database.clone(), settings);
+        Some(())
+    }
+
+    /// Drops connections of the changed server and connects again to databases of its editors,
+    /// if password has to be asked, it is done once some editor is used
+    fn reconnect_changed_server(&mut self, server_id: Uuid) {
+        let server = match self.stored_server(server_id) {
+            Some(server) => server,
+            None => return,
+        };
+        let listens_server = self
+            .showing_notifications
+            .as_ref()
+            .is_some_and(|editor_id| editor_id.server_id == server_id);
+        if listens_server {
+            self.close_notifications();
+        }
+        self.send_db_request(DbRequest::DisconnectServer(server_id))
+            .unwrap();
+
+        let editor_ids: Vec<EditorId> = self
+            .query_editors
+            .iter()
+            .map(|(editor_id, _)| editor_id.clone())
+            .filter(|editor_id| editor_id.server_id == server_id)
+            .collect();
+        for editor_id in &editor_ids {
+            if let Some(metadata) = self.query_editors.get_mut(editor_id) {
+                metadata.name = server.name.clone();
+            }
+        }
+        self.update_editor_tabs();
+
+        if editor_ids.is_empty() {
+            return;
+        }
+        match self.try_server_settings(&server) {
+            Ok(settings) => {
+                self.connect_to_server(server_id, settings.clone());
+                for editor_id in editor_ids {
+                    self.connect_to_database(server_id, editor_id.database, settings.clone());
+                }
+            }
+            Err(SettingsError::Invalid(message)) => self.app_status.show_error(&message),
+            Err(SettingsError::Prompt(_)) => {}
+        }
+    }
+
+    /// Component shown under the editor, which could get focus
+    fn result_panel(&self) -> Id {
+        match self.execute_result_state {
+            ExecuteResultState::Plan => Id::ExplainPlanTree,
+            _ => Id::QueryResultTable,
+        }
+    }
+
+    fn close_history(&mut self) -> Option<EditorId> {
+        let editor_id = self.showing_history.take()?;
+        self.app.umount(&Id::HistoryTable).unwrap();
+        self.app.active(&Id::Editor(editor_id.clone())).unwrap();
+        Some(editor_id)
+    }
+
+    /// Shows notifications panel for the database and listens to channels
+    /// in addition to already listened ones, other database stops listening
+    fn listen(&mut self, editor_id: EditorId, channels: Vec<String>) {
+        if self.showing_notifications.as_ref() != Some(&editor_id) {
+            self.close_notifications();
+            self.app
+                .mount(
+                    Id::NotificationsTable,
+                    Box::new(NotificationsTable::new(
+                        editor_id.clone(),
+                        editor_id.database.clone(),
+                    )),
+                    vec![
+                        Sub::new(
+                            SubEventClause::User(TisqEvent::DbResponse(DbResponse::None)),
+                            SubClause::Always,
+                        ),
+                        Sub::new(
+                            SubEventClause::User(TisqEvent::NotificationReceived(
+                                // the content does not matter due to the PartialEq implementation
+                                Notification {
+                                    server_id: Uuid::nil(),
+                                    database: String::new(),
+                                    channel: String::new(),
+                                    payload: String::new(),
+                                    process_id: 0,
+                                    received_at: OffsetDateTime::UNIX_EPOCH,
+                                },
+                            )),
+                            SubClause::Always,
+                        ),
+                    ],
+                )
+                .unwrap();
+        }
+        self.send_db_request(DbRequest::Listen {
+            server_id: editor_id.server_id,
+            database: editor_id.database.clone(),
+            channels,
+        })
         .unwrap();
+        self.app.active(&Id::NotificationsTable).unwrap();
+        self.showing_notifications = Some(editor_id);
     }
 
-    fn reconnect(&mut self, editor_id: &EditorId) {
-        let server = self
-            .storage
-            .get_server(editor_id.server_id)
-            .unwrap()
-            .unwrap(); // TODO: display error properly
-        self.connect_to_server(&server);
-        self.connect_to_database(&server, editor_id.database.clone());
+    fn close_notifications(&mut self) -> Option<EditorId> {
+        let editor_id = self.showing_notifications.take()?;
+        self.app.umount(&Id::NotificationsTable).unwrap();
+        self.send_db_request(DbRequest::Unlisten {
+            server_id: editor_id.server_id,
+            database: editor_id.database.clone(),
+        })
+        .unwrap();
+        self.app.active(&Id::Editor(editor_id.clone())).unwrap();
+        Some(editor_id)
+    }
+
+    fn close_bind_parameters_form(&mut self) -> Option<BindParametersForm> {
+        let form = self.bind_parameters_form.take()?;
+        form.unmount(&mut self.app).unwrap();
+        self.app
+            .active(&Id::Editor(form.editor_id.clone()))
+            .unwrap();
+        Some(form)
     }
 
     fn show_settings(&mut self) {
@@ -849,6 +1413,24 @@ impl Model {
         self.showing_settings = true;
     }
 
+    /// Connects with properties entered in the form without adding the server,
+    /// outcome is shown in the form once it is known
+    fn test_server_connection(&mut self) {
+        let settings = self
+            .add_server_form
+            .connection_properties(&self.app)
+            .map(|properties| ServerSettings::from_properties(&properties));
+        match settings {
+            Ok(Some(settings)) => {
+                AddServerForm::show_status(&mut self.app, "Testing connection...", Color::Yellow);
+                self.send_db_request(DbRequest::TestConnection(settings))
+                    .unwrap();
+            }
+            Ok(None) => {}
+            Err(message) => AddServerForm::show_status(&mut self.app, &message, Color::Red),
+        }
+    }
+
     fn finish_adding_server(&mut self) {
         self.app.active(&Id::Tree).unwrap();
         self.unmount_server_add_form();
@@ -878,11 +1460,25 @@ impl Update<Msg> for Model {
                     None
                 }
                 Msg::ExecuteCommand(cmd) => {
-                    match cmd.as_str() {
-                        "settings" => {
+                    match cmd.split_whitespace().collect::<Vec<_>>().as_slice() {
+                        ["settings"] => {
                             self.show_settings();
                             self.exit_command_line();
                         }
+                        // channels of the database shown in editor
+                        ["listen", channels @ ..] if !channels.is_empty() => {
+                            if let Some(editor_id) = self.shown_editor.clone() {
+                                self.exit_command_line();
+                                let channels = channels.iter().map(|x| x.to_string()).collect();
+                                self.listen(editor_id, channels);
+                            }
+                        }
+                        ["unlisten"] => {
+                            self.exit_command_line();
+                            if self.close_notifications().is_none() {
+                                self.app.active(&Id::Tree).unwrap();
+                            }
+                        }
                         _ => (),
                     };
                     None
@@ -900,10 +1496,25 @@ impl Update<Msg> for Model {
                 }
                 Msg::TriggerRedraw => None, // this message does nothing except to trigger a redraw, which all messages do
                 Msg::Cancel => {
-                    if self.showing_snippets {
+                    if self.password_prompt.is_some() {
+                        self.close_password_prompt();
+                        None
+                    } else if self.group_prompt.is_some() {
+                        self.close_group_prompt();
+                        None
+                    } else if self.bind_parameters_form.is_some() {
+                        self.close_bind_parameters_form();
+                        None
+                    } else if self.showing_snippets {
                         self.showing_snippets = false;
                         self.app.umount(&Id::SnippetsTable).unwrap();
                         None
+                    } else if self.showing_history.is_some() {
+                        self.close_history();
+                        None
+                    } else if self.showing_notifications.is_some() {
+                        self.close_notifications();
+                        None
                     } else if self.showing_command_line {
                         self.exit_command_line();
                         self.app.active(&Id::Tree).unwrap(); // TODO: activate what was active before?
@@ -941,11 +1552,15 @@ impl Update<Msg> for Model {
                     remove_input,
                 } => {
                     tracing::debug!("trying to expand text: {}", text);
-                    if let Some(snippet) = self.snippets_library.get(&text) {
+                    let snippet = self
+                        .editor_snippets(&editor_id)
+                        .and_then(|snippets| snippets.get(&text))
+                        .map(|snippet| snippet.query.clone());
+                    if let Some(content) = snippet {
                         self.event_dispatcher_port.dispatch(Event::User(
                             TisqEvent::EditorSnippetResolve {
                                 editor_id,
-                                content: snippet.query.clone(),
+                                content,
                                 remove_input,
                             },
                         ));
@@ -954,47 +1569,16 @@ impl Update<Msg> for Model {
                         Some(Msg::ShowSnippets)
                     }
                 }
-                Msg::OpenTable {
+                Msg::OpenObject {
                     server_id,
-                    database,
-                    schema,
-                    table,
+                    path,
                     retries,
                 } => {
-                    let server = self.storage.get_server(server_id).unwrap().unwrap();
-                    self.send_db_request(DbRequest::ListColumns {
-                        server_id: server.id,
-                        database,
-                        schema,
-                        table,
-                        retries,
-                    })
-                    .unwrap();
-                    None
-                }
-                Msg::OpenSchema {
-                    server_id,
-                    database,
-                    schema,
-                    retries,
-                } => {
-                    let server = self.storage.get_server(server_id).unwrap().unwrap();
-
-                    self.send_db_request(DbRequest::ListTables {
-                        server_id: server.id,
-                        database,
-                        schema,
-                        retries,
-                    })
-                    .unwrap();
-                    None
-                }
-                Msg::OpenDatabase(server_id, database, retries) => {
-                    let server = self.storage.get_server(server_id).unwrap().unwrap();
+                    let server = self.stored_server(server_id)?;
 
-                    self.send_db_request(DbRequest::ListSchemas {
+                    self.send_db_request(DbRequest::ListObjects {
                         server_id: server.id,
-                        database,
+                        path,
                         retries,
                     })
                     .unwrap();
@@ -1002,43 +1586,60 @@ impl Update<Msg> for Model {
                     None
                 }
                 Msg::ReconnectAndRepeat(original_request) => {
-                    match original_request {
-                        DbRequest::Execute(server_id, database, query, retries) => {
-                            if retries > 3 {
-                                return None;
-                            }
-                            self.reconnect(&EditorId {
+                    let retry = Msg::ReconnectAndRepeat(original_request.clone());
+                    let gave_up = match &original_request {
+                        DbRequest::Execute(server_id, _, _, _, retries)
+                        | DbRequest::ListObjects {
+                            server_id, retries, ..
+                        } if *retries > 3 => Some(*server_id),
+                        _ => None,
+                    };
+                    if let Some(server_id) = gave_up {
+                        // final response of the request, so that its status is popped
+                        self.event_dispatcher_port
+                            .dispatch(Event::User(TisqEvent::DbResponse(DbResponse::Error(
                                 server_id,
-                                database: database.clone(),
-                            });
-                            Some(Msg::ExecuteQuery(
-                                EditorId {
+                                "Connection is lost, reconnecting did not help".to_string(),
+                            ))));
+                        return None;
+                    }
+                    // repeated request has its own status
+                    self.app_status.pop_db_request(&mut self.spinner_ticking);
+                    match original_request {
+                        DbRequest::Execute(server_id, database, query, parameters, retries) => {
+                            self.reconnect(
+                                &EditorId {
                                     server_id,
-                                    database,
+                                    database: database.clone(),
                                 },
-                                query,
-                                retries,
+                                retry,
+                            )?;
+                            // values of placeholders were already asked, so they are reused
+                            self.send_db_request(DbRequest::Execute(
+                                server_id, database, query, parameters, retries,
                             ))
+                            .unwrap();
+                            None
                         }
-                        DbRequest::ListSchemas {
+                        DbRequest::ListObjects {
                             server_id,
-                            database,
+                            path,
                             retries,
                         } => {
-                            if retries > 3 {
-                                return None;
-                            }
-                            self.reconnect(&EditorId {
+                            let database = path.first()?.clone();
+                            self.reconnect(
+                                &EditorId {
+                                    server_id,
+                                    database,
+                                },
+                                retry,
+                            )?;
+                            Some(Msg::OpenObject {
                                 server_id,
-                                database: database.clone(),
-                            });
-                            Some(Msg::OpenDatabase(server_id, database, retries))
+                                path,
+                                retries,
+                            })
                         }
-                        // DbRequest::ListTables {
-                        //     server_id,
-                        //     database,
-                        //     schema
-                        // } => Some(Msg::OpenDatabase(server_id, database)),
                         _ => None,
                     }
                     // Some(Msg::ExecuteQuery(editor_id, query))
@@ -1068,19 +1669,36 @@ impl Update<Msg> for Model {
                 }
                 Msg::CycleNavigation => match self.app.focus() {
                     Some(&Id::Tree) => Some(Msg::ChangeFocus(Id::EditorPanel)),
-                    Some(&Id::Editor(_)) => Some(Msg::ChangeFocus(Id::QueryResultTable)), // TODO: if error?
-                    Some(&Id::QueryResultTable) => Some(Msg::ChangeFocus(Id::Tree)),
+                    Some(&Id::Editor(_)) => Some(Msg::ChangeFocus(self.result_panel())), // TODO: if error?
+                    Some(&Id::QueryResultTable | &Id::ExplainPlanTree)
+                        if self.showing_server_messages =>
+                    {
+                        Some(Msg::ChangeFocus(Id::ServerMessagesTable))
+                    }
+                    Some(
+                        &Id::QueryResultTable | &Id::ExplainPlanTree | &Id::ServerMessagesTable,
+                    ) => Some(Msg::ChangeFocus(Id::Tree)),
                     _ => None,
                 },
                 Msg::NavigateRight | Msg::NavigateLeft => match self.app.focus() {
                     Some(&Id::Tree) => Some(Msg::ChangeFocus(Id::EditorPanel)),
                     Some(&Id::Editor(_)) => Some(Msg::ChangeFocus(Id::Tree)),
-                    Some(&Id::QueryResultTable) => Some(Msg::ChangeFocus(Id::Tree)),
+                    Some(&Id::QueryResultTable | &Id::ExplainPlanTree)
+                        if self.showing_server_messages =>
+                    {
+                        Some(Msg::ChangeFocus(Id::ServerMessagesTable))
+                    }
+                    Some(&Id::QueryResultTable | &Id::ExplainPlanTree) => {
+                        Some(Msg::ChangeFocus(Id::Tree))
+                    }
+                    Some(&Id::ServerMessagesTable) => Some(Msg::ChangeFocus(self.result_panel())),
                     _ => None,
                 },
                 Msg::NavigateUp | Msg::NavigateDown => match self.app.focus() {
-                    Some(&Id::Editor(_)) => Some(Msg::ChangeFocus(Id::QueryResultTable)),
-                    Some(&Id::QueryResultTable) => Some(Msg::ChangeFocus(Id::EditorPanel)),
+                    Some(&Id::Editor(_)) => Some(Msg::ChangeFocus(self.result_panel())),
+                    Some(
+                        &Id::QueryResultTable | &Id::ExplainPlanTree | &Id::ServerMessagesTable,
+                    ) => Some(Msg::ChangeFocus(Id::EditorPanel)),
                     _ => None,
                 },
                 // Msg::ApplySnippet => {
@@ -1094,12 +1712,99 @@ impl Update<Msg> for Model {
                     }
                     None
                 }
+                Msg::AddHistoryEntry(editor_id, summary) => {
+                    let entry = HistoryEntry {
+                        server_id: editor_id.server_id,
+                        database: editor_id.database,
+                        query: summary.query,
+                        executed_at: HistoryEntry::now_millis().saturating_sub(summary.duration_ms),
+                        duration_ms: summary.duration_ms,
+                        rows: summary.rows,
+                        more_rows: summary.more_rows,
+                        error: summary.error,
+                    };
+                    if let Err(e) = self.storage.add_history_entry(entry) {
+                        self.app_status
+                            .show_error(&format!("Failed to store query history: {}", e));
+                    }
+                    None
+                }
+                Msg::ShowHistory(editor_id) => {
+                    if self.showing_history.is_some() || self.showing_snippets {
+                        return None;
+                    }
+                    let entries = match self
+                        .storage
+                        .read_history(editor_id.server_id, &editor_id.database)
+                    {
+                        Ok(entries) => entries,
+                        Err(e) => {
+                            self.app_status
+                                .show_error(&format!("Failed to read query history: {}", e));
+                            return None;
+                        }
+                    };
+                    self.app
+                        .mount(
+                            Id::HistoryTable,
+                            Box::new(HistoryTable::new(entries)),
+                            vec![],
+                        )
+                        .unwrap();
+                    self.app.active(&Id::HistoryTable).unwrap();
+                    self.showing_history = Some(editor_id);
+                    None
+                }
+                Msg::PasteFromHistory(query) => {
+                    if let Some(editor_id) = self.close_history() {
+                        self.event_dispatcher_port
+                            .dispatch(Event::User(TisqEvent::EditorContentAdd(editor_id, query)));
+                    }
+                    None
+                }
+                Msg::SendNotification {
+                    editor_id,
+                    channel,
+                    payload,
+                } => {
+                    self.send_db_request(DbRequest::Notify {
+                        server_id: editor_id.server_id,
+                        database: editor_id.database,
+                        channel,
+                        payload,
+                    })
+                    .unwrap();
+                    None
+                }
+                Msg::ExecuteFromHistory(query) => self
+                    .close_history()
+                    .map(|editor_id| Msg::ExecuteQuery(editor_id, query, 0)),
                 Msg::ShowErrorResult => {
                     self.execute_result_state = ExecuteResultState::Error;
                     None
                 }
                 Msg::ShowFetchedTable => {
                     self.execute_result_state = ExecuteResultState::FetchedTable;
+                    // focus follows the result panel that replaced the hidden one
+                    if self.app.focus() == Some(&Id::ExplainPlanTree) {
+                        self.app.active(&Id::QueryResultTable).unwrap();
+                    }
+                    None
+                }
+                Msg::ShowServerMessages(show) => {
+                    self.showing_server_messages = show;
+                    // hidden panel can not keep focus
+                    if !show && self.app.focus() == Some(&Id::ServerMessagesTable) {
+                        let result_panel = self.result_panel();
+                        self.app.active(&result_panel).unwrap();
+                    }
+                    None
+                }
+                Msg::ShowExplainPlan => {
+                    self.execute_result_state = ExecuteResultState::Plan;
+                    if self.app.focus() == Some(&Id::QueryResultTable) {
+                        self.app.active(&Id::ExplainPlanTree).unwrap();
+                    }
                     None
                 }
                 Msg::MoveTabRight(editor_id) => {
@@ -1121,8 +1826,10 @@ impl Update<Msg> for Model {
                     None
                 }
                 Msg::OpenQueryEditor(server_id, database) => {
-                    let server = self.storage.get_server(server_id).unwrap().unwrap();
+                    let server = self.stored_server(server_id)?;
                     let server_name = server.name.clone();
+                    let retry = Msg::OpenQueryEditor(server_id, database.clone());
+                    let settings = self.server_settings(&server, retry)?;
 
                     let editor_id = EditorId {
                         server_id,
@@ -1160,20 +1867,24 @@ impl Update<Msg> for Model {
 
                     // This is to make sure we have an active connection
                     // TODO: process response as well
-                    self.connect_to_database(&server, database);
+                    self.connect_to_database(server_id, database, settings);
 
                     None
                 }
                 Msg::OpenConnection(server_id) => {
-                    let server = self.storage.get_server(server_id).unwrap();
-                    let server = server.unwrap();
-                    self.connect_to_server(&server);
+                    let server = self.stored_server(server_id)?;
+                    let settings = self.server_settings(&server, Msg::OpenConnection(server_id))?;
+                    self.connect_to_server(server_id, settings);
 
                     Some(Msg::LoadDatabases(server_id))
                 }
                 Msg::LoadDatabases(server_id) => {
-                    self.send_db_request(DbRequest::ListDatabases(server_id))
-                        .unwrap();
+                    self.send_db_request(DbRequest::ListObjects {
+                        server_id,
+                        path: vec![],
+                        retries: 0,
+                    })
+                    .unwrap();
 
                     None
                 }
@@ -1183,6 +1894,15 @@ impl Update<Msg> for Model {
                         Some(group) => group,
                         None => return None,
                     };
+                    if group == "group" {
+                        let group_id = Uuid::parse_str(split.next()?).ok()?;
+                        if let Err(e) = self.storage.delete_group(group_id) {
+                            self.app_status
+                                .show_error(&format!("Failed to delete group: {}", e));
+                        }
+                        self.update_browser();
+                        return None;
+                    }
                     if group != "server" {
                         return None;
                     }
@@ -1195,58 +1915,307 @@ impl Update<Msg> for Model {
                         }
                     };
                     self.storage.delete_server(server_id).unwrap();
+                    self.secrets.forget_password(server_id);
                     self.update_browser();
                     None
                 }
-                Msg::SubmitAddServerForm => {
-                    let server_name_state = self.app.state(&Id::ServerNameInput).unwrap();
-                    let server_name = match server_name_state {
-                        State::One(StateValue::String(input_text)) => input_text,
-                        _ => {
-                            panic!("unexpected state: {:?}", server_name_state);
+                Msg::ImportServers => {
+                    match self.storage.import_servers(discover_servers()) {
+                        Ok(imported) => tracing::info!("imported servers: {:?}", imported),
+                        Err(e) => tracing::error!("servers could not be imported: {}", e),
+                    }
+                    self.update_browser();
+                    None
+                }
+                Msg::StartCreatingGroup(selected) => {
+                    let parent_id = selected.and_then(|item| self.item_group(item));
+                    let title = match parent_id {
+                        Some(_) => format!("Name of new group in {}", self.group_path(parent_id)?),
+                        None => "Name of new group".to_string(),
+                    };
+                    self.show_group_prompt(GroupAction::Create(parent_id), &title, "");
+                    None
+                }
+                Msg::StartRenamingGroup(group_id) => {
+                    let group = self.stored_group(group_id)?;
+                    let title = format!("New name of group {}", group.name);
+                    self.show_group_prompt(GroupAction::Rename(group_id), &title, &group.name);
+                    None
+                }
+                Msg::StartMovingToGroup(item) => {
+                    let (name, group_id) = match item {
+                        BrowsedItem::Server(server_id) => {
+                            let server = self.stored_server(server_id)?;
+                            (server.name, server.group_id)
+                        }
+                        BrowsedItem::Group(group_id) => {
+                            let group = self.stored_group(group_id)?;
+                            (group.name, group.parent_id)
                         }
                     };
-
-                    let connection_url_state = self.app.state(&Id::ConnectionUrlInput).unwrap();
-                    let connection_url = match connection_url_state {
-                        State::One(StateValue::String(input_text)) => input_text,
-                        _ => {
-                            panic!("unexpected state: {:?}", connection_url_state);
+                    let title = format!("Move {} to group like prod/eu, empty for top level", name);
+                    let path = self.group_path(group_id)?;
+                    self.show_group_prompt(GroupAction::Move(item), &title, &path);
+                    None
+                }
+                Msg::SubmitGroupPrompt => {
+                    let prompt = self.group_prompt.as_ref()?;
+                    let value = prompt.value(&self.app);
+                    let result = match prompt.action {
+                        GroupAction::Create(parent_id) => {
+                            self.storage.add_group(&value, parent_id).map(|_| ())
+                        }
+                        GroupAction::Rename(group_id) => {
+                            self.storage.rename_group(group_id, &value)
+                        }
+                        GroupAction::Move(BrowsedItem::Server(server_id)) => {
+                            self.storage.move_server(server_id, &value)
+                        }
+                        GroupAction::Move(BrowsedItem::Group(group_id)) => {
+                            self.storage.move_group(group_id, &value)
                         }
                     };
+                    if let Err(e) = result {
+                        prompt.show_error(&mut self.app, &e.to_string());
+                        return None;
+                    }
+                    self.close_group_prompt();
+                    self.update_browser();
+                    None
+                }
+                Msg::SubmitAddServerForm
+                    if self.add_server_form.active_input == Id::TestConnectionButton =>
+                {
+                    self.test_server_connection();
+                    None
+                }
+                Msg::SubmitAddServerForm => {
+                    let form = &self.add_server_form;
+                    let properties = form.server_name(&self.app).and_then(|name| {
+                        let properties = form.connection_properties(&self.app)?;
+                        let password_storage = form.password_storage(&self.app, &properties)?;
+                        Ok((name, properties, password_storage))
+                    });
+                    let (server_name, mut connection_properties, password_storage) =
+                        match properties {
+                            Ok(properties) => properties,
+                            Err(message) => {
+                                AddServerForm::show_status(&mut self.app, &message, Color::Red);
+                                return None;
+                            }
+                        };
+
+                    let password = take_password(&mut connection_properties);
+                    match (password_storage, &password) {
+                        (Some(PasswordStorage::Encrypted), Some(password)) => {
+                            if !self
+                                .secrets
+                                .encrypt_password(&mut connection_properties, password)
+                            {
+                                self.show_password_prompt(
+                                    SecretPrompt::MasterPassphrase,
+                                    &server_name,
+                                    Msg::SubmitAddServerForm,
+                                );
+                                return None;
+                            }
+                        }
+                        // form allows it only for edited server, which already has one
+                        (Some(PasswordStorage::Encrypted), None) => {
+                            if let Some(server) = &self.add_server_form.editing {
+                                keep_encrypted_password(
+                                    &server.connection_properties,
+                                    &mut connection_properties,
+                                );
+                            }
+                        }
+                        (Some(storage), _) => storage.set(&mut connection_properties),
+                        (None, _) => {}
+                    }
 
-                    self.storage
-                        .add_server(NewServer {
-                            connection_properties: HashMap::from([(
-                                "url".to_string(),
-                                connection_url,
-                            )]),
+                    let edited_server = self
+                        .add_server_form
+                        .editing
+                        .as_ref()
+                        .map(|server| (server.id, server.group_id));
+                    let saved = match edited_server {
+                        Some((server_id, group_id)) => self
+                            .storage
+                            .update_server(StoredServer {
+                                id: server_id,
+                                name: server_name,
+                                connection_properties,
+                                group_id,
+                            })
+                            .map(|_| server_id),
+                        None => self.storage.add_server(NewServer {
+                            connection_properties,
                             name: server_name,
-                        })
-                        .unwrap();
+                        }),
+                    };
+                    // form stays open, so that it could be submitted again
+                    let server_id = match saved {
+                        Ok(server_id) => server_id,
+                        Err(e) => {
+                            let message = format!("Server could not be saved: {}", e);
+                            AddServerForm::show_status(&mut self.app, &message, Color::Red);
+                            return None;
+                        }
+                    };
+                    // password entered in the form is not asked again
+                    match (password, password_storage) {
+                        (Some(password), _) => self.secrets.remember_password(server_id, password),
+                        (None, None) => self.secrets.forget_password(server_id),
+                        _ => {}
+                    }
 
                     self.update_browser();
 
                     self.finish_adding_server();
 
+                    if edited_server.is_some() {
+                        self.reconnect_changed_server(server_id);
+                    }
+
                     None
                 }
                 Msg::FocusPreviousInput => {
-                    self.add_server_form
-                        .activate_previous_input(&mut self.app)
-                        .unwrap();
+                    if self.password_prompt.is_some() || self.group_prompt.is_some() {
+                        // prompt has only one input
+                    } else if let Some(form) = &mut self.bind_parameters_form {
+                        form.activate_previous_input(&mut self.app).unwrap();
+                    } else {
+                        self.add_server_form
+                            .activate_previous_input(&mut self.app)
+                            .unwrap();
+                    }
                     None
                 }
                 Msg::FocusNextInput => {
-                    self.add_server_form
-                        .activate_next_input(&mut self.app)
-                        .unwrap();
+                    if self.password_prompt.is_some() || self.group_prompt.is_some() {
+                        // prompt has only one input
+                    } else if let Some(form) = &mut self.bind_parameters_form {
+                        form.activate_next_input(&mut self.app).unwrap();
+                    } else {
+                        self.add_server_form
+                            .activate_next_input(&mut self.app)
+                            .unwrap();
+                    }
+                    None
+                }
+                Msg::SubmitPassword => {
+                    let prompt = self.password_prompt.as_ref()?;
+                    let value = prompt.value(&self.app);
+                    match prompt.prompt {
+                        SecretPrompt::ServerPassword(server_id) => {
+                            self.secrets.remember_password(server_id, value)
+                        }
+                        // key of the passphrase entered before is still being derived
+                        SecretPrompt::MasterPassphrase if self.secrets.is_unlocking() => {
+                            return None
+                        }
+                        SecretPrompt::MasterPassphrase => {
+                            let stored = match self.storage.get_master_key() {
+                                Ok(stored) => stored,
+                                Err(e) => {
+                                    prompt.show_error(&mut self.app, &e.to_string());
+                                    return None;
+                                }
+                            };
+                            let mut dispatcher = self.event_dispatcher_port.clone();
+                            self.secrets.start_unlock(stored, value, move || {
+                                dispatcher.dispatch(Event::User(TisqEvent::MasterKeyDerived));
+                            });
+                            self.app_status.push_task(
+                                "Deriving master key... ",
+                                &mut self.app,
+                                &mut self.spinner_ticking,
+                            );
+                            // prompt is closed once the key is derived and checked
+                            return None;
+                        }
+                    }
+                    self.close_password_prompt().map(|prompt| prompt.retry)
+                }
+                Msg::MasterKeyDerived => {
+                    let result = self.secrets.finish_unlock()?;
+                    self.app_status
+                        .pop_task(&mut self.app, &mut self.spinner_ticking);
+                    // entering of passphrase could be cancelled meanwhile
+                    let prompt = self
+                        .password_prompt
+                        .as_ref()
+                        .filter(|prompt| prompt.prompt == SecretPrompt::MasterPassphrase)?;
+                    let result = result.and_then(|mut master_key| {
+                        if let Some(created) = master_key.created.take() {
+                            self.storage
+                                .set_master_key(created)
+                                .map_err(|e| e.to_string())?;
+                        }
+                        Ok(master_key)
+                    });
+                    match result {
+                        Ok(master_key) => {
+                            self.secrets.set_master_key(master_key);
+                            self.close_password_prompt().map(|prompt| prompt.retry)
+                        }
+                        Err(e) => {
+                            prompt.show_error(&mut self.app, &e);
+                            None
+                        }
+                    }
+                }
+                Msg::EncryptPlainPasswords => {
+                    match self.secrets.encrypt_plain_passwords(&mut self.storage) {
+                        Ok(names) => tracing::info!("encrypted stored passwords of: {:?}", names),
+                        Err(e) => tracing::error!("stored passwords could not be encrypted: {}", e),
+                    }
+                    None
+                }
+                Msg::ForgetPassword(server_id) => {
+                    self.secrets.forget_password(server_id);
+                    None
+                }
+                Msg::ServerConnectionTested(error) => {
+                    // response is handled here instead of status listener
+                    self.app_status.pop_db_request(&mut self.spinner_ticking);
+                    if self.add_server_form_mounted {
+                        match error {
+                            Some(error) => {
+                                AddServerForm::show_status(&mut self.app, &error, Color::Red)
+                            }
+                            None => AddServerForm::show_status(
+                                &mut self.app,
+                                "Connection succeeded",
+                                Color::LightGreen,
+                            ),
+                        }
+                    }
+                    None
+                }
+                Msg::StartEditingServer(server_id) => {
+                    let server = self.stored_server(server_id)?;
+                    // form is filled again, even if some server is already edited
+                    if self.add_server_form_mounted {
+                        self.unmount_server_add_form();
+                    }
+                    Self::mount_server_add_form(&mut self.app);
+                    self.add_server_form_mounted = true;
+                    self.adding_server = true;
+                    self.add_server_form = AddServerForm::editing(&mut self.app, server);
+                    self.app.active(&Id::ServerNameInput).unwrap();
                     None
                 }
                 Msg::StartAddingServer => {
                     self.adding_server = true;
+                    // form of edited server is replaced with the empty one
+                    if self.add_server_form_mounted && self.add_server_form.editing.is_some() {
+                        self.unmount_server_add_form();
+                        self.add_server_form_mounted = false;
+                    }
                     if !self.add_server_form_mounted {
                         Self::mount_server_add_form(&mut self.app);
+                        self.add_server_form = AddServerForm::new();
 
                         // Self::mount_add_server_form(&mut self.app);
                         self.add_server_form_mounted = true;
@@ -1283,11 +2252,23 @@ impl Update<Msg> for Model {
                     //         data: execute_result,
                     //     }),
                     // ));
+                    let dialect = self.editor_database_kind(&editor_id).capabilities().dialect;
+                    if !placeholder_names(&query, dialect).is_empty() {
+                        // values are asked from user before executing
+                        self.send_db_request(DbRequest::DescribeParameters {
+                            server_id: editor_id.server_id,
+                            database: editor_id.database,
+                            query,
+                        })
+                        .unwrap();
+                        return None;
+                    }
                     self.send_db_request(DbRequest::Execute(
                         // self.get_or_set_shown_editor_id().unwrap(),
                         editor_id.server_id,
                         editor_id.database,
                         query,
+                        vec![],
                         retries,
                     ))
                     .unwrap();
@@ -1298,6 +2279,126 @@ impl Update<Msg> for Model {
                     // }));
                     None
                 }
+                Msg::ShowBindParametersForm(editor_id, query, described) => {
+                    // response is handled here instead of status listener
+                    self.app_status.pop_db_request(&mut self.spinner_ticking);
+                    self.close_bind_parameters_form();
+
+                    let remembered = self.bind_parameters.get(&editor_id);
+                    let parameters = described
+                        .into_iter()
+                        .map(|(name, type_name)| {
+                            let known = remembered.and_then(|parameters| {
+                                parameters.iter().find(|parameter| parameter.name == name)
+                            });
+                            match known {
+                                Some(known) => BindParameter {
+                                    type_name: known.type_name.clone().or(type_name),
+                                    ..known.clone()
+                                },
+                                None => BindParameter {
+                                    name,
+                                    value: Some(String::new()),
+                                    type_name,
+                                },
+                            }
+                        })
+                        .collect();
+
+                    let form =
+                        BindParametersForm::mount(&mut self.app, editor_id, query, parameters)
+                            .unwrap();
+                    self.bind_parameters_form = Some(form);
+                    None
+                }
+                Msg::SubmitBindParameters => {
+                    let parameters = match &self.bind_parameters_form {
+                        Some(form) => form.parameters(&self.app),
+                        None => return None,
+                    };
+                    let form = self.close_bind_parameters_form().unwrap();
+                    let editor_id = form.editor_id;
+
+                    let remembered = self.bind_parameters.entry(editor_id.clone()).or_default();
+                    for parameter in &parameters {
+                        remembered.retain(|known| known.name != parameter.name);
+                        remembered.push(parameter.clone());
+                    }
+
+                    self.send_db_request(DbRequest::Execute(
+                        editor_id.server_id,
+                        editor_id.database,
+                        form.query,
+                        parameters,
+                        0,
+                    ))
+                    .unwrap();
+                    None
+                }
+                Msg::ExplainQuery(editor_id, query, analyze) => {
+                    self.send_db_request(DbRequest::Explain {
+                        server_id: editor_id.server_id,
+                        database: editor_id.database,
+                        query,
+                        analyze,
+                    })
+                    .unwrap();
+                    None
+                }
+                Msg::FetchMoreRows(editor_id) => {
+                    self.send_db_request(DbRequest::FetchMore {
+                        server_id: editor_id.server_id,
+                        database: editor_id.database,
+                    })
+                    .unwrap();
+                    None
+                }
+                Msg::ToggleTransactionMode(editor_id) => {
+                    let manual = self
+                        .app_status
+                        .transaction_status(&editor_id)
+                        .map_or(false, |status| status.manual);
+                    self.send_db_request(DbRequest::SetTransactionMode {
+                        server_id: editor_id.server_id,
+                        database: editor_id.database,
+                        manual: !manual,
+                    })
+                    .unwrap();
+                    None
+                }
+                Msg::CommitTransaction(editor_id) => {
+                    self.send_db_request(DbRequest::Commit {
+                        server_id: editor_id.server_id,
+                        database: editor_id.database,
+                    })
+                    .unwrap();
+                    None
+                }
+                Msg::RollbackTransaction(editor_id) => {
+                    self.send_db_request(DbRequest::Rollback {
+                        server_id: editor_id.server_id,
+                        database: editor_id.database,
+                    })
+                    .unwrap();
+                    None
+                }
+                Msg::TransactionStatusChanged(editor_id, status) => {
+                    self.app_status.set_transaction_status(editor_id, status);
+                    None
+                }
+                Msg::CancelQuery(editor_id) => {
+                    self.send_db_request(DbRequest::CancelQuery {
+                        server_id: editor_id.server_id,
+                        database: editor_id.database,
+                    })
+                    .unwrap();
+                    None
+                }
+                Msg::QueryNotCancelled(reason) => {
+                    self.app_status.pop_db_request(&mut self.spinner_ticking);
+                    self.app_status.show_error(&reason);
+                    None
+                }
                 Msg::AppClose => {
                     if let Some(editor_id) = &self.shown_editor {
                         let mut text_to_store: String = String::new();
@@ -0,0 +1,434 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use uuid::Uuid;
+
+use super::connection::split_url_password;
+use super::storage::{Storage, StoredMasterKey, StoredServer};
+
+const PASSWORD_STORAGE_PROPERTY: &str = "password_storage";
+const ENCRYPTED_PASSWORD_PROPERTY: &str = "encrypted_password";
+
+// text encrypted with master key to check entered passphrase
+const MASTER_KEY_CHECK: &str = "tisq";
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+
+/// How password of the server is kept, it is never stored as is
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub(crate) enum PasswordStorage {
+    // password is asked once per run of the app and kept only in memory
+    Ask,
+    // password is stored encrypted with key derived from master passphrase
+    Encrypted,
+}
+
+impl PasswordStorage {
+    /// How password is kept by the server, None if it does not need one
+    pub(crate) fn of(properties: &HashMap<String, String>) -> Option<Self> {
+        match properties.get(PASSWORD_STORAGE_PROPERTY)?.as_str() {
+            "ask" => Some(PasswordStorage::Ask),
+            "encrypted" => Some(PasswordStorage::Encrypted),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn set(&self, properties: &mut HashMap<String, String>) {
+        let value = match self {
+            PasswordStorage::Ask => "ask",
+            PasswordStorage::Encrypted => "encrypted",
+        };
+        properties.insert(PASSWORD_STORAGE_PROPERTY.to_string(), value.to_string());
+    }
+}
+
+/// What has to be entered before connecting to the server
+#[derive(PartialEq, Clone, Debug)]
+pub(crate) enum SecretPrompt {
+    ServerPassword(Uuid),
+    MasterPassphrase,
+}
+
+/// Passwords known during this run of the app, they are only kept in memory
+#[derive(Default)]
+pub(crate) struct Secrets {
+    passwords: HashMap<Uuid, String>,
+    master_key: Option<LessSafeKey>,
+    // master key being derived from entered passphrase in another thread
+    unlocking: Option<Receiver<Result<MasterKey, String>>>,
+}
+
+/// Key derived from master passphrase, the new one has to be stored,
+/// so that passphrases entered later could be checked against it
+pub(crate) struct MasterKey {
+    key: LessSafeKey,
+    pub(crate) created: Option<StoredMasterKey>,
+}
+
+impl Secrets {
+    /// Password to connect to the server, None if server does not need one
+    /// and error with what should be asked first, if password is not known yet
+    pub(crate) fn password(
+        &mut self,
+        server: &StoredServer,
+    ) -> Result<Option<String>, SecretPrompt> {
+        let storage = match PasswordStorage::of(&server.connection_properties) {
+            Some(storage) => storage,
+            None => return Ok(None),
+        };
+        if let Some(password) = self.passwords.get(&server.id) {
+            return Ok(Some(password.clone()));
+        }
+        if storage == PasswordStorage::Ask {
+            return Err(SecretPrompt::ServerPassword(server.id));
+        }
+        let master_key = self
+            .master_key
+            .as_ref()
+            .ok_or(SecretPrompt::MasterPassphrase)?;
+        let password = server
+            .connection_properties
+            .get(ENCRYPTED_PASSWORD_PROPERTY)
+            .and_then(|sealed| open(master_key, sealed))
+            // damaged password could still be entered by hand
+            .ok_or(SecretPrompt::ServerPassword(server.id))?;
+        self.passwords.insert(server.id, password.clone());
+        Ok(Some(password))
+    }
+
+    pub(crate) fn remember_password(&mut self, server_id: Uuid, password: String) {
+        self.passwords.insert(server_id, password);
+    }
+
+    pub(crate) fn forget_password(&mut self, server_id: Uuid) {
+        self.passwords.remove(&server_id);
+    }
+
+    /// Starts deriving master key from passphrase, which takes a while, so it is done
+    /// in another thread and `derived` is called once the key could be taken.
+    /// The first entered passphrase becomes the master one, later ones are checked against it
+    pub(crate) fn start_unlock(
+        &mut self,
+        stored: Option<StoredMasterKey>,
+        passphrase: String,
+        derived: impl FnOnce() + Send + 'static,
+    ) {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            if tx.send(derive_master_key(stored, &passphrase)).is_ok() {
+                derived();
+            }
+        });
+        self.unlocking = Some(rx);
+    }
+
+    pub(crate) fn is_unlocking(&self) -> bool {
+        self.unlocking.is_some()
+    }
+
+    /// Takes master key derived in another thread, None if it is not done yet
+    pub(crate) fn finish_unlock(&mut self) -> Option<Result<MasterKey, String>> {
+        let result = match self.unlocking.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => Err("Master key could not be derived".to_string()),
+        };
+        self.unlocking = None;
+        Some(result)
+    }
+
+    /// Uses derived key for stored passwords, the new key should be stored before
+    pub(crate) fn set_master_key(&mut self, master_key: MasterKey) {
+        self.master_key = Some(master_key.key);
+    }
+
+    /// Puts password encrypted with master key into properties,
+    /// returns false if master passphrase was not entered yet
+    pub(crate) fn encrypt_password(
+        &self,
+        properties: &mut HashMap<String, String>,
+        password: &str,
+    ) -> bool {
+        let master_key = match &self.master_key {
+            Some(master_key) => master_key,
+            None => return false,
+        };
+        PasswordStorage::Encrypted.set(properties);
+        properties.insert(
+            ENCRYPTED_PASSWORD_PROPERTY.to_string(),
+            seal(master_key, password),
+        );
+        true
+    }
+
+    /// Encrypts passwords stored as is by previous versions of the app,
+    /// master passphrase has to be entered first, returns names of changed servers
+    pub(crate) fn encrypt_plain_passwords(
+        &self,
+        storage: &mut Storage,
+    ) -> eyre::Result<Vec<String>> {
+        if self.master_key.is_none() {
+            return Err(eyre::eyre!("Master passphrase is not entered"));
+        }
+        let mut encrypted = vec![];
+        for mut server in storage.read_servers()? {
+            let password = match take_password(&mut server.connection_properties) {
+                Some(password) => password,
+                None => continue,
+            };
+            self.encrypt_password(&mut server.connection_properties, &password);
+            encrypted.push(server.name.clone());
+            storage.update_server(server)?;
+        }
+        if !encrypted.is_empty() {
+            // replaced values could still be found in files of the store otherwise
+            storage.rewrite()?;
+        }
+        Ok(encrypted)
+    }
+}
+
+/// Tells if password is stored as is, like previous versions of the app did it
+pub(crate) fn has_plain_password(properties: &HashMap<String, String>) -> bool {
+    properties.contains_key("password")
+        || properties
+            .get("url")
+            .is_some_and(|url| split_url_password(url).1.is_some())
+}
+
+/// Removes password given as property or as part of url, so that it is asked
+/// on connect instead, returns true if there was one
+pub(crate) fn ask_for_password(properties: &mut HashMap<String, String>) -> bool {
+    let removed = take_password(properties).is_some();
+    if removed && PasswordStorage::of(properties).is_none() {
+        PasswordStorage::Ask.set(properties);
+    }
+    removed
+}
+
+/// Removes password given as property or as part of connection url and returns it
+pub(crate) fn take_password(properties: &mut HashMap<String, String>) -> Option<String> {
+    let mut password = properties.remove("password");
+    if let Some(url) = properties.get_mut("url") {
+        let (without_password, url_password) = split_url_password(url);
+        if url_password.is_some() {
+            *url = without_password;
+            password = url_password;
+        }
+    }
+    password
+}
+
+/// Keeps password encrypted before in properties of the edited server,
+/// when new password is not entered
+pub(crate) fn keep_encrypted_password(
+    previous: &HashMap<String, String>,
+    properties: &mut HashMap<String, String>,
+) {
+    if let Some(sealed) = previous.get(ENCRYPTED_PASSWORD_PROPERTY) {
+        PasswordStorage::Encrypted.set(properties);
+        properties.insert(ENCRYPTED_PASSWORD_PROPERTY.to_string(), sealed.clone());
+    }
+}
+
+/// Derives key from passphrase and checks it against the stored one,
+/// if there is no stored key yet, the new one is made with random salt
+fn derive_master_key(
+    stored: Option<StoredMasterKey>,
+    passphrase: &str,
+) -> Result<MasterKey, String> {
+    match stored {
+        Some(stored) => {
+            let salt = STANDARD
+                .decode(&stored.salt)
+                .map_err(|e| format!("Stored master key is damaged: {}", e))?;
+            let key = derive_key(passphrase, &salt);
+            if open(&key, &stored.check).as_deref() != Some(MASTER_KEY_CHECK) {
+                return Err("Wrong master passphrase".to_string());
+            }
+            Ok(MasterKey { key, created: None })
+        }
+        None => {
+            if passphrase.is_empty() {
+                return Err("Master passphrase could not be empty".to_string());
+            }
+            let mut salt = [0; SALT_LEN];
+            fill_random(&mut salt);
+            let key = derive_key(passphrase, &salt);
+            let created = StoredMasterKey {
+                salt: STANDARD.encode(salt),
+                check: seal(&key, MASTER_KEY_CHECK),
+            };
+            Ok(MasterKey {
+                key,
+                created: Some(created),
+            })
+        }
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> LessSafeKey {
+    let mut key = [0; 32];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    LessSafeKey::new(UnboundKey::new(&CHACHA20_POLY1305, &key).unwrap())
+}
+
+fn fill_random(bytes: &mut [u8]) {
+    SystemRandom::new()
+        .fill(bytes)
+        .expect("random bytes could not be generated");
+}
+
+/// Encrypts text with random nonce, which is put in front of encrypted bytes
+fn seal(key: &LessSafeKey, text: &str) -> String {
+    let mut nonce = [0; NONCE_LEN];
+    fill_random(&mut nonce);
+    let mut sealed = text.as_bytes().to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::empty(),
+        &mut sealed,
+    )
+    .expect("text could not be encrypted");
+    STANDARD.encode([nonce.as_slice(), &sealed].concat())
+}
+
+/// Decrypts text sealed with the same key, None if key is wrong or text is damaged
+fn open(key: &LessSafeKey, sealed: &str) -> Option<String> {
+    let sealed = STANDARD.decode(sealed).ok()?;
+    if sealed.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, encrypted) = sealed.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+    let mut encrypted = encrypted.to_vec();
+    let text = key
+        .open_in_place(nonce, Aad::empty(), &mut encrypted)
+        .ok()?;
+    String::from_utf8(text.to_vec()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::storage::{with_storage, NewServer};
+    use super::*;
+
+    const SALT: [u8; SALT_LEN] = [7; SALT_LEN];
+
+    fn tamper(sealed: &str, index: usize) -> String {
+        let mut bytes = STANDARD.decode(sealed).unwrap();
+        bytes[index] ^= 1;
+        STANDARD.encode(bytes)
+    }
+
+    #[test]
+    fn sealed_text_is_opened_with_the_same_key() {
+        let key = derive_key("passphrase", &SALT);
+        let sealed = seal(&key, "secret password");
+        assert_eq!(open(&key, &sealed).as_deref(), Some("secret password"));
+        // nonce is random, so the same text is never sealed the same way
+        assert_ne!(seal(&key, "secret password"), sealed);
+    }
+
+    #[test]
+    fn sealed_text_is_not_opened_with_wrong_passphrase() {
+        let sealed = seal(&derive_key("passphrase", &SALT), "secret password");
+        let wrong_key = derive_key("wrong passphrase", &SALT);
+        assert_eq!(open(&wrong_key, &sealed), None);
+    }
+
+    #[test]
+    fn tampered_text_is_not_opened() {
+        let key = derive_key("passphrase", &SALT);
+        let sealed = seal(&key, "secret password");
+        // in nonce, in encrypted text and in tag
+        assert_eq!(open(&key, &tamper(&sealed, 0)), None);
+        assert_eq!(open(&key, &tamper(&sealed, NONCE_LEN + 1)), None);
+        let last = STANDARD.decode(&sealed).unwrap().len() - 1;
+        assert_eq!(open(&key, &tamper(&sealed, last)), None);
+        // truncated and not base64 at all
+        assert_eq!(open(&key, &STANDARD.encode([1; NONCE_LEN - 1])), None);
+        assert_eq!(open(&key, "not base64!"), None);
+    }
+
+    #[test]
+    fn master_key_is_checked_against_stored_one() {
+        let created = derive_master_key(None, "passphrase")
+            .ok()
+            .and_then(|master_key| master_key.created)
+            .unwrap();
+        let stored = StoredMasterKey {
+            salt: created.salt.clone(),
+            check: created.check.clone(),
+        };
+        let checked = derive_master_key(Some(stored), "passphrase").unwrap();
+        assert!(checked.created.is_none());
+        assert_eq!(
+            derive_master_key(Some(created), "wrong passphrase").err(),
+            Some("Wrong master passphrase".to_string())
+        );
+        assert!(derive_master_key(None, "").is_err());
+    }
+
+    #[test]
+    fn edited_server_keeps_id_group_and_encrypted_password() {
+        with_storage(|storage| {
+            let key = derive_key("passphrase", &SALT);
+            let mut properties = HashMap::from([("host".to_string(), "old".to_string())]);
+            PasswordStorage::Encrypted.set(&mut properties);
+            let sealed = seal(&key, "secret password");
+            properties.insert(ENCRYPTED_PASSWORD_PROPERTY.to_string(), sealed.clone());
+            let server_id = storage
+                .add_server(NewServer {
+                    name: "old".to_string(),
+                    connection_properties: properties,
+                })
+                .unwrap();
+            let group_id = storage.add_group("prod", None).unwrap();
+            storage.move_server(server_id, "prod").unwrap();
+            let previous = storage.get_server(server_id).unwrap().unwrap();
+
+            // password field is left empty in the form
+            let mut properties = HashMap::from([("host".to_string(), "new".to_string())]);
+            keep_encrypted_password(&previous.connection_properties, &mut properties);
+            storage
+                .update_server(StoredServer {
+                    id: previous.id,
+                    name: "new".to_string(),
+                    connection_properties: properties,
+                    group_id: previous.group_id,
+                })
+                .unwrap();
+
+            let servers = storage.read_servers().unwrap();
+            assert_eq!(servers.len(), 1);
+            let server = &servers[0];
+            assert_eq!(server.id, server_id);
+            assert_eq!(server.name, "new");
+            assert_eq!(server.group_id, Some(group_id));
+            assert_eq!(server.connection_properties["host"], "new");
+            assert_eq!(
+                PasswordStorage::of(&server.connection_properties),
+                Some(PasswordStorage::Encrypted)
+            );
+            let stored = &server.connection_properties[ENCRYPTED_PASSWORD_PROPERTY];
+            assert_eq!(open(&key, stored).as_deref(), Some("secret password"));
+        });
+    }
+}
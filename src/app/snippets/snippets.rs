@@ -9,13 +9,35 @@ pub(crate) struct Snippet {
     pub query: String,
 }
 
-#[derive(Deserialize, Serialize, PartialEq, Eq, Hash)]
+use crate::app::DatabaseKind;
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub(crate) enum SnippetDatabase {
     Postgres,
+    Sqlite,
+    MySql,
+}
+
+impl From<DatabaseKind> for SnippetDatabase {
+    fn from(kind: DatabaseKind) -> Self {
+        match kind {
+            DatabaseKind::Postgres => SnippetDatabase::Postgres,
+            DatabaseKind::Sqlite => SnippetDatabase::Sqlite,
+            DatabaseKind::MySql => SnippetDatabase::MySql,
+        }
+    }
 }
 
 pub(crate) type SnippetsConfig = HashMap<SnippetDatabase, Vec<Snippet>>;
 
+pub(crate) fn standard_snippets(database: SnippetDatabase) -> HashMap<String, Snippet> {
+    match database {
+        SnippetDatabase::Postgres => standard_postgres_snippets(),
+        SnippetDatabase::Sqlite => standard_sqlite_snippets(),
+        SnippetDatabase::MySql => standard_mysql_snippets(),
+    }
+}
+
 pub(crate) fn standard_postgres_snippets() -> HashMap<String, Snippet> {
     HashMap::from([
         (
@@ -135,3 +157,239 @@ WHERE l.relation = t.relid ORDER BY relation asc;"#
         ),
     ])
 }
+
+pub(crate) fn standard_sqlite_snippets() -> HashMap<String, Snippet> {
+    HashMap::from([
+        (
+            "sel".to_string(),
+            Snippet {
+                shortcut: "sel".to_string(),
+                description: "SELECT * FROM".to_string(),
+                query: "SELECT * FROM".to_string(),
+            },
+        ),
+        (
+            "ins".to_string(),
+            Snippet {
+                shortcut: "ins".to_string(),
+                description: "INSERT INTO".to_string(),
+                query: "INSERT INTO".to_string(),
+            },
+        ),
+        (
+            "upd".to_string(),
+            Snippet {
+                shortcut: "upd".to_string(),
+                description: "UPDATE".to_string(),
+                query: "UPDATE".to_string(),
+            },
+        ),
+        (
+            "del".to_string(),
+            Snippet {
+                shortcut: "del".to_string(),
+                description: "DELETE FROM".to_string(),
+                query: "DELETE FROM".to_string(),
+            },
+        ),
+        (
+            "cre".to_string(),
+            Snippet {
+                shortcut: "cre".to_string(),
+                description: "CREATE TABLE".to_string(),
+                query: "CREATE TABLE".to_string(),
+            },
+        ),
+        (
+            "alt".to_string(),
+            Snippet {
+                shortcut: "alt".to_string(),
+                description: "ALTER TABLE".to_string(),
+                query: "ALTER TABLE".to_string(),
+            },
+        ),
+        (
+            "dro".to_string(),
+            Snippet {
+                shortcut: "dro".to_string(),
+                description: "DROP TABLE".to_string(),
+                query: "DROP TABLE".to_string(),
+            },
+        ),
+        (
+            "ts".to_string(),
+            Snippet {
+                shortcut: "ts".to_string(),
+                description: "Tables and views".to_string(),
+                query: "SELECT type, name, sql
+FROM sqlite_master
+WHERE type IN ('table', 'view')
+ORDER BY name;"
+                    .to_string(),
+            },
+        ),
+        (
+            "ix".to_string(),
+            Snippet {
+                shortcut: "ix".to_string(),
+                description: "Indexes".to_string(),
+                query: "SELECT tbl_name, name, sql
+FROM sqlite_master
+WHERE type = 'index'
+ORDER BY tbl_name, name;"
+                    .to_string(),
+            },
+        ),
+        (
+            "ds".to_string(),
+            Snippet {
+                shortcut: "ds".to_string(),
+                description: "Database size".to_string(),
+                query: "SELECT page_count * page_size AS size_bytes, freelist_count * page_size AS free_bytes
+FROM pragma_page_count(), pragma_page_size(), pragma_freelist_count();"
+                    .to_string(),
+            },
+        ),
+        (
+            "ic".to_string(),
+            Snippet {
+                shortcut: "ic".to_string(),
+                description: "Integrity check".to_string(),
+                query: "PRAGMA integrity_check;".to_string(),
+            },
+        ),
+        (
+            "vac".to_string(),
+            Snippet {
+                shortcut: "vac".to_string(),
+                description: "Rebuild database file".to_string(),
+                query: "VACUUM;".to_string(),
+            },
+        ),
+    ])
+}
+
+pub(crate) fn standard_mysql_snippets() -> HashMap<String, Snippet> {
+    HashMap::from([
+        (
+            "sel".to_string(),
+            Snippet {
+                shortcut: "sel".to_string(),
+                description: "SELECT * FROM".to_string(),
+                query: "SELECT * FROM".to_string(),
+            },
+        ),
+        (
+            "ins".to_string(),
+            Snippet {
+                shortcut: "ins".to_string(),
+                description: "INSERT INTO".to_string(),
+                query: "INSERT INTO".to_string(),
+            },
+        ),
+        (
+            "upd".to_string(),
+            Snippet {
+                shortcut: "upd".to_string(),
+                description: "UPDATE".to_string(),
+                query: "UPDATE".to_string(),
+            },
+        ),
+        (
+            "del".to_string(),
+            Snippet {
+                shortcut: "del".to_string(),
+                description: "DELETE FROM".to_string(),
+                query: "DELETE FROM".to_string(),
+            },
+        ),
+        (
+            "cre".to_string(),
+            Snippet {
+                shortcut: "cre".to_string(),
+                description: "CREATE TABLE".to_string(),
+                query: "CREATE TABLE".to_string(),
+            },
+        ),
+        (
+            "alt".to_string(),
+            Snippet {
+                shortcut: "alt".to_string(),
+                description: "ALTER TABLE".to_string(),
+                query: "ALTER TABLE".to_string(),
+            },
+        ),
+        (
+            "dro".to_string(),
+            Snippet {
+                shortcut: "dro".to_string(),
+                description: "DROP TABLE".to_string(),
+                query: "DROP TABLE".to_string(),
+            },
+        ),
+        (
+            "trun".to_string(),
+            Snippet {
+                shortcut: "trun".to_string(),
+                description: "TRUNCATE TABLE".to_string(),
+                query: "TRUNCATE TABLE".to_string(),
+            },
+        ),
+        (
+            "cq".to_string(),
+            Snippet {
+                shortcut: "cq".to_string(),
+                description: "Current queries".to_string(),
+                query: "SHOW FULL PROCESSLIST;".to_string(),
+            },
+        ),
+        (
+            "ds".to_string(),
+            Snippet {
+                shortcut: "ds".to_string(),
+                description: "Databases sizes".to_string(),
+                query: "SELECT table_schema AS database_name, SUM(data_length + index_length) AS size_bytes
+FROM information_schema.tables
+GROUP BY table_schema
+ORDER BY size_bytes DESC;"
+                    .to_string(),
+            },
+        ),
+        (
+            "ts".to_string(),
+            Snippet {
+                shortcut: "ts".to_string(),
+                description: "Tables sizes".to_string(),
+                query: "SELECT table_name, table_rows, data_length, index_length
+FROM information_schema.tables
+WHERE table_schema = DATABASE()
+ORDER BY data_length + index_length DESC;"
+                    .to_string(),
+            },
+        ),
+        (
+            "tx".to_string(),
+            Snippet {
+                shortcut: "tx".to_string(),
+                description: "Running transactions".to_string(),
+                query: "SELECT trx_id, trx_state, trx_started, trx_mysql_thread_id, trx_query
+FROM information_schema.innodb_trx
+ORDER BY trx_started;"
+                    .to_string(),
+            },
+        ),
+        (
+            "ix".to_string(),
+            Snippet {
+                shortcut: "ix".to_string(),
+                description: "Indexes".to_string(),
+                query: "SELECT table_name, index_name, GROUP_CONCAT(column_name ORDER BY seq_in_index) AS columns
+FROM information_schema.statistics
+WHERE table_schema = DATABASE()
+GROUP BY table_name, index_name
+ORDER BY table_name, index_name;"
+                    .to_string(),
+            },
+        ),
+    ])
+}
@@ -3,22 +3,35 @@ use tuirealm::{
     Application, Frame, Sub, SubClause, SubEventClause,
 };
 
+use std::collections::HashMap;
+
+use tuirealm::{
+    props::{PropPayload, PropValue, TextSpan},
+    AttrValue, Attribute,
+};
+
 use crate::{
     app::{DbResponse, TisqEvent},
-    components::{PressedKey, StatusSpan, StatusSpinner},
+    components::{PressedKey, StatusError, StatusSpan, StatusSpinner, TransactionSpan},
     tui::{Id, Msg},
 };
 
 use super::{
     model::TisqApplication,
     spinner_ticking_port::{self, SpinnerTickingPort},
-    DbRequest,
+    DbRequest, EditorId, TransactionState, TransactionStatus,
 };
 
 pub(crate) struct AppStatus {
     requests_in_progress: i32,
     doing: String,
     pub(crate) query_processing: bool,
+    transactions: HashMap<EditorId, TransactionStatus>,
+    // what is currently set in transaction span
+    shown_transaction: String,
+    // failure to show until the next request
+    error: String,
+    shown_error: String,
 }
 
 impl Default for AppStatus {
@@ -27,6 +40,10 @@ impl Default for AppStatus {
             requests_in_progress: 0,
             doing: String::new(),
             query_processing: false,
+            transactions: HashMap::new(),
+            shown_transaction: String::new(),
+            error: String::new(),
+            shown_error: String::new(),
         }
     }
 }
@@ -39,7 +56,33 @@ impl AppStatus {
     ) {
         self.requests_in_progress += 1;
         self.query_processing = true;
+        self.error.clear();
+        spinner_ticking_port.set_ticking(true);
+    }
+
+    /// Shows spinner with given text while app is busy with something else than database
+    pub(super) fn push_task(
+        &mut self,
+        doing: &str,
+        app: &mut TisqApplication,
+        spinner_ticking_port: &mut SpinnerTickingPort,
+    ) {
+        self.requests_in_progress += 1;
+        self.query_processing = true;
+        self.error.clear();
+        self.doing = doing.to_string();
         spinner_ticking_port.set_ticking(true);
+        StatusSpan::show(app, doing);
+    }
+
+    pub(super) fn pop_task(
+        &mut self,
+        app: &mut TisqApplication,
+        spinner_ticking_port: &mut SpinnerTickingPort,
+    ) {
+        self.doing.clear();
+        StatusSpan::reset(app);
+        self.pop_db_request(spinner_ticking_port);
     }
 
     pub(super) fn pop_db_request(&mut self, spinner_ticking_port: &mut SpinnerTickingPort) {
@@ -56,12 +99,51 @@ impl AppStatus {
         spinner_ticking_port.set_ticking(self.query_processing);
     }
 
-    pub(super) fn view(&self, rect: Rect, f: &mut Frame, app: &mut TisqApplication) {
+    /// Shows error in place of spinner, while nothing is processed
+    pub(super) fn show_error(&mut self, message: &str) {
+        tracing::error!("{}", message);
+        self.error = message.to_string();
+    }
+
+    pub(super) fn transaction_status(&self, editor_id: &EditorId) -> Option<&TransactionStatus> {
+        self.transactions.get(editor_id)
+    }
+
+    pub(super) fn set_transaction_status(
+        &mut self,
+        editor_id: EditorId,
+        status: TransactionStatus,
+    ) {
+        self.transactions.insert(editor_id, status);
+    }
+
+    fn transaction_text(&self, shown_editor: Option<&EditorId>) -> String {
+        let status = match shown_editor.and_then(|id| self.transactions.get(id)) {
+            Some(status) => status,
+            None => return String::new(),
+        };
+        match (status.manual, status.state) {
+            (_, TransactionState::Aborted) => "transaction aborted",
+            (_, TransactionState::Active) => "in transaction",
+            (true, TransactionState::Idle) => "manual commit",
+            (false, TransactionState::Idle) => "",
+        }
+        .to_string()
+    }
+
+    pub(super) fn view(
+        &mut self,
+        rect: Rect,
+        f: &mut Frame,
+        app: &mut TisqApplication,
+        shown_editor: Option<&EditorId>,
+    ) {
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(vec![
                 Constraint::Length(2),
                 Constraint::Min(0),     // fills remaining space
+                Constraint::Length(20), // "transaction aborted" is the longest text
                 Constraint::Length(20), // Ctrl+Shift+Alt+Enter is the longest combination
             ])
             .split(rect);
@@ -69,8 +151,36 @@ impl AppStatus {
         if self.query_processing {
             app.view(&Id::StatusSpinner, f, chunks[0]);
             app.view(&Id::StatusSpan, f, chunks[1]);
+        } else if !self.error.is_empty() {
+            if self.error != self.shown_error {
+                let _ = app.attr(
+                    &Id::StatusError,
+                    Attribute::Text,
+                    AttrValue::Payload(PropPayload::Vec(vec![PropValue::TextSpan(TextSpan::new(
+                        &self.error,
+                    ))])),
+                );
+                self.shown_error = self.error.clone();
+            }
+            app.view(&Id::StatusError, f, chunks[1]);
+        }
+
+        let transaction = self.transaction_text(shown_editor);
+        if transaction != self.shown_transaction {
+            let _ = app.attr(
+                &Id::StatusTransaction,
+                Attribute::Text,
+                AttrValue::Payload(PropPayload::Vec(vec![PropValue::TextSpan(TextSpan::new(
+                    &transaction,
+                ))])),
+            );
+            self.shown_transaction = transaction;
         }
-        app.view(&Id::StatusPressedKey, f, chunks[2]);
+        if !self.shown_transaction.is_empty() {
+            app.view(&Id::StatusTransaction, f, chunks[2]);
+        }
+
+        app.view(&Id::StatusPressedKey, f, chunks[3]);
     }
 
     pub(super) fn mount_spinner(app: &mut TisqApplication) {
@@ -95,7 +205,27 @@ impl AppStatus {
 
     pub(super) fn mount_span(app: &mut TisqApplication) {
         assert!(app
-            .mount(Id::StatusSpan, Box::new(StatusSpan::default()), vec![])
+            .mount(
+                Id::StatusSpan,
+                Box::new(StatusSpan::default()),
+                StatusSpan::subscriptions()
+            )
+            .is_ok());
+    }
+
+    pub(super) fn mount_transaction(app: &mut TisqApplication) {
+        assert!(app
+            .mount(
+                Id::StatusTransaction,
+                Box::new(TransactionSpan::default()),
+                vec![]
+            )
+            .is_ok());
+    }
+
+    pub(super) fn mount_error(app: &mut TisqApplication) {
+        assert!(app
+            .mount(Id::StatusError, Box::new(StatusError::default()), vec![])
             .is_ok());
     }
 
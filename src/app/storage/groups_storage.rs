@@ -0,0 +1,253 @@
+use kv::{Bucket, Json, Store};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::id::Id;
+use super::Storage;
+
+const GROUPS_BUCKET: &str = "groups";
+
+// separates names of nested groups in entered path
+const GROUP_PATH_SEPARATOR: char = '/';
+
+/// Named folder of servers in browser, which could be put into another group
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct StoredGroup {
+    pub id: Uuid,
+    pub name: String,
+    // None for groups at the top level
+    pub parent_id: Option<Uuid>,
+}
+
+impl Storage {
+    /// Adds group with given name inside of the parent group
+    pub fn add_group(&mut self, name: &str, parent_id: Option<Uuid>) -> eyre::Result<Uuid> {
+        let name = self.check_group_name(name, parent_id, None)?;
+        let group = StoredGroup {
+            id: Uuid::new_v4(),
+            name,
+            parent_id,
+        };
+        self.put_group(&group)?;
+        Ok(group.id)
+    }
+
+    pub fn rename_group(&mut self, id: Uuid, name: &str) -> eyre::Result<()> {
+        let mut group = self
+            .get_group(id)?
+            .ok_or_else(|| eyre::eyre!("Group is not found"))?;
+        group.name = self.check_group_name(name, group.parent_id, Some(id))?;
+        self.put_group(&group)
+    }
+
+    /// Moves group into the one with given path, which could not be inside of the moved group
+    pub fn move_group(&mut self, id: Uuid, path: &str) -> eyre::Result<()> {
+        let mut group = self
+            .get_group(id)?
+            .ok_or_else(|| eyre::eyre!("Group is not found"))?;
+        let parent_id = self.group_by_path(path)?;
+        let groups = self.read_groups()?;
+        let mut ancestor_id = parent_id;
+        while let Some(current_id) = ancestor_id {
+            if current_id == id {
+                return Err(eyre::eyre!("Group could not be moved inside of itself"));
+            }
+            ancestor_id = groups
+                .iter()
+                .find(|group| group.id == current_id)
+                .and_then(|group| group.parent_id);
+        }
+        group.name = self.check_group_name(&group.name, parent_id, Some(id))?;
+        group.parent_id = parent_id;
+        self.put_group(&group)
+    }
+
+    /// Moves server into the group with given path, empty path means the top level
+    pub fn move_server(&mut self, server_id: Uuid, path: &str) -> eyre::Result<()> {
+        let group_id = self.group_by_path(path)?;
+        let mut server = self
+            .get_server(server_id)?
+            .ok_or_else(|| eyre::eyre!("Server is not found"))?;
+        server.group_id = group_id;
+        self.update_server(server)
+    }
+
+    /// Deletes group, servers and groups inside of it are moved to its parent
+    pub fn delete_group(&mut self, id: Uuid) -> eyre::Result<()> {
+        let group = match self.get_group(id)? {
+            Some(group) => group,
+            None => return Ok(()),
+        };
+        for mut server in self.read_servers()? {
+            if server.group_id == Some(id) {
+                server.group_id = group.parent_id;
+                self.update_server(server)?;
+            }
+        }
+        for mut child in self.read_groups()? {
+            if child.parent_id == Some(id) {
+                child.parent_id = group.parent_id;
+                self.put_group(&child)?;
+            }
+        }
+        let store = self.get_store()?;
+        let bucket = Self::get_groups_bucket(&store)?;
+        bucket.remove(&Id(id))?;
+        Ok(())
+    }
+
+    pub fn get_group(&self, id: Uuid) -> eyre::Result<Option<StoredGroup>> {
+        let store = self.get_store()?;
+        let bucket = Self::get_groups_bucket(&store)?;
+        let group: Option<Json<StoredGroup>> = bucket.get(&Id(id))?;
+        Ok(group.map(|Json(group)| group))
+    }
+
+    pub fn read_groups(&self) -> eyre::Result<Vec<StoredGroup>> {
+        let store = self.get_store()?;
+        let groups: eyre::Result<Vec<StoredGroup>> = Self::get_groups_bucket(&store)?
+            .iter()
+            .map(|item| {
+                let Json(group): Json<StoredGroup> = item?.value()?;
+                Ok(group)
+            })
+            .collect();
+        groups
+    }
+
+    /// Finds group by names separated with `/`, missing groups are created,
+    /// returns None for empty path, which means the top level
+    fn group_by_path(&mut self, path: &str) -> eyre::Result<Option<Uuid>> {
+        let groups = self.read_groups()?;
+        let mut parent_id = None;
+        for name in path
+            .split(GROUP_PATH_SEPARATOR)
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            let existing = groups
+                .iter()
+                .find(|group| group.parent_id == parent_id && group.name == name);
+            parent_id = Some(match existing {
+                Some(group) => group.id,
+                None => self.add_group(name, parent_id)?,
+            });
+        }
+        Ok(parent_id)
+    }
+
+    /// Trims the name and checks that it is not used by other group with the same parent
+    fn check_group_name(
+        &self,
+        name: &str,
+        parent_id: Option<Uuid>,
+        id: Option<Uuid>,
+    ) -> eyre::Result<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(eyre::eyre!("Group name could not be empty"));
+        }
+        if name.contains(GROUP_PATH_SEPARATOR) {
+            return Err(eyre::eyre!(
+                "Group name could not contain {}",
+                GROUP_PATH_SEPARATOR
+            ));
+        }
+        let taken = self.read_groups()?.iter().any(|group| {
+            group.parent_id == parent_id && group.name == name && Some(group.id) != id
+        });
+        if taken {
+            return Err(eyre::eyre!("Group {} already exists there", name));
+        }
+        Ok(name.to_string())
+    }
+
+    fn put_group(&mut self, group: &StoredGroup) -> eyre::Result<()> {
+        let store = self.get_store()?;
+        let bucket = Self::get_groups_bucket(&store)?;
+        bucket.set(&Id(group.id), &Json(group.clone()))?;
+        Ok(())
+    }
+
+    fn get_groups_bucket<'a>(store: &Store) -> eyre::Result<Bucket<'a, Id, Json<StoredGroup>>> {
+        let bucket = store.bucket(Some(GROUPS_BUCKET))?;
+        Ok(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::super::storage::with_storage;
+    use super::super::NewServer;
+    use super::*;
+
+    fn group_named(storage: &Storage, name: &str) -> StoredGroup {
+        storage
+            .read_groups()
+            .unwrap()
+            .into_iter()
+            .find(|group| group.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn path_creates_missing_nested_groups() {
+        with_storage(|storage| {
+            let eu_id = storage.group_by_path(" prod / eu /").unwrap();
+            let prod = group_named(storage, "prod");
+            let eu = group_named(storage, "eu");
+            assert_eq!(eu_id, Some(eu.id));
+            assert_eq!(prod.parent_id, None);
+            assert_eq!(eu.parent_id, Some(prod.id));
+
+            // existing groups are found, not created again
+            assert_eq!(storage.group_by_path("prod/eu").unwrap(), Some(eu.id));
+            assert_eq!(storage.read_groups().unwrap().len(), 2);
+            assert_eq!(storage.group_by_path("").unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn group_could_not_be_moved_into_its_descendant() {
+        with_storage(|storage| {
+            let outer_id = storage.add_group("outer", None).unwrap();
+            storage.add_group("inner", Some(outer_id)).unwrap();
+
+            let error = storage.move_group(outer_id, "outer/inner").unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "Group could not be moved inside of itself"
+            );
+            assert_eq!(
+                storage.get_group(outer_id).unwrap().unwrap().parent_id,
+                None
+            );
+        });
+    }
+
+    #[test]
+    fn deleted_group_leaves_its_content_to_parent() {
+        with_storage(|storage| {
+            let parent_id = storage.add_group("parent", None).unwrap();
+            let deleted_id = storage.add_group("deleted", Some(parent_id)).unwrap();
+            let child_id = storage.add_group("child", Some(deleted_id)).unwrap();
+            let server_id = storage
+                .add_server(NewServer {
+                    name: "local".to_string(),
+                    connection_properties: HashMap::new(),
+                })
+                .unwrap();
+            storage.move_server(server_id, "parent/deleted").unwrap();
+
+            storage.delete_group(deleted_id).unwrap();
+
+            assert!(storage.get_group(deleted_id).unwrap().is_none());
+            let child = storage.get_group(child_id).unwrap().unwrap();
+            assert_eq!(child.parent_id, Some(parent_id));
+            let server = storage.get_server(server_id).unwrap().unwrap();
+            assert_eq!(server.group_id, Some(parent_id));
+        });
+    }
+}
@@ -0,0 +1,221 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use kv::{Bucket, Error, Json, Key, Raw, Store};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Storage;
+
+const HISTORY_BUCKET: &str = "history";
+
+// oldest entries are removed once history grows over this size
+const HISTORY_LIMIT: usize = 1000;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct HistoryEntry {
+    pub server_id: Uuid,
+    pub database: String,
+    pub query: String,
+    // milliseconds since unix epoch
+    pub executed_at: u64,
+    pub duration_ms: u64,
+    pub rows: usize,
+    // whether query returned more rows than were fetched
+    pub more_rows: bool,
+    pub error: Option<String>,
+}
+
+/// Time of execution in microseconds, big endian so that entries are ordered by it
+struct HistoryKey([u8; 8]);
+
+impl AsRef<[u8]> for HistoryKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Key<'_> for HistoryKey {
+    fn from_raw_key(r: &'_ Raw) -> Result<Self, Error> {
+        let key = r
+            .as_ref()
+            .try_into()
+            .map_err(|_| kv::Error::Message("Failed to parse history key".to_owned()))?;
+        Ok(HistoryKey(key))
+    }
+
+    fn to_raw_key(&self) -> Result<Raw, Error> {
+        Ok(self.as_ref().into())
+    }
+}
+
+impl HistoryEntry {
+    pub(crate) fn now_millis() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default()
+    }
+}
+
+/// Tells if query could set password, like `ALTER USER .. PASSWORD '..'`
+/// or `IDENTIFIED BY '..'`, as history is stored as plain text.
+/// Any query mentioning password together with a literal is treated so
+fn sets_password(query: &str) -> bool {
+    let query = query.to_lowercase();
+    let mentions_password = query
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|word| word == "password" || word == "identified");
+    mentions_password && query.contains(['\'', '"', '$'])
+}
+
+impl Storage {
+    /// Records executed query, unless it could contain password,
+    /// the same query executed before in the database is replaced
+    pub fn add_history_entry(&mut self, entry: HistoryEntry) -> eyre::Result<()> {
+        self.push_history_entry(entry, HISTORY_LIMIT)
+    }
+
+    fn push_history_entry(&mut self, entry: HistoryEntry, limit: usize) -> eyre::Result<()> {
+        if sets_password(&entry.query) {
+            return Ok(());
+        }
+        let store = self.get_store()?;
+        let bucket = Self::get_history_bucket(&store)?;
+
+        let mut keys = vec![];
+        for item in bucket.iter() {
+            let item = item?;
+            let key: HistoryKey = item.key()?;
+            let Json(stored): Json<HistoryEntry> = item.value()?;
+            if stored.server_id == entry.server_id
+                && stored.database == entry.database
+                && stored.query == entry.query
+            {
+                bucket.remove(&key)?;
+            } else {
+                keys.push(key);
+            }
+        }
+        if keys.len() >= limit {
+            for key in &keys[..keys.len() + 1 - limit] {
+                bucket.remove(key)?;
+            }
+        }
+
+        let mut micros = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_micros() as u64)
+            .unwrap_or_default();
+        // keys must stay unique even if clock did not move
+        while bucket.contains(&HistoryKey(micros.to_be_bytes()))? {
+            micros += 1;
+        }
+        bucket.set(&HistoryKey(micros.to_be_bytes()), &Json(entry))?;
+        Ok(())
+    }
+
+    /// Reads history of the database, most recent queries go first
+    pub fn read_history(&self, server_id: Uuid, database: &str) -> eyre::Result<Vec<HistoryEntry>> {
+        let store = self.get_store()?;
+        let mut entries = vec![];
+        for item in Self::get_history_bucket(&store)?.iter() {
+            let Json(entry): Json<HistoryEntry> = item?.value()?;
+            if entry.server_id == server_id && entry.database == database {
+                entries.push(entry);
+            }
+        }
+        entries.reverse();
+        Ok(entries)
+    }
+
+    fn get_history_bucket<'a>(
+        store: &Store,
+    ) -> eyre::Result<Bucket<'a, HistoryKey, Json<HistoryEntry>>> {
+        let bucket = store.bucket(Some(HISTORY_BUCKET))?;
+        Ok(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::storage::with_storage;
+    use super::*;
+
+    fn entry(query: &str) -> HistoryEntry {
+        HistoryEntry {
+            server_id: Uuid::nil(),
+            database: "postgres".to_string(),
+            query: query.to_string(),
+            executed_at: HistoryEntry::now_millis(),
+            duration_ms: 1,
+            rows: 0,
+            more_rows: false,
+            error: None,
+        }
+    }
+
+    fn queries(storage: &Storage) -> Vec<String> {
+        storage
+            .read_history(Uuid::nil(), "postgres")
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.query)
+            .collect()
+    }
+
+    #[test]
+    fn most_recent_queries_go_first() {
+        with_storage(|storage| {
+            for query in ["select 1", "select 2", "select 3"] {
+                storage.add_history_entry(entry(query)).unwrap();
+            }
+            let mut other = entry("select 4");
+            other.database = "other".to_string();
+            storage.add_history_entry(other).unwrap();
+
+            assert_eq!(queries(storage), ["select 3", "select 2", "select 1"]);
+        });
+    }
+
+    #[test]
+    fn oldest_entries_are_removed_over_limit() {
+        with_storage(|storage| {
+            for query in ["select 1", "select 2", "select 3", "select 4"] {
+                storage.push_history_entry(entry(query), 3).unwrap();
+            }
+            assert_eq!(queries(storage), ["select 4", "select 3", "select 2"]);
+        });
+    }
+
+    #[test]
+    fn repeated_query_replaces_previous_entry() {
+        with_storage(|storage| {
+            for query in ["select 1", "select 2", "select 1"] {
+                storage.push_history_entry(entry(query), 2).unwrap();
+            }
+            assert_eq!(queries(storage), ["select 1", "select 2"]);
+
+            // the same query in other database is kept separately
+            let mut other = entry("select 1");
+            other.database = "other".to_string();
+            storage.add_history_entry(other).unwrap();
+            assert_eq!(queries(storage), ["select 1", "select 2"]);
+        });
+    }
+
+    #[test]
+    fn queries_with_passwords_are_not_recorded() {
+        with_storage(|storage| {
+            for query in [
+                "ALTER USER app PASSWORD 'secret'",
+                "create role app with login password $$secret$$",
+                "CREATE USER 'app'@'%' IDENTIFIED BY \"secret\"",
+                "select password from accounts",
+            ] {
+                storage.add_history_entry(entry(query)).unwrap();
+            }
+            assert_eq!(queries(storage), ["select password from accounts"]);
+        });
+    }
+}
@@ -1,5 +1,9 @@
 mod editors_storage;
+mod groups_storage;
+mod history_storage;
 mod id;
 mod storage;
 
+pub(crate) use groups_storage::StoredGroup;
+pub(crate) use history_storage::HistoryEntry;
 pub(crate) use storage::*;
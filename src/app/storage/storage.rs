@@ -10,12 +10,16 @@ pub(crate) struct Storage {
 }
 
 const SERVERS_BUCKET: &str = "servers";
+const MASTER_KEY: &str = "master_key";
 
 #[derive(Serialize, Deserialize)]
 pub(crate) struct StoredServer {
     pub id: Uuid,
     pub name: String,
     pub connection_properties: HashMap<String, String>,
+    // None for servers at the top level of browser
+    #[serde(default)]
+    pub group_id: Option<Uuid>,
 }
 
 pub(crate) struct NewServer {
@@ -23,8 +27,18 @@ pub(crate) struct NewServer {
     pub connection_properties: HashMap<String, String>,
 }
 
+/// Salt of master passphrase and some known text encrypted with it,
+/// which tells if entered passphrase is the right one
+#[derive(Serialize, Deserialize)]
+pub(crate) struct StoredMasterKey {
+    pub salt: String,
+    pub check: String,
+}
+
 impl Storage {
-    pub fn add_server(&mut self, server: NewServer) -> eyre::Result<()> {
+    /// Adds server and returns its id, properties are stored as given,
+    /// so password has to be taken out or encrypted before
+    pub fn add_server(&mut self, server: NewServer) -> eyre::Result<Uuid> {
         let store = self.get_store()?;
         let bucket = Self::get_servers_bucket(&store)?;
         let id = Uuid::new_v4();
@@ -32,12 +46,50 @@ impl Storage {
             id,
             name: server.name,
             connection_properties: server.connection_properties,
+            group_id: None,
         };
         bucket.set(&Id(id), &Json(server))?;
         // bucket.set()
+        Ok(id)
+    }
+
+    /// Replaces name and connection properties of the server with the same id,
+    /// properties are stored as given, the same way as when adding server
+    pub fn update_server(&mut self, server: StoredServer) -> eyre::Result<()> {
+        let store = self.get_store()?;
+        let bucket = Self::get_servers_bucket(&store)?;
+        bucket.set(&Id(server.id), &Json(server))?;
         Ok(())
     }
 
+    /// Adds servers, which are not stored yet under the same name or with the same
+    /// connection properties, returns names of added servers
+    pub fn import_servers(
+        &mut self,
+        servers: Vec<(String, HashMap<String, String>)>,
+    ) -> eyre::Result<Vec<String>> {
+        let mut known: Vec<(String, HashMap<String, String>)> = self
+            .read_servers()?
+            .into_iter()
+            .map(|server| (server.name, server.connection_properties))
+            .collect();
+        let mut imported = vec![];
+        for (name, connection_properties) in servers {
+            if known.iter().any(|(known_name, known_properties)| {
+                *known_name == name || *known_properties == connection_properties
+            }) {
+                continue;
+            }
+            self.add_server(NewServer {
+                name: name.clone(),
+                connection_properties: connection_properties.clone(),
+            })?;
+            known.push((name.clone(), connection_properties));
+            imported.push(name);
+        }
+        Ok(imported)
+    }
+
     pub fn delete_server(&mut self, id: Uuid) -> eyre::Result<()> {
         let store = self.get_store()?;
         let bucket = Self::get_servers_bucket(&store)?;
@@ -94,6 +146,20 @@ impl Storage {
         Ok(enabled)
     }
 
+    pub fn get_master_key(&self) -> eyre::Result<Option<StoredMasterKey>> {
+        let store = self.get_store()?;
+        let bucket = store.bucket(Some("settings"))?;
+        let master_key: Option<Json<StoredMasterKey>> = bucket.get(&MASTER_KEY.to_string())?;
+        Ok(master_key.map(|Json(master_key)| master_key))
+    }
+
+    pub fn set_master_key(&mut self, master_key: StoredMasterKey) -> eyre::Result<()> {
+        let store = self.get_store()?;
+        let bucket = store.bucket(Some("settings"))?;
+        bucket.set(&MASTER_KEY.to_string(), &Json(master_key))?;
+        Ok(())
+    }
+
     pub(crate) fn get_store(&self) -> eyre::Result<Store> {
         let storage_path = self.files_root.join("storage");
         let cfg = Config::new(storage_path);
@@ -125,5 +191,45 @@ impl Storage {
         })
     }
 
+    /// Copies all buckets into new store, which then replaces the old one,
+    /// as values replaced in the store could still be found in its files
+    pub fn rewrite(&mut self) -> eyre::Result<()> {
+        let storage_path = self.files_root.join("storage");
+        let rewritten_path = self.files_root.join("storage.rewritten");
+        let replaced_path = self.files_root.join("storage.replaced");
+        for path in [&rewritten_path, &replaced_path] {
+            if path.exists() {
+                fs::remove_dir_all(path)?;
+            }
+        }
+        {
+            let store = self.get_store()?;
+            let rewritten = Store::new(Config::new(rewritten_path.clone()))?;
+            for name in store.buckets() {
+                let bucket = store.bucket::<Raw, Raw>(Some(name.as_str()))?;
+                let target = rewritten.bucket::<Raw, Raw>(Some(name.as_str()))?;
+                for item in bucket.iter() {
+                    let item = item?;
+                    target.set(&item.key::<Raw>()?, &item.value::<Raw>()?)?;
+                }
+                target.flush()?;
+            }
+        }
+        // old store is only removed once the new one is in place
+        fs::rename(&storage_path, &replaced_path)?;
+        fs::rename(&rewritten_path, &storage_path)?;
+        fs::remove_dir_all(&replaced_path)?;
+        Ok(())
+    }
+
     // fn (&self) {}
 }
+
+/// Runs test with storage in new folder, which is removed afterwards
+#[cfg(test)]
+pub(crate) fn with_storage(test: impl FnOnce(&mut Storage)) {
+    let files_root = std::env::temp_dir().join(format!("tisq-{}", Uuid::new_v4()));
+    let mut storage = Storage::open(&files_root).unwrap();
+    test(&mut storage);
+    fs::remove_dir_all(files_root).unwrap();
+}
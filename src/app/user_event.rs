@@ -1,12 +1,19 @@
 use crate::components::SentTree;
 
-use super::{connection::DbResponse, EditorId};
+use super::{
+    connection::{DbResponse, Notification},
+    EditorId,
+};
 
 #[derive(PartialOrd, Clone, Eq, Debug)]
 pub(crate) enum TisqEvent {
     TreeReloaded(SentTree),
     DbResponse(DbResponse),
+    // notification from listened channel, it is not a response for any request
+    NotificationReceived(Notification),
     SpinnerTick,
+    // key derived from master passphrase in another thread is ready
+    MasterKeyDerived,
     EditorContentAdd(EditorId, String), // TODO: use attr instead of UserEvent
     EditorSnippetResolve {
         editor_id: EditorId,
@@ -21,8 +28,10 @@ impl PartialEq for TisqEvent {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::SpinnerTick, Self::SpinnerTick) => true,
+            (Self::MasterKeyDerived, Self::MasterKeyDerived) => true,
             (Self::TreeReloaded(_), Self::TreeReloaded(_)) => true,
             (Self::DbResponse(_), Self::DbResponse(_)) => true,
+            (Self::NotificationReceived(_), Self::NotificationReceived(_)) => true,
             (Self::EditorContentAdd(_, _), Self::EditorContentAdd(_, _)) => true,
             (
                 Self::EditorSnippetResolve {
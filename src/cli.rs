@@ -1,6 +1,7 @@
 use argh::FromArgs;
 
 use crate::app::cmd::add_server::AddServerArgs;
+use crate::app::cmd::import_servers::ImportServersArgs;
 
 #[derive(FromArgs)]
 /// tisq - a TUI for SQL
@@ -35,4 +36,5 @@ pub(crate) struct Servers {
 #[argh(subcommand)]
 pub(crate) enum ServerSubcommands {
     AddServer(AddServerArgs),
+    ImportServers(ImportServersArgs),
 }
@@ -2,17 +2,19 @@ use std::path::PathBuf;
 
 use argh::FromArgs;
 
+use crate::app::ask_for_password;
 use crate::app::storage::{NewServer, Storage};
 
 pub(crate) fn run(add_server_args: AddServerArgs, files_root: &PathBuf) -> eyre::Result<()> {
     let mut storage = Storage::open(files_root)?;
+    let mut connection_properties = std::collections::HashMap::new();
+    connection_properties.insert("url".to_string(), add_server_args.connection_url);
+    if ask_for_password(&mut connection_properties) {
+        println!("Password is not stored, it will be asked when connecting to the server");
+    }
     let server = NewServer {
         name: add_server_args.name,
-        connection_properties: {
-            let mut map = std::collections::HashMap::new();
-            map.insert("url".to_string(), add_server_args.connection_url);
-            map
-        },
+        connection_properties,
     };
     storage.add_server(server)?;
     Ok(())
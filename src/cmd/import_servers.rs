@@ -0,0 +1,23 @@
+use std::path::PathBuf;
+
+use argh::FromArgs;
+
+use crate::app::discover_servers;
+use crate::app::storage::Storage;
+
+pub(crate) fn run(_: ImportServersArgs, files_root: &PathBuf) -> eyre::Result<()> {
+    let mut storage = Storage::open(files_root)?;
+    let imported = storage.import_servers(discover_servers())?;
+    if imported.is_empty() {
+        println!("No new servers found in pg_service.conf or PG* environment variables");
+    }
+    for name in imported {
+        println!("Imported server {}", name);
+    }
+    Ok(())
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Import servers from pg_service.conf and PG* environment variables
+#[argh(subcommand, name = "import")]
+pub(crate) struct ImportServersArgs {}
@@ -1 +1,2 @@
 pub(crate) mod add_server;
+pub(crate) mod import_servers;
@@ -0,0 +1,63 @@
+use tui_realm_stdlib::Radio;
+use tuirealm::{
+    command::{Cmd, CmdResult, Direction},
+    event::{Key, KeyEvent, KeyEventKind},
+    props::{Alignment, BorderType, Borders, Color},
+    Component, Event, MockComponent,
+};
+
+use crate::{app::TisqEvent, Msg};
+
+/// Choice of one of few values, which are switched with Left and Right
+#[derive(MockComponent)]
+pub(crate) struct ChoiceInput {
+    component: Radio,
+}
+
+impl ChoiceInput {
+    /// The first choice is selected by default
+    pub(crate) fn new(title: &str, choices: &[&str]) -> Self {
+        Self {
+            component: Radio::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(Color::LightYellow),
+                )
+                .foreground(Color::LightYellow)
+                .title(title, Alignment::Left)
+                .rewind(true)
+                .choices(choices)
+                .value(0),
+        }
+    }
+}
+
+impl Component<Msg, TisqEvent> for ChoiceInput {
+    fn on(&mut self, ev: Event<TisqEvent>) -> Option<Msg> {
+        let _ = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Up,
+                kind: KeyEventKind::Press,
+                ..
+            }) => return Some(Msg::FocusPreviousInput),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down | Key::Tab,
+                kind: KeyEventKind::Press,
+                ..
+            }) => return Some(Msg::FocusNextInput),
+            Event::Keyboard(KeyEvent {
+                code: Key::Left,
+                kind: KeyEventKind::Press,
+                ..
+            }) => self.perform(Cmd::Move(Direction::Left)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right,
+                kind: KeyEventKind::Press,
+                ..
+            }) => self.perform(Cmd::Move(Direction::Right)),
+            _ => CmdResult::None,
+        };
+        Some(Msg::None)
+    }
+}
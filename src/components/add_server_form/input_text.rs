@@ -3,7 +3,7 @@ use tuirealm::{
     command::{Cmd, CmdResult, Direction, Position},
     event::{Key, KeyEvent, KeyEventKind},
     props::{Alignment, BorderType, Borders, Color, InputType, Style},
-    Component, Event, MockComponent,
+    AttrValue, Attribute, Component, Event, MockComponent,
 };
 
 use crate::{app::TisqEvent, Msg};
@@ -29,6 +29,15 @@ impl InputText {
                 .invalid_style(Style::default().fg(Color::Red)),
         }
     }
+
+    /// Hides entered text, f.e for passwords
+    pub(crate) fn masked(mut self) -> Self {
+        self.attr(
+            Attribute::InputType,
+            AttrValue::InputType(InputType::Password('*')),
+        );
+        self
+    }
 }
 
 impl Component<Msg, TisqEvent> for InputText {
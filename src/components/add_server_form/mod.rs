@@ -1,28 +1,161 @@
-use std::io::Stdout;
+use std::{collections::HashMap, io::Stdout};
 
 use tuirealm::{
     application::ApplicationResult,
+    props::{Color, PropPayload, PropValue},
     tui::{
         prelude::{Constraint, CrosstermBackend, Direction, Layout, Rect},
         Frame,
     },
+    AttrValue, Attribute, State, StateValue,
 };
 
-use crate::{app::model::TisqApplication, Id};
+use crate::{
+    app::{
+        model::TisqApplication, split_url_password, storage::StoredServer, PasswordStorage,
+        ServerSettings, SessionTimeouts,
+    },
+    Id,
+};
 
+mod choice_input;
 pub mod input_text;
 mod submit;
+mod test_connection;
 
+pub(crate) use choice_input::ChoiceInput;
 pub use submit::FormSubmitListener;
+pub(crate) use test_connection::{AddServerFormStatus, TestConnectionButton};
+
+/// Values of `sslmode` understood by Postgres, the first one is the default
+pub(crate) const SSL_MODES: [&str; 6] = [
+    "prefer",
+    "disable",
+    "allow",
+    "require",
+    "verify-ca",
+    "verify-full",
+];
+
+// in order of `PASSWORD_STORAGE_VALUES`
+pub(crate) const PASSWORD_STORAGES: [&str; 3] = ["Ask on connect", "Encrypted", "Not needed"];
+const PASSWORD_STORAGE_VALUES: [Option<PasswordStorage>; 3] = [
+    Some(PasswordStorage::Ask),
+    Some(PasswordStorage::Encrypted),
+    None,
+];
+
+// in order of focusing with Tab
+const INPUTS: [Id; 13] = [
+    Id::ServerNameInput,
+    Id::ServerHostInput,
+    Id::ServerPortInput,
+    Id::ServerUserInput,
+    Id::ServerPasswordInput,
+    Id::PasswordStorageInput,
+    Id::DefaultDatabaseInput,
+    Id::SslModeInput,
+    Id::ConnectionUrlInput,
+    Id::StatementTimeoutInput,
+    Id::LockTimeoutInput,
+    Id::IdleTimeoutInput,
+    Id::TestConnectionButton,
+];
+
+// properties which are used to make connection url
+const URL_FIELDS: [(&str, Id); 5] = [
+    ("host", Id::ServerHostInput),
+    ("port", Id::ServerPortInput),
+    ("user", Id::ServerUserInput),
+    ("password", Id::ServerPasswordInput),
+    ("database", Id::DefaultDatabaseInput),
+];
+
+// in order of timeout properties, empty means server default
+const TIMEOUT_INPUTS: [Id; 3] = [
+    Id::StatementTimeoutInput,
+    Id::LockTimeoutInput,
+    Id::IdleTimeoutInput,
+];
 
 pub(crate) struct AddServerForm {
     pub(crate) active_input: Id,
+    // server, which is changed instead of adding the new one
+    pub(crate) editing: Option<StoredServer>,
 }
 
 impl AddServerForm {
     pub(crate) fn new() -> Self {
         Self {
             active_input: Id::ServerNameInput,
+            editing: None,
+        }
+    }
+
+    /// Fills mounted form with properties of the server, password is left empty,
+    /// as it is not stored as is
+    pub(crate) fn editing(app: &mut TisqApplication, server: StoredServer) -> Self {
+        let properties = &server.connection_properties;
+        let _ = app.attr(
+            &Id::ServerNameInput,
+            Attribute::Value,
+            AttrValue::String(server.name.clone()),
+        );
+        let fields = URL_FIELDS
+            .iter()
+            .map(|(property, id)| (*property, id))
+            .chain(
+                SessionTimeouts::PROPERTIES
+                    .into_iter()
+                    .zip(TIMEOUT_INPUTS.iter()),
+            )
+            .chain([("url", &Id::ConnectionUrlInput)]);
+        for (property, id) in fields {
+            if let Some(value) = properties.get(property) {
+                let _ = app.attr(id, Attribute::Value, AttrValue::String(value.clone()));
+            }
+        }
+        // imported connection service is edited as url, which names the service
+        if !properties.contains_key("url") && properties.contains_key("service") {
+            if let Some(settings) = ServerSettings::from_properties(properties) {
+                let _ = app.attr(
+                    &Id::ConnectionUrlInput,
+                    Attribute::Value,
+                    AttrValue::String(settings.url),
+                );
+            }
+        }
+
+        let ssl_mode = properties
+            .get("sslmode")
+            .and_then(|mode| SSL_MODES.iter().position(|known| known == mode))
+            .unwrap_or_default();
+        let password_storage = PASSWORD_STORAGE_VALUES
+            .iter()
+            .position(|storage| *storage == PasswordStorage::of(properties))
+            .unwrap_or_default();
+        for (id, choice) in [
+            (Id::SslModeInput, ssl_mode),
+            (Id::PasswordStorageInput, password_storage),
+        ] {
+            let _ = app.attr(
+                &id,
+                Attribute::Value,
+                AttrValue::Payload(PropPayload::One(PropValue::Usize(choice))),
+            );
+        }
+
+        Self::show_status(
+            app,
+            &format!(
+                "Editing {}, empty password keeps the current one",
+                server.name
+            ),
+            Color::LightYellow,
+        );
+        Self {
+            active_input: Id::ServerNameInput,
+            editing: Some(server),
         }
     }
 
@@ -30,11 +163,11 @@ impl AddServerForm {
         &mut self,
         app: &mut TisqApplication,
     ) -> ApplicationResult<()> {
-        self.active_input = match self.active_input {
-            Id::ServerNameInput => Id::ConnectionUrlInput,
-            Id::ConnectionUrlInput => Id::ServerNameInput,
-            _ => Id::ServerNameInput,
-        };
+        let position = INPUTS
+            .iter()
+            .position(|id| *id == self.active_input)
+            .unwrap_or_default();
+        self.active_input = INPUTS[(position + 1) % INPUTS.len()].clone();
         app.active(&self.active_input)
     }
 
@@ -42,14 +175,135 @@ impl AddServerForm {
         &mut self,
         app: &mut TisqApplication,
     ) -> ApplicationResult<()> {
-        self.active_input = match self.active_input {
-            Id::ServerNameInput => Id::ConnectionUrlInput,
-            Id::ConnectionUrlInput => Id::ServerNameInput,
-            _ => Id::ServerNameInput,
-        };
+        let position = INPUTS
+            .iter()
+            .position(|id| *id == self.active_input)
+            .unwrap_or_default();
+        self.active_input = INPUTS[(position + INPUTS.len() - 1) % INPUTS.len()].clone();
         app.active(&self.active_input)
     }
 
+    pub(crate) fn server_name(&self, app: &TisqApplication) -> Result<String, String> {
+        let name = Self::input_value(app, &Id::ServerNameInput)
+            .trim()
+            .to_string();
+        if name.is_empty() {
+            return Err("Server name is required".to_string());
+        }
+        Ok(name)
+    }
+
+    /// Reads entered properties of connection, checking that url could be made of them,
+    /// either host or url is required and they could not be used together
+    pub(crate) fn connection_properties(
+        &self,
+        app: &TisqApplication,
+    ) -> Result<HashMap<String, String>, String> {
+        let mut properties = HashMap::new();
+        let fields = URL_FIELDS
+            .iter()
+            .map(|(property, id)| (*property, id))
+            .chain(
+                SessionTimeouts::PROPERTIES
+                    .into_iter()
+                    .zip(TIMEOUT_INPUTS.iter()),
+            );
+        for (property, id) in fields {
+            let value = Self::input_value(app, id);
+            // password is taken as is, spaces could be a part of it
+            let value = if property == "password" {
+                value
+            } else {
+                value.trim().to_string()
+            };
+            if !value.is_empty() {
+                properties.insert(property.to_string(), value);
+            }
+        }
+
+        let url = Self::input_value(app, &Id::ConnectionUrlInput)
+            .trim()
+            .to_string();
+        let uses_fields = URL_FIELDS
+            .iter()
+            .any(|(property, _)| properties.contains_key(*property));
+        if !url.is_empty() {
+            if uses_fields {
+                return Err(
+                    "Connection URL could not be used together with fields above".to_string(),
+                );
+            }
+            properties.insert("url".to_string(), url);
+            return Ok(properties);
+        }
+
+        if !properties.contains_key("host") {
+            return Err("Host or connection URL is required".to_string());
+        }
+        if let Some(port) = properties.get("port") {
+            if !matches!(port.parse::<u16>(), Ok(port) if port > 0) {
+                return Err(format!(
+                    "Port should be a number from 1 to 65535, not {}",
+                    port
+                ));
+            }
+        }
+        if let Ok(State::One(StateValue::Usize(choice))) = app.state(&Id::SslModeInput) {
+            properties.insert("sslmode".to_string(), SSL_MODES[choice].to_string());
+        }
+        Ok(properties)
+    }
+
+    /// How entered password should be kept, checking that it is entered if it is needed
+    pub(crate) fn password_storage(
+        &self,
+        app: &TisqApplication,
+        properties: &HashMap<String, String>,
+    ) -> Result<Option<PasswordStorage>, String> {
+        let storage = match app.state(&Id::PasswordStorageInput) {
+            Ok(State::One(StateValue::Usize(choice))) => PASSWORD_STORAGE_VALUES[choice],
+            _ => PASSWORD_STORAGE_VALUES[0],
+        };
+        let has_password = properties.contains_key("password")
+            || properties
+                .get("url")
+                .is_some_and(|url| split_url_password(url).1.is_some());
+        // edited server could keep already encrypted password
+        let encrypted = self.editing.as_ref().is_some_and(|server| {
+            PasswordStorage::of(&server.connection_properties) == Some(PasswordStorage::Encrypted)
+        });
+        match storage {
+            Some(PasswordStorage::Encrypted) if !has_password && !encrypted => {
+                Err("Password is required to be encrypted".to_string())
+            }
+            None if has_password => {
+                Err("Password is entered, but it is chosen to be not needed".to_string())
+            }
+            _ => Ok(storage),
+        }
+    }
+
+    /// Shows problem with entered values or outcome of testing connection
+    pub(crate) fn show_status(app: &mut TisqApplication, text: &str, color: Color) {
+        let _ = app.attr(
+            &Id::AddServerFormStatus,
+            Attribute::Text,
+            AttrValue::String(text.to_string()),
+        );
+        let _ = app.attr(
+            &Id::AddServerFormStatus,
+            Attribute::Foreground,
+            AttrValue::Color(color),
+        );
+    }
+
+    fn input_value(app: &TisqApplication, id: &Id) -> String {
+        match app.state(id) {
+            Ok(State::One(StateValue::String(value))) => value,
+            _ => String::new(),
+        }
+    }
+
     pub(crate) fn view(
         &self,
         area: Rect,
@@ -61,12 +315,47 @@ impl AddServerForm {
             .constraints(vec![
                 Constraint::Length(3),
                 Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Length(1),
                 Constraint::Min(0), // fills remaining space
             ])
             .margin(1)
             .split(area);
 
+        let host_and_port = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(75), Constraint::Percentage(25)])
+            .split(chunks[1]);
+        let user_and_password = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[2]);
+        let button = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Length(21), Constraint::Min(0)])
+            .split(chunks[10]);
+
         app.view(&Id::ServerNameInput, f, chunks[0]);
-        app.view(&Id::ConnectionUrlInput, f, chunks[1]);
+        app.view(&Id::ServerHostInput, f, host_and_port[0]);
+        app.view(&Id::ServerPortInput, f, host_and_port[1]);
+        app.view(&Id::ServerUserInput, f, user_and_password[0]);
+        app.view(&Id::ServerPasswordInput, f, user_and_password[1]);
+        app.view(&Id::PasswordStorageInput, f, chunks[3]);
+        app.view(&Id::DefaultDatabaseInput, f, chunks[4]);
+        app.view(&Id::SslModeInput, f, chunks[5]);
+        app.view(&Id::ConnectionUrlInput, f, chunks[6]);
+        app.view(&Id::StatementTimeoutInput, f, chunks[7]);
+        app.view(&Id::LockTimeoutInput, f, chunks[8]);
+        app.view(&Id::IdleTimeoutInput, f, chunks[9]);
+        app.view(&Id::TestConnectionButton, f, button[0]);
+        app.view(&Id::AddServerFormStatus, f, chunks[11]);
     }
 }
@@ -6,20 +6,40 @@ use tuirealm::{
 
 use crate::{app::TisqEvent, Id, Msg};
 
-#[derive(Default, MockComponent)]
+/// Submits given form on Enter
+#[derive(MockComponent)]
 pub struct FormSubmitListener {
     component: Phantom,
+    form: Id,
 }
 
 impl FormSubmitListener {
-    pub(crate) fn get_subscription() -> Sub<Id, TisqEvent> {
+    pub(crate) fn new(form: Id) -> Self {
+        Self {
+            component: Phantom::default(),
+            form,
+        }
+    }
+
+    /// Listens for Enter while given input of the form is mounted,
+    /// unless some prompt is shown in place of the form
+    pub(crate) fn get_subscription(input: Id) -> Sub<Id, TisqEvent> {
+        let mut clause = SubClause::IsMounted(input.clone());
+        for prompt in [Id::PasswordPromptInput, Id::GroupPromptInput] {
+            if input != prompt {
+                clause = SubClause::And(
+                    Box::new(clause),
+                    Box::new(SubClause::Not(Box::new(SubClause::IsMounted(prompt)))),
+                );
+            }
+        }
         Sub::new(
             SubEventClause::Keyboard(KeyEvent {
                 code: Key::Enter,
                 modifiers: KeyModifiers::NONE,
                 kind: KeyEventKind::Press,
             }),
-            SubClause::IsMounted(Id::ConnectionUrlInput),
+            clause,
         )
     }
 }
@@ -31,7 +51,12 @@ impl Component<Msg, TisqEvent> for FormSubmitListener {
                 code: Key::Enter,
                 modifiers: KeyModifiers::NONE,
                 kind: KeyEventKind::Press,
-            }) => Some(Msg::SubmitAddServerForm),
+            }) => match self.form {
+                Id::BindParametersForm => Some(Msg::SubmitBindParameters),
+                Id::PasswordPrompt => Some(Msg::SubmitPassword),
+                Id::GroupPrompt => Some(Msg::SubmitGroupPrompt),
+                _ => Some(Msg::SubmitAddServerForm),
+            },
             _ => None,
         }
     }
@@ -0,0 +1,69 @@
+use tui_realm_stdlib::{Label, Paragraph};
+use tuirealm::{
+    event::{Key, KeyEvent, KeyEventKind},
+    props::{Alignment, BorderType, Borders, Color, TextSpan},
+    Component, Event, MockComponent,
+};
+
+use crate::{app::TisqEvent, Msg};
+
+/// Tests connection with entered properties on Enter,
+/// which is caught by submit listener of the form
+#[derive(MockComponent)]
+pub(crate) struct TestConnectionButton {
+    component: Paragraph,
+}
+
+impl Default for TestConnectionButton {
+    fn default() -> Self {
+        Self {
+            component: Paragraph::default()
+                .borders(
+                    Borders::default()
+                        .modifiers(BorderType::Rounded)
+                        .color(Color::LightYellow),
+                )
+                .foreground(Color::LightYellow)
+                .alignment(Alignment::Center)
+                .text(&[TextSpan::new("Test Connection")]),
+        }
+    }
+}
+
+impl Component<Msg, TisqEvent> for TestConnectionButton {
+    fn on(&mut self, ev: Event<TisqEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Up,
+                kind: KeyEventKind::Press,
+                ..
+            }) => Some(Msg::FocusPreviousInput),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down | Key::Tab,
+                kind: KeyEventKind::Press,
+                ..
+            }) => Some(Msg::FocusNextInput),
+            _ => Some(Msg::None),
+        }
+    }
+}
+
+/// Shows why the form could not be submitted or how testing of connection went
+#[derive(MockComponent)]
+pub(crate) struct AddServerFormStatus {
+    component: Label,
+}
+
+impl Default for AddServerFormStatus {
+    fn default() -> Self {
+        Self {
+            component: Label::default().text(""),
+        }
+    }
+}
+
+impl Component<Msg, TisqEvent> for AddServerFormStatus {
+    fn on(&mut self, _ev: Event<TisqEvent>) -> Option<Msg> {
+        None
+    }
+}
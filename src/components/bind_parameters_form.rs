@@ -0,0 +1,171 @@
+use std::io::Stdout;
+
+use tuirealm::{
+    application::ApplicationResult,
+    tui::{
+        prelude::{Constraint, CrosstermBackend, Direction, Layout, Rect},
+        Frame,
+    },
+    State, StateValue,
+};
+
+use crate::{
+    app::{model::TisqApplication, BindParameter, EditorId},
+    Id,
+};
+
+use super::{FormSubmitListener, InputText};
+
+const NULL_VALUE: &str = "NULL";
+
+/// Asks for values of placeholders found in the query before executing it
+pub(crate) struct BindParametersForm {
+    pub(crate) editor_id: EditorId,
+    pub(crate) query: String,
+    names: Vec<String>,
+    active_input: Id,
+}
+
+impl BindParametersForm {
+    pub(crate) fn mount(
+        app: &mut TisqApplication,
+        editor_id: EditorId,
+        query: String,
+        parameters: Vec<BindParameter>,
+    ) -> ApplicationResult<Self> {
+        for (index, parameter) in parameters.iter().enumerate() {
+            let value = parameter.value.as_deref().unwrap_or(NULL_VALUE);
+            app.mount(
+                Id::BindParameterValueInput(index),
+                Box::new(InputText::new(&parameter.name, value)),
+                vec![],
+            )?;
+            let type_name = parameter.type_name.as_deref().unwrap_or_default();
+            app.mount(
+                Id::BindParameterTypeInput(index),
+                Box::new(InputText::new("Type", type_name)),
+                vec![],
+            )?;
+        }
+        app.mount(
+            Id::BindParametersSubmitListener,
+            Box::new(FormSubmitListener::new(Id::BindParametersForm)),
+            vec![FormSubmitListener::get_subscription(
+                Id::BindParameterValueInput(0),
+            )],
+        )?;
+
+        let active_input = Id::BindParameterValueInput(0);
+        app.active(&active_input)?;
+        Ok(Self {
+            editor_id,
+            query,
+            names: parameters
+                .into_iter()
+                .map(|parameter| parameter.name)
+                .collect(),
+            active_input,
+        })
+    }
+
+    pub(crate) fn unmount(&self, app: &mut TisqApplication) -> ApplicationResult<()> {
+        for index in 0..self.names.len() {
+            app.umount(&Id::BindParameterValueInput(index))?;
+            app.umount(&Id::BindParameterTypeInput(index))?;
+        }
+        app.umount(&Id::BindParametersSubmitListener)
+    }
+
+    /// Reads entered values, `NULL` is bound as null and empty type means text
+    pub(crate) fn parameters(&self, app: &TisqApplication) -> Vec<BindParameter> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let value = Self::input_value(app, &Id::BindParameterValueInput(index));
+                let type_name = Self::input_value(app, &Id::BindParameterTypeInput(index));
+                let type_name = type_name.trim();
+                BindParameter {
+                    name: name.clone(),
+                    value: if value.eq_ignore_ascii_case(NULL_VALUE) {
+                        None
+                    } else {
+                        Some(value)
+                    },
+                    type_name: if type_name.is_empty() {
+                        None
+                    } else {
+                        Some(type_name.to_string())
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn input_value(app: &TisqApplication, id: &Id) -> String {
+        match app.state(id) {
+            Ok(State::One(StateValue::String(value))) => value,
+            _ => String::new(),
+        }
+    }
+
+    fn inputs(&self) -> Vec<Id> {
+        (0..self.names.len())
+            .flat_map(|index| {
+                [
+                    Id::BindParameterValueInput(index),
+                    Id::BindParameterTypeInput(index),
+                ]
+            })
+            .collect()
+    }
+
+    pub(crate) fn activate_next_input(
+        &mut self,
+        app: &mut TisqApplication,
+    ) -> ApplicationResult<()> {
+        let inputs = self.inputs();
+        let position = inputs
+            .iter()
+            .position(|id| *id == self.active_input)
+            .unwrap_or_default();
+        self.active_input = inputs[(position + 1) % inputs.len()].clone();
+        app.active(&self.active_input)
+    }
+
+    pub(crate) fn activate_previous_input(
+        &mut self,
+        app: &mut TisqApplication,
+    ) -> ApplicationResult<()> {
+        let inputs = self.inputs();
+        let position = inputs
+            .iter()
+            .position(|id| *id == self.active_input)
+            .unwrap_or_default();
+        self.active_input = inputs[(position + inputs.len() - 1) % inputs.len()].clone();
+        app.active(&self.active_input)
+    }
+
+    pub(crate) fn view(
+        &self,
+        area: Rect,
+        app: &mut TisqApplication,
+        f: &mut Frame<CrosstermBackend<Stdout>>,
+    ) {
+        let mut constraints = vec![Constraint::Length(3); self.names.len()];
+        constraints.push(Constraint::Min(0)); // fills remaining space
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(area);
+
+        for index in 0..self.names.len() {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Percentage(70), Constraint::Percentage(30)])
+                .split(rows[index]);
+            app.view(&Id::BindParameterValueInput(index), f, columns[0]);
+            app.view(&Id::BindParameterTypeInput(index), f, columns[1]);
+        }
+    }
+}
@@ -12,18 +12,12 @@ use crate::{
     Msg,
 };
 
-pub enum BrowserTreeId {
-    Server(Uuid),
-    Database(Uuid, String),
-    Schema(Uuid, String, String),
-    Table(Uuid, String, String, String),
-    Column {
-        server_id: Uuid,
-        database: String,
-        schema: String,
-        table: String,
-        column: String,
-    },
+/// Node of browser tree, which is either server itself or object inside of it,
+/// like database or table, identified by names of all objects on the way to it
+pub struct BrowserTreeId {
+    server_id: Uuid,
+    // starts with database, empty for server node
+    path: Vec<String>,
 }
 
 impl BrowserTreeId {
@@ -36,6 +30,7 @@ impl BrowserTreeId {
             }
         }
     }
+
     fn parse_str(s: &str) -> Option<Self> {
         let mut parts = s.splitn(2, ':');
         let section = parts.next()?;
@@ -44,78 +39,76 @@ impl BrowserTreeId {
             "server" => {
                 let node: &str = parts.next()?;
                 let server_id = Self::parse_server_uuid(node)?;
-                Some(Self::Server(server_id))
-            }
-            "database" => {
-                let node: &str = parts.next()?;
-                let mut parts = node.splitn(2, ':');
-                let server_id = parts.next()?;
-                let server_id = Self::parse_server_uuid(server_id)?;
-                Some(Self::Database(server_id, parts.next()?.to_string()))
-            }
-            "schema" => {
-                let node: &str = parts.next()?;
-                let mut parts = node.splitn(3, ':');
-                let server_id = parts.next()?;
-                let server_id = Self::parse_server_uuid(server_id)?;
-                Some(Self::Schema(
+                Some(Self {
                     server_id,
-                    parts.next()?.to_string(),
-                    parts.next()?.to_string(),
-                ))
-            }
-            "table" => {
-                let node: &str = parts.next()?;
-                let mut parts = node.splitn(4, ':');
-                let server_id = parts.next()?;
-                let server_id = Self::parse_server_uuid(server_id)?;
-                Some(Self::Table(
-                    server_id,
-                    parts.next()?.to_string(),
-                    parts.next()?.to_string(),
-                    parts.next()?.to_string(),
-                ))
+                    path: vec![],
+                })
             }
-            "column" => {
+            "object" => {
                 let node: &str = parts.next()?;
-                let mut parts = node.splitn(4, ':');
+                let mut parts = node.splitn(2, ':');
                 let server_id = parts.next()?;
                 let server_id = Self::parse_server_uuid(server_id)?;
-                Some(Self::Column {
+                Some(Self {
                     server_id,
-                    database: parts.next()?.to_string(),
-                    schema: parts.next()?.to_string(),
-                    table: parts.next()?.to_string(),
-                    column: parts.next()?.to_string(),
+                    path: Self::split_path(parts.next()?),
                 })
             }
             _ => None,
         }
     }
 
-    fn to_string(&self) -> String {
-        match self {
-            Self::Server(id) => format!("server:{}", id),
-            Self::Database(server_id, name) => format!("database:{}:{}", server_id, name),
-            Self::Schema(server_id, database, name) => {
-                format!("schema:{}:{}:{}", server_id, database, name)
-            }
-            Self::Table(server_id, database, schema, table) => {
-                format!("table:{}:{}:{}:{}", server_id, database, schema, table)
-            }
-            Self::Column {
-                server_id,
-                database,
-                schema,
-                table,
-                column,
-            } => {
-                format!(
-                    "column:{}:{}:{}:{}:{}",
-                    server_id, database, schema, table, column
-                )
+    // names could contain separator, so it is escaped
+    fn split_path(s: &str) -> Vec<String> {
+        let mut path = vec![String::new()];
+        let mut chars = s.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => path.last_mut().unwrap().extend(chars.next()),
+                ':' => path.push(String::new()),
+                _ => path.last_mut().unwrap().push(ch),
             }
         }
+        path
+    }
+
+    fn to_string(&self) -> String {
+        if self.path.is_empty() {
+            return format!("server:{}", self.server_id);
+        }
+        let path = self
+            .path
+            .iter()
+            .map(|name| name.replace('\\', "\\\\").replace(':', "\\:"))
+            .collect::<Vec<_>>()
+            .join(":");
+        format!("object:{}:{}", self.server_id, path)
+    }
+
+    fn child(&self, name: &str) -> Self {
+        let mut path = self.path.clone();
+        path.push(name.to_string());
+        Self {
+            server_id: self.server_id,
+            path,
+        }
+    }
+}
+
+/// Server or group, which browsed node belongs to
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub(crate) enum BrowsedItem {
+    Server(Uuid),
+    Group(Uuid),
+}
+
+impl BrowsedItem {
+    fn parse_str(s: &str) -> Option<Self> {
+        if let Some(group_id) = s.strip_prefix("group:") {
+            return Uuid::parse_str(group_id).ok().map(BrowsedItem::Group);
+        }
+        let BrowserTreeId { server_id, .. } = BrowserTreeId::parse_str(s)?;
+        Some(BrowsedItem::Server(server_id))
     }
 }
 
@@ -171,24 +164,32 @@ impl BrowserTree {
             Some(id) => id,
             None => return Some(Msg::None),
         };
-        let database = match BrowserTreeId::parse_str(&selected_id) {
-            Some(BrowserTreeId::Database(_server_id, database)) => database,
-            _ => return Some(Msg::None),
-        };
+        match BrowserTreeId::parse_str(&selected_id) {
+            // database is always the first level under server
+            Some(BrowserTreeId { server_id, path }) if path.len() == 1 => {
+                Some(Msg::OpenQueryEditor(server_id, path[0].clone()))
+            }
+            _ => Some(Msg::None),
+        }
+    }
+
+    /// Edits server of the selected node, which could be any node inside of the server
+    fn edit_server(&self) -> Option<Msg> {
+        let selected_id = self.component.tree_state().selected()?;
+        let BrowserTreeId { server_id, .. } = BrowserTreeId::parse_str(selected_id)?;
+        Some(Msg::StartEditingServer(server_id))
+    }
 
-        if let Some(parent_node) = self
-            .component
+    fn selected_item(&self) -> Option<BrowsedItem> {
+        BrowsedItem::parse_str(self.component.tree_state().selected()?)
+    }
+
+    fn is_expandable(&self, id: &str) -> bool {
+        self.component
             .tree()
             .root()
-            .parent(&selected_id.to_string())
-        {
-            let server_id = match BrowserTreeId::parse_str(parent_node.id()) {
-                Some(BrowserTreeId::Server(server_id)) => server_id,
-                _ => return Some(Msg::None),
-            };
-            return Some(Msg::OpenQueryEditor(server_id, database));
-        }
-        return Some(Msg::None);
+            .query(&id.to_string())
+            .map_or(false, |node| !node.is_leaf())
     }
 }
 
@@ -206,6 +207,18 @@ impl Component<Msg, TisqEvent> for BrowserTree {
         let res_message = match ev {
             Event::Keyboard(kb_event) => match self.keybindings.get_action(&kb_event) {
                 Some(TisqKeyboundAction::BrowserAddServer) => Some(Msg::StartAddingServer),
+                Some(TisqKeyboundAction::BrowserEditServer) => self.edit_server(),
+                Some(TisqKeyboundAction::BrowserCreateGroup) => {
+                    Some(Msg::StartCreatingGroup(self.selected_item()))
+                }
+                Some(TisqKeyboundAction::BrowserRenameGroup) => match self.selected_item() {
+                    Some(BrowsedItem::Group(group_id)) => Some(Msg::StartRenamingGroup(group_id)),
+                    _ => None,
+                },
+                Some(TisqKeyboundAction::BrowserMoveToGroup) => {
+                    self.selected_item().map(Msg::StartMovingToGroup)
+                }
+                Some(TisqKeyboundAction::BrowserImportServers) => Some(Msg::ImportServers),
                 Some(TisqKeyboundAction::BrowserDatabaseOpenQueryEditor) => {
                     self.open_query_editor()
                 }
@@ -222,191 +235,43 @@ impl Component<Msg, TisqEvent> for BrowserTree {
                 return Some(Msg::None);
             }
 
-            Event::User(TisqEvent::DbResponse(DbResponse::ColumnsListed {
-                server_id,
-                database,
-                schema,
-                table,
-                columns,
-            })) => {
-                // tracing::debug!("Schemas listed: {:?}", tables);
-                let table_id: BrowserTreeId = BrowserTreeId::Table(
-                    server_id,
-                    database.clone(),
-                    schema.clone(),
-                    table.clone(),
-                );
-                let tree_id = table_id.to_string();
-                {
-                    let tree = self.component.tree_mut();
-                    let node = tree.root_mut().query_mut(&tree_id)?;
-                    node.clear();
-                    for column in columns {
-                        let id = BrowserTreeId::Column {
-                            server_id,
-                            database: database.clone(),
-                            schema: schema.clone(),
-                            table: table.clone(),
-                            column: column.clone(),
-                        }
-                        .to_string();
-                        let column_node = Node::new(id, column.clone());
-
-                        // dummy is created to make the table node expandable
-                        // it is a workaround for the limitation of the treeview component
-                        // let dummy: Node = Node::new(
-                        //     format!("dummy:{}/{}/{}/{}", server_id, database, schema),
-                        //     "loading tables...".to_string(),
-                        // );
-                        // table_node.add_child(dummy);
-
-                        node.add_child(column_node);
-                    }
-                }
-                // This solution can work as well without blocking, but requires
-                // open access to open_node method as well as new one tree_state_mut
-                let node = self.component.tree().root().query(&tree_id)?.clone();
-                let root = self.component.tree().root().clone();
-                self.component.tree_state_mut().open_node(&root, &node);
-
-                return Some(Msg::None);
-            }
-
-            Event::User(TisqEvent::DbResponse(DbResponse::TablesListed {
-                server_id,
-                database,
-                schema,
-                tables,
-            })) => {
-                // tracing::debug!("Schemas listed: {:?}", tables);
-                let schema_id = BrowserTreeId::Schema(server_id, database.clone(), schema.clone());
-                let tree_id = schema_id.to_string();
-                {
-                    let tree = self.component.tree_mut();
-                    let node = tree.root_mut().query_mut(&tree_id)?;
-                    node.clear();
-                    for table in tables {
-                        let id = BrowserTreeId::Table(
-                            server_id,
-                            database.clone(),
-                            schema.clone(),
-                            table.clone(),
-                        )
-                        .to_string();
-                        let mut table_node = Node::new(id, table.clone());
-
-                        // dummy is created to make the table node expandable
-                        // it is a workaround for the limitation of the treeview component
-                        let dummy: Node = Node::new(
-                            format!("dummy:{}/{}/{}/{}", server_id, database, schema, table),
-                            "loading tables...".to_string(),
-                        );
-                        table_node.add_child(dummy);
-
-                        node.add_child(table_node);
-                    }
-                }
-                // This solution can work as well without blocking, but requires
-                // open access to open_node method as well as new one tree_state_mut
-                let node = self.component.tree().root().query(&tree_id)?.clone();
-                let root = self.component.tree().root().clone();
-                self.component.tree_state_mut().open_node(&root, &node);
-
-                return Some(Msg::None);
-            }
-
-            Event::User(TisqEvent::DbResponse(DbResponse::SchemasListed {
+            Event::User(TisqEvent::DbResponse(DbResponse::ObjectsListed {
                 server_id,
-                database,
-                schemas,
+                path,
+                names,
+                inner_level,
             })) => {
-                // tracing::debug!("Schemas listed: {:?}", tables);
+                let parent_id = BrowserTreeId { server_id, path };
+                let tree_id = parent_id.to_string();
                 {
                     let tree = self.component.tree_mut();
-                    let tree_id = BrowserTreeId::Database(server_id, database.clone()).to_string();
                     let node = tree.root_mut().query_mut(&tree_id)?;
                     node.clear();
-                    for schema in schemas {
-                        let id = BrowserTreeId::Schema(server_id, database.clone(), schema.clone())
-                            .to_string();
-                        let mut schema_node = Node::new(id, schema.clone());
+                    for name in names {
+                        let id = parent_id.child(&name).to_string();
+                        let mut child_node = Node::new(id.clone(), name);
 
-                        // dummy is created to make the schema node expandable
+                        // dummy is created to make the node expandable
                         // it is a workaround for the limitation of the treeview component
-                        let dummy: Node = Node::new(
-                            format!("dummy:{}/{}/{}", server_id, database, schema),
-                            "loading tables...".to_string(),
-                        );
-                        schema_node.add_child(dummy);
-
-                        node.add_child(schema_node);
-                    }
-                }
-                // This solution can work as well without blocking, but requires
-                // open access to open_node method as well as new one tree_state_mut
-                let tree_id = BrowserTreeId::Database(server_id, database.clone());
-                let node = self
-                    .component
-                    .tree()
-                    .root()
-                    .query(&tree_id.to_string())?
-                    .clone();
-                let root = self.component.tree().root().clone();
-                self.component.tree_state_mut().open_node(&root, &node);
-
-                return Some(Msg::None);
-            }
-            Event::User(TisqEvent::DbResponse(DbResponse::DatabasesListed(
-                server_id,
-                databases,
-            ))) => {
-                // tracing::debug!("Databases listed: {:?}", databases);
-                {
-                    let tree = self.component.tree_mut();
-                    let tree_id = BrowserTreeId::Server(server_id);
-                    let node = tree.root_mut().query_mut(&tree_id.to_string())?;
-                    node.clear();
-                    for database in databases {
-                        let id = BrowserTreeId::Database(server_id, database.clone()).to_string();
-                        let mut database_node = Node::new(id, database.clone());
-
-                        // dummy is created to make the server node expandable
-                        // it is a workaround for the limitation of the treeview component
-                        let dummy: Node = Node::new(
-                            format!("dummy:{}/{}", server_id, database),
-                            "loading schemas...".to_string(),
-                        );
-                        database_node.add_child(dummy);
+                        if let Some(inner_level) = inner_level {
+                            let dummy: Node = Node::new(
+                                format!("dummy:{}", id),
+                                format!("loading {}...", inner_level.plural_name()),
+                            );
+                            child_node.add_child(dummy);
+                        }
 
-                        node.add_child(database_node);
+                        node.add_child(child_node);
                     }
                 }
 
                 // this can potentially not work if user has switched to another node
                 // in the meantime. To avoid this, we should block switching to another node
-                // while database is being opened, however that can have other side effects
-
-                // let tree_id = BrowserTreeId::Server(server_id.to_string());
-                // let node = self
-                //     .component
-                //     .tree()
-                //     .root()
-                //     .query(&tree_id.to_string())?
-                //     .clone();
-                // self.component.tree_state_mut().open(&node);
-
-                // if we do block switching to another node, then we can do this insead:
-                // self.perform(Cmd::Custom(TREE_CMD_OPEN))
+                // while object is being opened, however that can have other side effects
 
                 // This solution can work as well without blocking, but requires
                 // open access to open_node method as well as new one tree_state_mut
-                let tree_id = BrowserTreeId::Server(server_id);
-                let node = self
-                    .component
-                    .tree()
-                    .root()
-                    .query(&tree_id.to_string())?
-                    .clone();
+                let node = self.component.tree().root().query(&tree_id)?.clone();
                 let root = self.component.tree().root().clone();
                 self.component.tree_state_mut().open_node(&root, &node);
 
@@ -440,26 +305,14 @@ impl Component<Msg, TisqEvent> for BrowserTree {
                 match self.component.state() {
                     // if node is server, open connection
                     State::One(StateValue::String(id)) => match BrowserTreeId::parse_str(&id) {
-                        Some(BrowserTreeId::Server(server_id)) => {
+                        Some(BrowserTreeId { server_id, path }) if path.is_empty() => {
                             return Some(Msg::OpenConnection(server_id))
                         }
-                        Some(BrowserTreeId::Database(server_id, database)) => {
-                            return Some(Msg::OpenDatabase(server_id, database, 0))
-                        }
-                        Some(BrowserTreeId::Schema(server_id, database, schema)) => {
-                            return Some(Msg::OpenSchema {
-                                server_id,
-                                database,
-                                schema,
-                                retries: 0,
-                            })
-                        }
-                        Some(BrowserTreeId::Table(server_id, database, schema, table)) => {
-                            return Some(Msg::OpenTable {
+                        // objects without anything inside have no dummy child
+                        Some(BrowserTreeId { server_id, path }) if self.is_expandable(&id) => {
+                            return Some(Msg::OpenObject {
                                 server_id,
-                                database,
-                                schema,
-                                table,
+                                path,
                                 retries: 0,
                             })
                         }
@@ -32,14 +32,20 @@ use tui_realm_textarea::{
 };
 
 use crate::{
-    app::{EditorId, SectionKeybindings, TisqEvent, TisqKeyboundAction},
+    app::{statement_at, EditorId, SectionKeybindings, SqlDialect, TisqEvent, TisqKeyboundAction},
     Msg,
 };
 
+const EDITOR_TITLE: &str = "Query Editor";
+
 pub struct Editor<'a> {
     component: TextArea<'a>,
     keybindings: SectionKeybindings<TisqKeyboundAction>,
     editor_id: EditorId,
+    // of the database editor is connected to, decides where statements end
+    dialect: SqlDialect,
+    // row and column where user started selecting text
+    selection_start: Option<(usize, usize)>,
 }
 
 impl<'a> MockComponent for Editor<'a> {
@@ -68,11 +74,14 @@ impl<'a> Editor<'a> {
     pub(crate) fn new(
         editor_id: EditorId,
         keybindings: SectionKeybindings<TisqKeyboundAction>,
+        dialect: SqlDialect,
     ) -> Self {
         let textarea = TextArea::default();
         Self {
             editor_id,
+            dialect,
             keybindings,
+            selection_start: None,
             component: textarea
                 .borders(
                     Borders::default()
@@ -95,7 +104,7 @@ impl<'a> Editor<'a> {
                 // Style::default().add_modifier(TextModifiers::REVERSED),
                 // )
                 .tab_length(4)
-                .title("Query Editor", Alignment::Center),
+                .title(EDITOR_TITLE, Alignment::Center),
         }
     }
 
@@ -121,6 +130,105 @@ impl<'a> Editor<'a> {
             0,
         )
     }
+
+    fn cursor_position(&self) -> Option<(usize, usize)> {
+        match self
+            .component
+            .query(Attribute::Custom(TEXTAREA_CURSOR_POSITION))
+        {
+            Some(AttrValue::Payload(PropPayload::Tup2((
+                PropValue::Usize(row),
+                PropValue::Usize(column),
+            )))) => Some((row, column)),
+            _ => None,
+        }
+    }
+
+    /// Converts row and column into position of character in the whole text
+    fn offset_in_text(text: &str, (row, column): (usize, usize)) -> usize {
+        let mut offset = 0;
+        for (i, line) in text.split('\n').enumerate() {
+            let length = line.chars().count();
+            if i == row {
+                return offset + column.min(length);
+            }
+            offset += length + 1;
+        }
+        offset
+    }
+
+    fn statement_under_cursor(&self) -> Option<String> {
+        let text = self.get_text().unwrap_or_default();
+        Self::statement_at_position(&text, self.cursor_position()?, self.dialect)
+    }
+
+    fn statement_at_position(
+        text: &str,
+        position: (usize, usize),
+        dialect: SqlDialect,
+    ) -> Option<String> {
+        statement_at(text, Self::offset_in_text(text, position), dialect)
+    }
+
+    /// Returns selected text or statement under cursor if nothing is selected
+    fn selected_query(&mut self) -> Option<String> {
+        let (start, end) = match (self.selection_start, self.cursor_position()) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return self.statement_under_cursor(),
+        };
+        self.set_selection_start(None);
+
+        let text = self.get_text().unwrap_or_default();
+        let start = Self::offset_in_text(&text, start);
+        let end = Self::offset_in_text(&text, end);
+        let selected: String = text
+            .chars()
+            .skip(start.min(end))
+            .take(start.max(end) - start.min(end))
+            .collect();
+        if selected.trim().is_empty() {
+            return None;
+        }
+        Some(selected)
+    }
+
+    fn execute_statement_message(&mut self) -> Msg {
+        match self.statement_under_cursor() {
+            Some(statement) => Msg::ExecuteQuery(self.editor_id.clone(), statement, 0),
+            None => Msg::None,
+        }
+    }
+
+    fn execute_selection_message(&mut self) -> Msg {
+        match self.selected_query() {
+            Some(selected) => Msg::ExecuteQuery(self.editor_id.clone(), selected, 0),
+            None => Msg::None,
+        }
+    }
+
+    fn explain_message(&mut self, analyze: bool) -> Msg {
+        match self.selected_query() {
+            Some(query) => Msg::ExplainQuery(self.editor_id.clone(), query, analyze),
+            None => Msg::None,
+        }
+    }
+
+    fn set_selection_start(&mut self, selection_start: Option<(usize, usize)>) {
+        self.selection_start = selection_start;
+        let title = match selection_start {
+            Some((row, column)) => format!(
+                "{} (selecting from Ln {}, Col {})",
+                EDITOR_TITLE,
+                row + 1,
+                column + 1
+            ),
+            None => EDITOR_TITLE.to_string(),
+        };
+        self.component.attr(
+            Attribute::Title,
+            AttrValue::Title((title, Alignment::Center)),
+        );
+    }
 }
 
 impl<'a> Component<Msg, TisqEvent> for Editor<'a> {
@@ -202,6 +310,37 @@ impl<'a> Component<Msg, TisqEvent> for Editor<'a> {
                 //     Some(Msg::None)
                 // }
                 Some(&TisqKeyboundAction::EditorExecute) => Some(self.execute_message()),
+                Some(&TisqKeyboundAction::EditorExecuteStatement) => {
+                    Some(self.execute_statement_message())
+                }
+                Some(&TisqKeyboundAction::EditorExecuteSelection) => {
+                    Some(self.execute_selection_message())
+                }
+                Some(&TisqKeyboundAction::EditorToggleSelection) => {
+                    let selection_start = match self.selection_start {
+                        Some(_) => None,
+                        None => self.cursor_position(),
+                    };
+                    self.set_selection_start(selection_start);
+                    Some(Msg::None)
+                }
+                Some(&TisqKeyboundAction::EditorToggleTransactionMode) => {
+                    Some(Msg::ToggleTransactionMode(self.editor_id.clone()))
+                }
+                Some(&TisqKeyboundAction::EditorCommit) => {
+                    Some(Msg::CommitTransaction(self.editor_id.clone()))
+                }
+                Some(&TisqKeyboundAction::EditorRollback) => {
+                    Some(Msg::RollbackTransaction(self.editor_id.clone()))
+                }
+                Some(&TisqKeyboundAction::EditorExplain) => Some(self.explain_message(false)),
+                Some(&TisqKeyboundAction::EditorExplainAnalyze) => Some(self.explain_message(true)),
+                Some(&TisqKeyboundAction::EditorShowHistory) => {
+                    Some(Msg::ShowHistory(self.editor_id.clone()))
+                }
+                Some(&TisqKeyboundAction::EditorCancelQuery) => {
+                    Some(Msg::CancelQuery(self.editor_id.clone()))
+                }
                 Some(&TisqKeyboundAction::EditorPaste) => {
                     self.perform(Cmd::Custom(TEXTAREA_CMD_PASTE));
                     Some(Msg::None)
@@ -555,3 +694,53 @@ impl<'a> Component<Msg, TisqEvent> for Editor<'a> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement(text: &str, position: (usize, usize)) -> Option<String> {
+        Editor::statement_at_position(text, position, SqlDialect::Postgres)
+    }
+
+    #[test]
+    fn offset_counts_characters_of_previous_lines() {
+        let text = "select 'ä';\nselect 2";
+        assert_eq!(Editor::offset_in_text(text, (0, 4)), 4);
+        assert_eq!(Editor::offset_in_text(text, (1, 0)), 12);
+        // column past the end of line stays on that line
+        assert_eq!(Editor::offset_in_text(text, (0, 100)), 11);
+        assert_eq!(Editor::offset_in_text(text, (5, 0)), 21);
+    }
+
+    #[test]
+    fn statement_is_found_at_cursor() {
+        let text = "select 1; select 2;\n\nselect 'a;\n\nb'\n-- comment;\nfrom t";
+        assert_eq!(statement(text, (0, 3)).as_deref(), Some("select 1"));
+        assert_eq!(statement(text, (0, 12)).as_deref(), Some("select 2"));
+        assert_eq!(
+            statement(text, (4, 1)).as_deref(),
+            Some("select 'a;\n\nb'\n-- comment;\nfrom t")
+        );
+    }
+
+    #[test]
+    fn statement_before_cursor_is_taken_at_boundary() {
+        let text = "select 1;\nselect 2";
+        // right after the semicolon
+        assert_eq!(statement(text, (0, 9)).as_deref(), Some("select 1"));
+        assert_eq!(statement(text, (1, 0)).as_deref(), Some("select 2"));
+        // at the very end of text
+        assert_eq!(statement(text, (1, 8)).as_deref(), Some("select 2"));
+    }
+
+    #[test]
+    fn dollar_quotes_and_casts_do_not_split_statement() {
+        let text = "do $$\nbegin\n\n  perform 1::int;\nend\n$$;\nselect 2";
+        assert_eq!(
+            statement(text, (3, 4)).as_deref(),
+            Some("do $$\nbegin\n\n  perform 1::int;\nend\n$$")
+        );
+        assert_eq!(statement(text, (6, 0)).as_deref(), Some("select 2"));
+    }
+}
@@ -1,19 +1,14 @@
-
-
-use tui_realm_stdlib::{Paragraph};
+use tui_realm_stdlib::Paragraph;
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
-use tuirealm::props::{
-    Alignment, BorderSides, Borders, PropPayload, PropValue,
-    TextSpan,
-};
+use tuirealm::props::{Alignment, BorderSides, Borders, PropPayload, PropValue, TextSpan};
 
 use tuirealm::{
-    event::{Key, KeyEvent}, Component, Event, MockComponent,
+    event::{Key, KeyEvent},
+    Component, Event, MockComponent,
 };
 use tuirealm::{AttrValue, Attribute};
 // tui
 
-
 use crate::app::{DbResponse, TisqEvent};
 use crate::Msg;
 
@@ -39,6 +34,13 @@ impl Default for ErrorResult {
 }
 
 impl ErrorResult {
+    fn set_title(&mut self, title: &str) {
+        self.attr(
+            Attribute::Title,
+            AttrValue::Title((title.to_string(), Alignment::Center)),
+        );
+    }
+
     fn set_message(&mut self, message: String) {
         self.attr(
             Attribute::Text,
@@ -58,6 +60,17 @@ impl Component<Msg, TisqEvent> for ErrorResult {
     fn on(&mut self, ev: Event<TisqEvent>) -> Option<Msg> {
         let _ = match ev {
             Event::User(TisqEvent::DbResponse(DbResponse::Error(_, message))) => {
+                self.set_title("Execution Error");
+                self.set_message(message);
+                return Some(Msg::ShowErrorResult);
+            }
+            Event::User(TisqEvent::DbResponse(DbResponse::Cancelled(_))) => {
+                self.set_title("Execution Cancelled");
+                self.set_message("cancelled".to_string());
+                return Some(Msg::ShowErrorResult);
+            }
+            Event::User(TisqEvent::DbResponse(DbResponse::TimedOut(_, message))) => {
+                self.set_title("Execution Timed Out");
                 self.set_message(message);
                 return Some(Msg::ShowErrorResult);
             }
@@ -8,31 +8,50 @@ use tuirealm::{
     event::{Key, KeyEvent},
     Component, Event, MockComponent,
 };
-use tuirealm::{AttrValue, Attribute};
+use tuirealm::{AttrValue, Attribute, State, StateValue};
 
-use crate::app::{DbResponse, SectionKeybindings, TisqEvent, TisqKeyboundAction};
+use crate::app::{
+    CellValue, ColumnInfo, DbResponse, EditorId, FetchStatus, SectionKeybindings, TisqEvent,
+    TisqKeyboundAction,
+};
 use crate::Msg;
 
 #[derive(PartialEq, PartialOrd, Clone, Eq, Debug)]
 pub(crate) struct QueryResult {
-    pub headers: Vec<String>,
-    pub data: Vec<Vec<String>>,
+    pub columns: Vec<ColumnInfo>,
+    pub data: Vec<Vec<CellValue>>,
+}
+
+/// Result of one statement from executed script
+struct StatementResult {
+    result: QueryResult,
+    fetch_status: FetchStatus,
+    failed: bool,
 }
 
 #[derive(MockComponent)]
 pub(crate) struct ExecuteResultTable {
     component: Table,
-    result: Option<QueryResult>,
+    results: Vec<StatementResult>,
+    shown_statement: usize,
+    total_statements: usize,
     column_offset: usize,
     keybindings: SectionKeybindings<TisqKeyboundAction>,
+    // where the result came from, to know where to ask for more rows
+    source: Option<EditorId>,
+    fetch_requested: bool,
 }
 
 impl ExecuteResultTable {
     pub(crate) fn new(keybindings: SectionKeybindings<TisqKeyboundAction>) -> Self {
         Self {
             column_offset: 0,
-            result: None,
+            results: vec![],
+            shown_statement: 0,
+            total_statements: 0,
             keybindings,
+            source: None,
+            fetch_requested: false,
             component: Table::default()
                 .borders(
                     Borders::default()
@@ -56,14 +75,29 @@ impl ExecuteResultTable {
         }
     }
 
+    fn result(&self) -> Option<&QueryResult> {
+        self.results
+            .get(self.shown_statement)
+            .map(|statement| &statement.result)
+    }
+
     fn update_result(&mut self) {
-        if let Some(result) = self.result.as_ref() {
+        // borrowing only results, as component is updated meanwhile
+        let shown = self
+            .results
+            .get(self.shown_statement)
+            .map(|statement| &statement.result);
+        if let Some(result) = shown {
             let mut builder = TableBuilder::default();
+            let widths = self.widths(result);
 
             result.data.iter().for_each(|row| {
-                row.iter().skip(self.column_offset).for_each(|col| {
-                    builder.add_col(TextSpan::from(col));
-                });
+                row.iter()
+                    .skip(self.column_offset)
+                    .zip(widths.iter())
+                    .for_each(|(cell, width)| {
+                        builder.add_col(Self::cell_span(cell, *width as usize));
+                    });
                 builder.add_row();
             });
 
@@ -72,16 +106,14 @@ impl ExecuteResultTable {
                 AttrValue::Table(builder.build()),
             );
 
-            let widths = self.widths(&result).clone();
-
             self.attr(
                 Attribute::Text,
                 AttrValue::Payload(PropPayload::Vec(
                     result
-                        .headers
+                        .columns
                         .iter()
                         .skip(self.column_offset)
-                        .map(|x| PropValue::Str(x.to_string()))
+                        .map(|x| PropValue::Str(x.name.to_string()))
                         .collect(),
                 )),
             );
@@ -95,12 +127,96 @@ impl ExecuteResultTable {
         }
     }
 
-    fn set_result(&mut self, result: QueryResult, column_offet: usize) {
+    /// Numbers are aligned to the right to be easier to compare,
+    /// nulls are dimmed to not be confused with "null" text
+    fn cell_span(cell: &CellValue, width: usize) -> TextSpan {
+        let text = cell.to_string();
+        if cell.is_numeric() {
+            return TextSpan::from(format!("{:>width$}", text, width = width));
+        }
+        if cell.is_null() || cell.is_unsupported() {
+            return TextSpan::from(text).fg(Color::DarkGray).italic();
+        }
+        TextSpan::from(text)
+    }
+
+    fn add_result(&mut self, result: StatementResult, index: usize, total: usize) {
         // println!("Setting result: {:?}", result);
         // self.component.table(TableBuilder::default().build());
-        self.column_offset = column_offet;
-        self.result = Some(result);
+        if index == 0 {
+            self.results.clear();
+        }
+        self.results.push(result);
+        self.total_statements = total;
+        self.show_statement(self.results.len() - 1);
+    }
+
+    fn show_statement(&mut self, index: usize) {
+        self.shown_statement = index;
+        self.column_offset = 0;
+        self.update_result();
+        self.update_title();
+    }
+
+    fn append_rows(&mut self, data: Vec<Vec<CellValue>>, fetch_status: FetchStatus) {
+        // only the last statement of script could have more rows to fetch
+        if let Some(statement) = self.results.last_mut() {
+            statement.result.data.extend(data);
+            statement.fetch_status = fetch_status;
+        }
         self.update_result();
+        self.update_title();
+    }
+
+    fn fetch_status(&self) -> FetchStatus {
+        self.results
+            .get(self.shown_statement)
+            .map_or(FetchStatus::Done, |statement| statement.fetch_status)
+    }
+
+    fn update_title(&mut self) {
+        let statement = match self.results.get(self.shown_statement) {
+            Some(statement) => statement,
+            None => return,
+        };
+        let rows = statement.result.data.len();
+        let name = if self.total_statements > 1 {
+            format!(
+                "Statement {}/{}",
+                self.shown_statement + 1,
+                self.total_statements
+            )
+        } else {
+            "Query Result".to_string()
+        };
+        let title = match statement.fetch_status {
+            _ if statement.failed => format!("{} failed", name),
+            FetchStatus::Done => format!("{} ({} rows)", name, rows),
+            FetchStatus::More => format!("{} ({} rows, scroll down for more)", name, rows),
+            FetchStatus::LimitReached => format!("{} (first {} rows)", name, rows),
+        };
+        self.component.attr(
+            Attribute::Title,
+            AttrValue::Title((title, Alignment::Center)),
+        );
+    }
+
+    fn is_last_row_selected(&self) -> bool {
+        let rows = self.result().map_or(0, |result| result.data.len());
+        match self.component.state() {
+            State::One(StateValue::Usize(index)) => index + 1 >= rows,
+            _ => false,
+        }
+    }
+
+    /// Asks for next page of rows once user got to the end of fetched ones
+    fn fetch_more(&mut self) -> Option<Msg> {
+        if self.fetch_status() != FetchStatus::More || self.fetch_requested {
+            return None;
+        }
+        let source = self.source.clone()?;
+        self.fetch_requested = true;
+        Some(Msg::FetchMoreRows(source))
     }
 
     fn widths(&self, result: &QueryResult) -> Vec<u16> {
@@ -118,14 +234,14 @@ impl ExecuteResultTable {
             row.iter()
                 .skip(self.column_offset)
                 .enumerate()
-                .for_each(|(i, col)| update_widths(&mut absolute_widths, col, i));
+                .for_each(|(i, col)| update_widths(&mut absolute_widths, &col.to_string(), i));
         });
         result
-            .headers
+            .columns
             .iter()
             .skip(self.column_offset)
             .enumerate()
-            .for_each(|(i, col)| update_widths(&mut absolute_widths, col, i));
+            .for_each(|(i, col)| update_widths(&mut absolute_widths, &col.name, i));
         // tracing::debug!("widths: {:?}", widths);
         let total = absolute_widths.iter().sum::<u16>();
 
@@ -167,8 +283,8 @@ impl Component<Msg, TisqEvent> for ExecuteResultTable {
                     return Some(Msg::None);
                 }
                 Some(TisqKeyboundAction::ResultOffsetColumnRight) => {
-                    self.column_offset = if let Some(result) = self.result.as_ref() {
-                        if self.column_offset < result.headers.len() - 1 {
+                    self.column_offset = if let Some(result) = self.result() {
+                        if self.column_offset < result.columns.len().saturating_sub(1) {
                             self.column_offset + 1
                         } else {
                             self.column_offset
@@ -179,14 +295,75 @@ impl Component<Msg, TisqEvent> for ExecuteResultTable {
                     self.update_result();
                     return Some(Msg::None);
                 }
+                Some(TisqKeyboundAction::ResultNextStatement) => {
+                    if self.shown_statement + 1 < self.results.len() {
+                        self.show_statement(self.shown_statement + 1);
+                    }
+                    return Some(Msg::None);
+                }
+                Some(TisqKeyboundAction::ResultPrevStatement) => {
+                    if self.shown_statement > 0 {
+                        self.show_statement(self.shown_statement - 1);
+                    }
+                    return Some(Msg::None);
+                }
                 _ => (),
             }
         };
         let _ = match ev {
-            Event::User(TisqEvent::DbResponse(DbResponse::Executed(_, headers, data))) => {
-                self.set_result(QueryResult { headers, data }, 0);
+            Event::User(TisqEvent::DbResponse(DbResponse::Executed {
+                server_id,
+                database,
+                position,
+                columns,
+                data,
+                status,
+            })) => {
+                self.source = Some(EditorId::new(server_id, database));
+                self.fetch_requested = false;
+                let result = StatementResult {
+                    result: QueryResult { columns, data },
+                    fetch_status: status,
+                    failed: false,
+                };
+                self.add_result(result, position.index, position.total);
+                return Some(Msg::ShowFetchedTable);
+            }
+            Event::User(TisqEvent::DbResponse(DbResponse::StatementFailed {
+                server_id,
+                database,
+                position,
+                message,
+                ..
+            })) => {
+                self.source = Some(EditorId::new(server_id, database));
+                let result = StatementResult {
+                    result: QueryResult {
+                        columns: vec![ColumnInfo {
+                            name: "error".to_string(),
+                            type_name: "text".to_string(),
+                        }],
+                        data: vec![vec![CellValue::Text(message)]],
+                    },
+                    fetch_status: FetchStatus::Done,
+                    failed: true,
+                };
+                self.add_result(result, position.index, position.total);
                 return Some(Msg::ShowFetchedTable);
             }
+            Event::User(TisqEvent::DbResponse(DbResponse::RowsFetched {
+                server_id,
+                database,
+                data,
+                status,
+            })) => {
+                if self.source != Some(EditorId::new(server_id, database)) {
+                    return Some(Msg::None);
+                }
+                self.fetch_requested = false;
+                self.append_rows(data, status);
+                return Some(Msg::None);
+            }
             Event::User(TisqEvent::DbResponse(DbResponse::ConnectionIsDown {
                 original_request,
                 ..
@@ -237,7 +414,13 @@ impl Component<Msg, TisqEvent> for ExecuteResultTable {
                 code: Key::Down,
                 kind: KeyEventKind::Press,
                 modifiers: KeyModifiers::NONE,
-            }) => self.perform(Cmd::Move(Direction::Down)),
+            }) => {
+                // moving down from the last row would rewind to the first one
+                if self.is_last_row_selected() && self.fetch_status() == FetchStatus::More {
+                    return self.fetch_more().or(Some(Msg::None));
+                }
+                self.perform(Cmd::Move(Direction::Down))
+            }
             Event::Keyboard(KeyEvent {
                 code: Key::Up,
                 kind: KeyEventKind::Press,
@@ -247,7 +430,13 @@ impl Component<Msg, TisqEvent> for ExecuteResultTable {
                 code: Key::PageDown,
                 kind: KeyEventKind::Press,
                 modifiers: KeyModifiers::NONE,
-            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Down));
+                if self.is_last_row_selected() {
+                    return self.fetch_more().or(Some(Msg::None));
+                }
+                CmdResult::None
+            }
             Event::Keyboard(KeyEvent {
                 code: Key::PageUp,
                 kind: KeyEventKind::Press,
@@ -262,7 +451,10 @@ impl Component<Msg, TisqEvent> for ExecuteResultTable {
                 code: Key::End,
                 kind: KeyEventKind::Press,
                 modifiers: KeyModifiers::NONE,
-            }) => self.perform(Cmd::GoTo(Position::End)),
+            }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                return self.fetch_more().or(Some(Msg::None));
+            }
             _ => CmdResult::None,
         };
         Some(Msg::None)
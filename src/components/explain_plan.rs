@@ -0,0 +1,308 @@
+use serde_json::Value;
+use tui_realm_treeview::{Node, Tree, TreeView, TREE_CMD_CLOSE, TREE_CMD_OPEN};
+use tuirealm::{
+    command::{Cmd, Direction, Position},
+    event::{Key, KeyEvent, KeyEventKind, KeyModifiers},
+    props::{Alignment, BorderSides, Borders, Color, Style},
+    AttrValue, Attribute, Component, Event, MockComponent,
+};
+
+use crate::{
+    app::{DbResponse, TisqEvent},
+    Msg,
+};
+
+const PLAN_ROOT_ID: &str = "plan";
+
+// node taking at least this part of the whole cost or time is highlighted
+const COSTLY_SHARE: f64 = 0.3;
+// estimated rows differing from actual this many times are highlighted
+const ROWS_MISMATCH: f64 = 10.0;
+
+/// Measurements of analyzed node, rows and time are per loop
+struct ActualStats {
+    rows: f64,
+    loops: f64,
+    total_time: f64,
+}
+
+/// Node of the plan as reported by EXPLAIN with FORMAT JSON
+struct PlanNode {
+    node_type: String,
+    relation: Option<String>,
+    alias: Option<String>,
+    index: Option<String>,
+    total_cost: f64,
+    plan_rows: f64,
+    actual: Option<ActualStats>,
+    shared_buffers: Option<(u64, u64)>,
+    children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    fn parse(plan: &Value) -> Option<Self> {
+        let text = |key: &str| plan.get(key).and_then(Value::as_str).map(str::to_string);
+        let number = |key: &str| plan.get(key).and_then(Value::as_f64);
+        let actual = match (
+            number("Actual Rows"),
+            number("Actual Loops"),
+            number("Actual Total Time"),
+        ) {
+            (Some(rows), Some(loops), Some(total_time)) => Some(ActualStats {
+                rows,
+                loops,
+                total_time,
+            }),
+            // node that was never executed has no time reported
+            _ => None,
+        };
+        let shared_buffers = match (
+            plan.get("Shared Hit Blocks").and_then(Value::as_u64),
+            plan.get("Shared Read Blocks").and_then(Value::as_u64),
+        ) {
+            (Some(hit), Some(read)) => Some((hit, read)),
+            _ => None,
+        };
+        Some(Self {
+            node_type: text("Node Type")?,
+            relation: text("Relation Name"),
+            alias: text("Alias"),
+            index: text("Index Name"),
+            total_cost: number("Total Cost").unwrap_or_default(),
+            plan_rows: number("Plan Rows").unwrap_or_default(),
+            actual,
+            shared_buffers,
+            children: plan
+                .get("Plans")
+                .and_then(Value::as_array)
+                .map(|plans| plans.iter().filter_map(Self::parse).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Time spent in node if it was analyzed, estimated cost otherwise
+    fn weight(&self) -> f64 {
+        match &self.actual {
+            Some(actual) => actual.total_time * actual.loops,
+            None => self.total_cost,
+        }
+    }
+
+    /// Weight of the node itself, without its children
+    fn own_weight(&self) -> f64 {
+        let children: f64 = self.children.iter().map(PlanNode::weight).sum();
+        (self.weight() - children).max(0.0)
+    }
+
+    fn warnings(&self, total_weight: f64) -> Vec<String> {
+        let mut warnings = vec![];
+        if total_weight > 0.0 {
+            let share = self.own_weight() / total_weight;
+            if share >= COSTLY_SHARE {
+                let measure = if self.actual.is_some() {
+                    "time"
+                } else {
+                    "cost"
+                };
+                warnings.push(format!("{:.0}% of {}", share * 100.0, measure));
+            }
+        }
+        if let Some(actual) = &self.actual {
+            let (low, high) = if actual.rows < self.plan_rows {
+                (actual.rows, self.plan_rows)
+            } else {
+                (self.plan_rows, actual.rows)
+            };
+            if high / low.max(1.0) >= ROWS_MISMATCH {
+                warnings.push(format!(
+                    "estimated {} rows, actual {}",
+                    self.plan_rows, actual.rows
+                ));
+            }
+        }
+        if self.node_type == "Seq Scan" {
+            warnings.push("sequential scan".to_string());
+        }
+        warnings
+    }
+
+    fn label(&self, total_weight: f64) -> String {
+        let mut label = self.node_type.clone();
+        if let Some(index) = &self.index {
+            label.push_str(&format!(" using {}", index));
+        }
+        if let Some(relation) = &self.relation {
+            label.push_str(&format!(" on {}", relation));
+            match &self.alias {
+                Some(alias) if alias != relation => label.push_str(&format!(" {}", alias)),
+                _ => {}
+            }
+        }
+        label.push_str(&format!(
+            " (cost={:.2} rows={})",
+            self.total_cost, self.plan_rows
+        ));
+        if let Some(actual) = &self.actual {
+            label.push_str(&format!(
+                " (actual time={:.3}ms rows={} loops={})",
+                actual.total_time, actual.rows, actual.loops
+            ));
+        }
+        if let Some((hit, read)) = self.shared_buffers {
+            if hit > 0 || read > 0 {
+                label.push_str(&format!(" (buffers hit={} read={})", hit, read));
+            }
+        }
+        let warnings = self.warnings(total_weight);
+        if warnings.is_empty() {
+            label
+        } else {
+            format!("⚠ {} [{}]", label, warnings.join(", "))
+        }
+    }
+
+    fn to_node(&self, id: String, total_weight: f64, ids: &mut Vec<String>) -> Node {
+        let mut node = Node::new(id.clone(), self.label(total_weight));
+        for (index, child) in self.children.iter().enumerate() {
+            node.add_child(child.to_node(format!("{}.{}", id, index), total_weight, ids));
+        }
+        ids.push(id);
+        node
+    }
+}
+
+#[derive(MockComponent)]
+pub(crate) struct ExplainPlanTree {
+    component: TreeView,
+}
+
+impl Default for ExplainPlanTree {
+    fn default() -> Self {
+        Self {
+            component: TreeView::default()
+                .foreground(Color::Reset)
+                .borders(Borders::default().sides(BorderSides::NONE))
+                .inactive(Style::default().fg(Color::Gray))
+                .indent_size(3)
+                .scroll_step(6)
+                .title("Query Plan", Alignment::Center)
+                .highlighted_color(Color::LightYellow)
+                .highlight_symbol("🚀")
+                .with_tree(Tree::new(Node::new(
+                    PLAN_ROOT_ID.to_string(),
+                    "No plan".to_string(),
+                )))
+                .preserve_state(false),
+        }
+    }
+}
+
+impl ExplainPlanTree {
+    fn set_plan(&mut self, plan: &str, analyzed: bool) {
+        let explained: Option<Value> = serde_json::from_str(plan).ok();
+        // EXPLAIN returns array with one object for the statement
+        let explained = explained.as_ref().and_then(|value| value.get(0));
+        let top = explained
+            .and_then(|explained| explained.get("Plan"))
+            .and_then(PlanNode::parse);
+
+        let timing = |key: &str| {
+            explained
+                .and_then(|explained| explained.get(key))
+                .and_then(Value::as_f64)
+        };
+        let summary = match (timing("Planning Time"), timing("Execution Time")) {
+            (Some(planning), Some(execution)) => format!(
+                "Planning time {:.3}ms, execution time {:.3}ms",
+                planning, execution
+            ),
+            _ => "Plan".to_string(),
+        };
+
+        let mut root = Node::new(PLAN_ROOT_ID.to_string(), summary);
+        let mut ids = vec![PLAN_ROOT_ID.to_string()];
+        match top {
+            Some(top) => {
+                root.add_child(top.to_node("0".to_string(), top.weight(), &mut ids));
+            }
+            None => {
+                tracing::error!("Could not read plan: {}", plan);
+                root = Node::new(PLAN_ROOT_ID.to_string(), "Could not read plan".to_string());
+            }
+        }
+        self.component.set_tree(Tree::new(root));
+
+        // whole plan is shown expanded
+        let root = self.component.tree().root().clone();
+        for id in ids {
+            if let Some(node) = root.query(&id) {
+                self.component.tree_state_mut().open_node(&root, node);
+            }
+        }
+
+        let title = if analyzed {
+            "Query Plan (analyzed)"
+        } else {
+            "Query Plan"
+        };
+        self.attr(
+            Attribute::Title,
+            AttrValue::Title((title.to_string(), Alignment::Center)),
+        );
+    }
+}
+
+impl Component<Msg, TisqEvent> for ExplainPlanTree {
+    fn on(&mut self, ev: Event<TisqEvent>) -> Option<Msg> {
+        let _ = match ev {
+            Event::User(TisqEvent::DbResponse(DbResponse::Explained {
+                plan, analyzed, ..
+            })) => {
+                self.set_plan(&plan, analyzed);
+                return Some(Msg::ShowExplainPlan);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left,
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+            }) => self.perform(Cmd::Custom(TREE_CMD_CLOSE)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right,
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+            }) => self.perform(Cmd::Custom(TREE_CMD_OPEN)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp,
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+            }) => self.perform(Cmd::Scroll(Direction::Up)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down,
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Up,
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+            }) => self.perform(Cmd::Move(Direction::Up)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home,
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent {
+                code: Key::End,
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+            }) => self.perform(Cmd::GoTo(Position::End)),
+            _ => return None,
+        };
+        Some(Msg::None)
+    }
+}
@@ -0,0 +1,93 @@
+use std::io::Stdout;
+
+use tuirealm::{
+    application::ApplicationResult,
+    props::Alignment,
+    tui::{
+        prelude::{Constraint, CrosstermBackend, Direction, Layout, Rect},
+        Frame,
+    },
+    AttrValue, Attribute, State, StateValue,
+};
+use uuid::Uuid;
+
+use crate::{app::model::TisqApplication, Id};
+
+use super::{BrowsedItem, FormSubmitListener, InputText};
+
+/// What is done with the name or path entered in group prompt
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum GroupAction {
+    // group is created inside of the given one or at the top level
+    Create(Option<Uuid>),
+    Rename(Uuid),
+    // entered path of the group could have groups, which are not created yet
+    Move(BrowsedItem),
+}
+
+/// Asks for name of the group or path of the group to move server or group into
+pub(crate) struct GroupPrompt {
+    pub(crate) action: GroupAction,
+}
+
+impl GroupPrompt {
+    pub(crate) fn mount(
+        app: &mut TisqApplication,
+        action: GroupAction,
+        title: &str,
+        value: &str,
+    ) -> ApplicationResult<Self> {
+        app.mount(
+            Id::GroupPromptInput,
+            Box::new(InputText::new(title, value)),
+            vec![],
+        )?;
+        app.mount(
+            Id::GroupPromptSubmitListener,
+            Box::new(FormSubmitListener::new(Id::GroupPrompt)),
+            vec![FormSubmitListener::get_subscription(Id::GroupPromptInput)],
+        )?;
+        app.active(&Id::GroupPromptInput)?;
+        Ok(Self { action })
+    }
+
+    /// Prompt is opened from the browser, so it gets focus back
+    pub(crate) fn unmount(&self, app: &mut TisqApplication) -> ApplicationResult<()> {
+        app.umount(&Id::GroupPromptInput)?;
+        app.umount(&Id::GroupPromptSubmitListener)?;
+        app.active(&Id::Tree)
+    }
+
+    pub(crate) fn value(&self, app: &TisqApplication) -> String {
+        match app.state(&Id::GroupPromptInput) {
+            Ok(State::One(StateValue::String(value))) => value,
+            _ => String::new(),
+        }
+    }
+
+    /// Tells why entered value was not accepted, keeping it to be fixed
+    pub(crate) fn show_error(&self, app: &mut TisqApplication, error: &str) {
+        let _ = app.attr(
+            &Id::GroupPromptInput,
+            Attribute::Title,
+            AttrValue::Title((format!("{}, try again", error), Alignment::Left)),
+        );
+    }
+
+    pub(crate) fn view(
+        &self,
+        area: Rect,
+        app: &mut TisqApplication,
+        f: &mut Frame<CrosstermBackend<Stdout>>,
+    ) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(3),
+                Constraint::Min(0), // fills remaining space
+            ])
+            .margin(1)
+            .split(area);
+        app.view(&Id::GroupPromptInput, f, rows[0]);
+    }
+}
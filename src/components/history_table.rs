@@ -0,0 +1,201 @@
+use tui_realm_stdlib::Table;
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
+use tuirealm::event::{KeyEventKind, KeyModifiers};
+use tuirealm::props::{
+    Alignment, BorderSides, Borders, Color, PropPayload, PropValue, TableBuilder, TextSpan,
+};
+use tuirealm::{
+    event::{Key, KeyEvent},
+    Component, Event, MockComponent,
+};
+use tuirealm::{AttrValue, Attribute, State, StateValue};
+
+use crate::app::storage::HistoryEntry;
+use crate::app::TisqEvent;
+use crate::Msg;
+
+const HEADERS: [&str; 5] = ["executed", "duration", "rows", "status", "query"];
+
+/// Previously executed queries of the database, which could be searched by typing
+#[derive(MockComponent)]
+pub(crate) struct HistoryTable {
+    component: Table,
+    entries: Vec<HistoryEntry>,
+    // indexes of entries matching the search, in the order they are shown
+    shown: Vec<usize>,
+    search: String,
+}
+
+impl HistoryTable {
+    pub(crate) fn new(entries: Vec<HistoryEntry>) -> Self {
+        let mut result = Self {
+            entries,
+            shown: vec![],
+            search: String::new(),
+            component: Table::default()
+                .borders(Borders::default().sides(BorderSides::NONE))
+                .scroll(true)
+                .highlighted_color(Color::LightYellow)
+                .highlighted_str("🚀")
+                .rewind(true)
+                .step(4)
+                .row_height(1)
+                .column_spacing(2)
+                .widths(&[12, 10, 8, 15, 55]),
+        };
+        result.update_result();
+        result
+    }
+
+    fn update_result(&mut self) {
+        let search = self.search.to_lowercase();
+        self.shown = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.query.to_lowercase().contains(&search))
+            .map(|(index, _)| index)
+            .collect();
+
+        let now = HistoryEntry::now_millis();
+        let mut builder = TableBuilder::default();
+        for index in &self.shown {
+            let entry = &self.entries[*index];
+            let rows = if entry.more_rows {
+                format!("{}+", entry.rows)
+            } else {
+                entry.rows.to_string()
+            };
+            let status = match &entry.error {
+                Some(error) => format!("error: {}", error),
+                None => "ok".to_string(),
+            };
+            builder.add_col(TextSpan::from(Self::age(
+                now.saturating_sub(entry.executed_at),
+            )));
+            builder.add_col(TextSpan::from(format!("{}ms", entry.duration_ms)));
+            builder.add_col(TextSpan::from(rows));
+            builder.add_col(TextSpan::from(status));
+            // whole query is shown in one line
+            builder.add_col(TextSpan::from(
+                entry.query.split_whitespace().collect::<Vec<_>>().join(" "),
+            ));
+            builder.add_row();
+        }
+
+        self.component.attr(
+            tuirealm::Attribute::Content,
+            AttrValue::Table(builder.build()),
+        );
+        self.attr(
+            Attribute::Text,
+            AttrValue::Payload(PropPayload::Vec(
+                HEADERS
+                    .iter()
+                    .map(|x| PropValue::Str(x.to_string()))
+                    .collect(),
+            )),
+        );
+
+        let title = if self.search.is_empty() {
+            "Query History (type to search)".to_string()
+        } else {
+            format!("Query History (search: {})", self.search)
+        };
+        self.attr(
+            Attribute::Title,
+            AttrValue::Title((title, Alignment::Center)),
+        );
+    }
+
+    fn age(millis: u64) -> String {
+        let seconds = millis / 1000;
+        match seconds {
+            0..=59 => format!("{}s ago", seconds),
+            60..=3599 => format!("{}m ago", seconds / 60),
+            3600..=86399 => format!("{}h ago", seconds / 3600),
+            _ => format!("{}d ago", seconds / 86400),
+        }
+    }
+
+    fn selected_query(&self) -> Option<String> {
+        match self.component.state() {
+            State::One(StateValue::Usize(list_index)) => self
+                .shown
+                .get(list_index)
+                .map(|index| self.entries[*index].query.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl Component<Msg, TisqEvent> for HistoryTable {
+    fn on(&mut self, ev: Event<TisqEvent>) -> Option<Msg> {
+        let _ = match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                return self.selected_query().map(Msg::PasteFromHistory);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('e'),
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::CONTROL,
+            }) => {
+                return self.selected_query().map(Msg::ExecuteFromHistory);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                kind: KeyEventKind::Press,
+                modifiers,
+            }) if modifiers == KeyModifiers::NONE || modifiers == KeyModifiers::SHIFT => {
+                self.search.push(ch);
+                self.update_result();
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.search.pop();
+                self.update_result();
+                self.perform(Cmd::GoTo(Position::Begin))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Up,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Move(Direction::Up)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Scroll(Direction::Up)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent {
+                code: Key::End,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::GoTo(Position::End)),
+            _ => CmdResult::None,
+        };
+        Some(Msg::None)
+    }
+}
@@ -2,13 +2,20 @@ use super::Msg;
 
 // -- modules
 mod add_server_form;
+mod bind_parameters_form;
 mod browser;
 mod command_line;
 mod editor;
 mod error_result;
 mod execute_result_table;
+mod explain_plan;
 mod global_keys;
+mod group_prompt;
+mod history_table;
 mod label;
+mod notifications_table;
+mod password_prompt;
+mod server_messages;
 mod settings;
 mod snippets_table;
 mod status;
@@ -17,19 +24,35 @@ mod tabs;
 // -- export
 pub(crate) use add_server_form::input_text::InputText;
 pub(crate) use add_server_form::AddServerForm;
+pub(crate) use add_server_form::AddServerFormStatus;
+pub(crate) use add_server_form::ChoiceInput;
 pub(crate) use add_server_form::FormSubmitListener;
+pub(crate) use add_server_form::TestConnectionButton;
+pub(crate) use add_server_form::PASSWORD_STORAGES;
+pub(crate) use add_server_form::SSL_MODES;
+pub(crate) use bind_parameters_form::BindParametersForm;
+pub(crate) use browser::BrowsedItem;
 pub(crate) use browser::BrowserTree;
 pub(crate) use browser::SentTree;
+pub(crate) use command_line::CommandLine;
 pub use editor::Editor;
 pub(crate) use error_result::ErrorResult;
-pub(crate) use command_line::CommandLine;
 pub(crate) use execute_result_table::ExecuteResultTable;
+pub(crate) use explain_plan::ExplainPlanTree;
+pub(crate) use group_prompt::GroupAction;
+pub(crate) use group_prompt::GroupPrompt;
+pub(crate) use history_table::HistoryTable;
+pub(crate) use notifications_table::NotificationsTable;
+pub(crate) use password_prompt::PasswordPrompt;
+pub(crate) use server_messages::ServerMessagesTable;
 pub(crate) use settings::SettingsForm;
 pub(crate) use snippets_table::SnippetsTable;
 pub(crate) use status::DbResponseStatusListener;
 pub(crate) use status::PressedKey;
+pub(crate) use status::StatusError;
 pub(crate) use status::StatusSpan;
 pub(crate) use status::StatusSpinner;
+pub(crate) use status::TransactionSpan;
 
 pub use global_keys::GlobalListener;
 pub use label::Label;
@@ -0,0 +1,233 @@
+use tui_realm_stdlib::Table;
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
+use tuirealm::event::{KeyEventKind, KeyModifiers};
+use tuirealm::props::{
+    Alignment, BorderSides, Borders, Color, PropPayload, PropValue, TableBuilder, TextSpan,
+};
+use tuirealm::{
+    event::{Key, KeyEvent},
+    Component, Event, MockComponent,
+};
+use tuirealm::{AttrValue, Attribute};
+use uuid::Uuid;
+
+use crate::app::{DbResponse, EditorId, Notification, TisqEvent};
+use crate::Msg;
+
+const HEADERS: [&str; 4] = ["time (UTC)", "channel", "pid", "payload"];
+
+// older notifications are dropped, as listening could go on for long
+const MAX_NOTIFICATIONS: usize = 1000;
+
+/// Notifications from listened channels of one database,
+/// typed `channel payload` line is sent as NOTIFY with Enter
+#[derive(MockComponent)]
+pub(crate) struct NotificationsTable {
+    component: Table,
+    editor_id: EditorId,
+    database: String,
+    channels: Vec<String>,
+    notifications: Vec<Notification>,
+    draft: String,
+    // why listening has stopped, if it did
+    stopped: Option<String>,
+}
+
+impl NotificationsTable {
+    pub(crate) fn new(editor_id: EditorId, database: String) -> Self {
+        let mut result = Self {
+            editor_id,
+            database,
+            channels: vec![],
+            notifications: vec![],
+            draft: String::new(),
+            stopped: None,
+            component: Table::default()
+                .borders(Borders::default().sides(BorderSides::NONE))
+                .scroll(true)
+                .highlighted_color(Color::LightYellow)
+                .highlighted_str("🚀")
+                .rewind(true)
+                .step(4)
+                .row_height(1)
+                .column_spacing(2)
+                .widths(&[14, 20, 8, 58]),
+        };
+        result.update_result();
+        result
+    }
+
+    fn is_own(&self, server_id: Uuid, database: &str) -> bool {
+        self.editor_id == EditorId::new(server_id, database.to_string())
+    }
+
+    fn update_result(&mut self) {
+        let mut builder = TableBuilder::default();
+        for notification in &self.notifications {
+            let time = notification.received_at;
+            builder.add_col(TextSpan::from(format!(
+                "{:02}:{:02}:{:02}.{:03}",
+                time.hour(),
+                time.minute(),
+                time.second(),
+                time.millisecond()
+            )));
+            builder.add_col(TextSpan::from(notification.channel.clone()));
+            builder.add_col(TextSpan::from(notification.process_id.to_string()));
+            builder.add_col(TextSpan::from(notification.payload.clone()));
+            builder.add_row();
+        }
+
+        self.component.attr(
+            tuirealm::Attribute::Content,
+            AttrValue::Table(builder.build()),
+        );
+        self.attr(
+            Attribute::Text,
+            AttrValue::Payload(PropPayload::Vec(
+                HEADERS
+                    .iter()
+                    .map(|x| PropValue::Str(x.to_string()))
+                    .collect(),
+            )),
+        );
+        self.update_title();
+    }
+
+    fn update_title(&mut self) {
+        let title = if !self.draft.is_empty() {
+            format!("NOTIFY {} (Enter to send)", self.draft)
+        } else if let Some(message) = &self.stopped {
+            format!("Notifications of {} (stopped: {})", self.database, message)
+        } else if self.channels.is_empty() {
+            format!("Notifications of {} (connecting...)", self.database)
+        } else {
+            format!(
+                "Notifications of {} on {} (type `channel payload` to notify)",
+                self.database,
+                self.channels.join(", ")
+            )
+        };
+        self.attr(
+            Attribute::Title,
+            AttrValue::Title((title, Alignment::Center)),
+        );
+    }
+
+    fn add_notification(&mut self, notification: Notification) {
+        self.notifications.push(notification);
+        if self.notifications.len() > MAX_NOTIFICATIONS {
+            self.notifications.remove(0);
+        }
+        self.update_result();
+        // the latest notification is followed
+        self.perform(Cmd::GoTo(Position::End));
+    }
+
+    fn send_draft(&mut self) -> Option<Msg> {
+        let draft = std::mem::take(&mut self.draft);
+        self.update_title();
+        let (channel, payload) = draft.split_once(' ').unwrap_or((&draft, ""));
+        if channel.is_empty() {
+            return Some(Msg::None);
+        }
+        Some(Msg::SendNotification {
+            editor_id: self.editor_id.clone(),
+            channel: channel.to_string(),
+            payload: payload.to_string(),
+        })
+    }
+}
+
+impl Component<Msg, TisqEvent> for NotificationsTable {
+    fn on(&mut self, ev: Event<TisqEvent>) -> Option<Msg> {
+        let _ = match ev {
+            Event::User(TisqEvent::NotificationReceived(notification)) => {
+                if self.is_own(notification.server_id, &notification.database) {
+                    self.add_notification(notification);
+                }
+                return Some(Msg::None);
+            }
+            Event::User(TisqEvent::DbResponse(DbResponse::Listening {
+                server_id,
+                database,
+                channels,
+            })) => {
+                if self.is_own(server_id, &database) {
+                    self.channels = channels;
+                    self.stopped = None;
+                    self.update_title();
+                }
+                return Some(Msg::None);
+            }
+            Event::User(TisqEvent::DbResponse(DbResponse::ListeningStopped {
+                server_id,
+                database,
+                message,
+            })) => {
+                if self.is_own(server_id, &database) {
+                    self.stopped = Some(message);
+                    self.update_title();
+                }
+                return Some(Msg::None);
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                return self.send_draft();
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                kind: KeyEventKind::Press,
+                modifiers,
+            }) if modifiers == KeyModifiers::NONE || modifiers == KeyModifiers::SHIFT => {
+                self.draft.push(ch);
+                self.update_title();
+                CmdResult::None
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => {
+                self.draft.pop();
+                self.update_title();
+                CmdResult::None
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Up,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Move(Direction::Up)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Scroll(Direction::Up)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent {
+                code: Key::End,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::GoTo(Position::End)),
+            _ => CmdResult::None,
+        };
+        Some(Msg::None)
+    }
+}
@@ -0,0 +1,103 @@
+use std::io::Stdout;
+
+use tuirealm::{
+    application::ApplicationResult,
+    props::Alignment,
+    tui::{
+        prelude::{Constraint, CrosstermBackend, Direction, Layout, Rect},
+        Frame,
+    },
+    AttrValue, Attribute, State, StateValue,
+};
+
+use crate::{
+    app::{model::TisqApplication, SecretPrompt},
+    Id, Msg,
+};
+
+use super::{FormSubmitListener, InputText};
+
+/// Asks for password of the server or for master passphrase before connecting,
+/// message which needed it is sent again once it is entered
+pub(crate) struct PasswordPrompt {
+    pub(crate) prompt: SecretPrompt,
+    pub(crate) retry: Msg,
+    // component which was active before the prompt
+    pub(crate) previous_focus: Option<Id>,
+}
+
+impl PasswordPrompt {
+    pub(crate) fn mount(
+        app: &mut TisqApplication,
+        prompt: SecretPrompt,
+        title: &str,
+        retry: Msg,
+    ) -> ApplicationResult<Self> {
+        let previous_focus = app.focus().cloned();
+        app.mount(
+            Id::PasswordPromptInput,
+            Box::new(InputText::new(title, "").masked()),
+            vec![],
+        )?;
+        app.mount(
+            Id::PasswordPromptSubmitListener,
+            Box::new(FormSubmitListener::new(Id::PasswordPrompt)),
+            vec![FormSubmitListener::get_subscription(
+                Id::PasswordPromptInput,
+            )],
+        )?;
+        app.active(&Id::PasswordPromptInput)?;
+        Ok(Self {
+            prompt,
+            retry,
+            previous_focus,
+        })
+    }
+
+    pub(crate) fn unmount(&self, app: &mut TisqApplication) -> ApplicationResult<()> {
+        app.umount(&Id::PasswordPromptInput)?;
+        app.umount(&Id::PasswordPromptSubmitListener)?;
+        match &self.previous_focus {
+            Some(id) if app.mounted(id) => app.active(id),
+            _ => app.active(&Id::Tree),
+        }
+    }
+
+    pub(crate) fn value(&self, app: &TisqApplication) -> String {
+        match app.state(&Id::PasswordPromptInput) {
+            Ok(State::One(StateValue::String(value))) => value,
+            _ => String::new(),
+        }
+    }
+
+    /// Clears entered value and tells why it was not accepted
+    pub(crate) fn show_error(&self, app: &mut TisqApplication, error: &str) {
+        let _ = app.attr(
+            &Id::PasswordPromptInput,
+            Attribute::Value,
+            AttrValue::String(String::new()),
+        );
+        let _ = app.attr(
+            &Id::PasswordPromptInput,
+            Attribute::Title,
+            AttrValue::Title((format!("{}, try again", error), Alignment::Left)),
+        );
+    }
+
+    pub(crate) fn view(
+        &self,
+        area: Rect,
+        app: &mut TisqApplication,
+        f: &mut Frame<CrosstermBackend<Stdout>>,
+    ) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![
+                Constraint::Length(3),
+                Constraint::Min(0), // fills remaining space
+            ])
+            .margin(1)
+            .split(area);
+        app.view(&Id::PasswordPromptInput, f, rows[0]);
+    }
+}
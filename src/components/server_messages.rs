@@ -0,0 +1,134 @@
+use tui_realm_stdlib::Table;
+use tuirealm::command::{Cmd, CmdResult, Direction, Position};
+use tuirealm::event::{KeyEventKind, KeyModifiers};
+use tuirealm::props::{
+    Alignment, BorderSides, Borders, Color, PropPayload, PropValue, TableBuilder, TextSpan,
+};
+use tuirealm::{
+    event::{Key, KeyEvent},
+    Component, Event, MockComponent,
+};
+use tuirealm::{AttrValue, Attribute};
+
+use crate::app::{DbResponse, NoticeSeverity, ServerMessage, TisqEvent};
+use crate::Msg;
+
+const HEADERS: [&str; 3] = ["time (UTC)", "severity", "message"];
+
+/// Notices sent by server during the last execution, f.e by RAISE NOTICE
+#[derive(MockComponent)]
+pub(crate) struct ServerMessagesTable {
+    component: Table,
+}
+
+impl Default for ServerMessagesTable {
+    fn default() -> Self {
+        let mut result = Self {
+            component: Table::default()
+                .borders(Borders::default().sides(BorderSides::LEFT))
+                .title("Messages", Alignment::Center)
+                .scroll(true)
+                .highlighted_color(Color::LightYellow)
+                .highlighted_str("🚀")
+                .rewind(true)
+                .step(4)
+                .row_height(1)
+                .column_spacing(2)
+                .widths(&[14, 9, 77]),
+        };
+        result.set_messages(&[]);
+        result
+    }
+}
+
+impl ServerMessagesTable {
+    fn set_messages(&mut self, messages: &[ServerMessage]) {
+        let mut builder = TableBuilder::default();
+        for message in messages {
+            let time = message.received_at;
+            builder.add_col(TextSpan::from(format!(
+                "{:02}:{:02}:{:02}.{:03}",
+                time.hour(),
+                time.minute(),
+                time.second(),
+                time.millisecond()
+            )));
+            let severity = TextSpan::from(message.severity.to_string());
+            builder.add_col(match message.severity {
+                NoticeSeverity::Error => severity.fg(Color::Red),
+                NoticeSeverity::Warning => severity.fg(Color::Yellow),
+                _ => severity,
+            });
+            // multiline messages, like from VACUUM VERBOSE, are shown in one line
+            builder.add_col(TextSpan::from(
+                message
+                    .message
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ));
+            builder.add_row();
+        }
+
+        self.component.attr(
+            tuirealm::Attribute::Content,
+            AttrValue::Table(builder.build()),
+        );
+        self.attr(
+            Attribute::Text,
+            AttrValue::Payload(PropPayload::Vec(
+                HEADERS
+                    .iter()
+                    .map(|x| PropValue::Str(x.to_string()))
+                    .collect(),
+            )),
+        );
+        if !messages.is_empty() {
+            // the latest message is the most interesting one
+            self.perform(Cmd::GoTo(Position::End));
+        }
+    }
+}
+
+impl Component<Msg, TisqEvent> for ServerMessagesTable {
+    fn on(&mut self, ev: Event<TisqEvent>) -> Option<Msg> {
+        let _ = match ev {
+            Event::User(TisqEvent::DbResponse(DbResponse::ServerMessages { messages, .. })) => {
+                self.set_messages(&messages);
+                return Some(Msg::ShowServerMessages(!messages.is_empty()));
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Move(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Up,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Move(Direction::Up)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Scroll(Direction::Down)),
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::Scroll(Direction::Up)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Home,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::GoTo(Position::Begin)),
+            Event::Keyboard(KeyEvent {
+                code: Key::End,
+                kind: KeyEventKind::Press,
+                modifiers: KeyModifiers::NONE,
+            }) => self.perform(Cmd::GoTo(Position::End)),
+            _ => CmdResult::None,
+        };
+        Some(Msg::None)
+    }
+}
@@ -4,7 +4,7 @@ use tuirealm::event::KeyEventKind;
 use tuirealm::props::{Alignment, BorderType, Borders, Color};
 use tuirealm::{
     event::{Key, KeyEvent},
-    Component, Event, MockComponent
+    Component, Event, MockComponent,
 };
 use tuirealm::{State, StateValue};
 
@@ -8,7 +8,7 @@ use tuirealm::{
     },
 };
 
-use crate::{app::model::TisqApplication, Id, components::settings::input_radio::RadioInput};
+use crate::{app::model::TisqApplication, components::settings::input_radio::RadioInput, Id};
 
 // pub mod input_text;
 mod input_radio;
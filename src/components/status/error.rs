@@ -0,0 +1,30 @@
+use tui_realm_stdlib::Span;
+use tuirealm::{
+    props::{Color, TextSpan},
+    Component, Event, MockComponent,
+};
+
+use crate::{app::TisqEvent, tui::Msg};
+
+/// Shows error of something app failed to do on its own, like reading stored server,
+/// text is set by app status and stays until the next request
+#[derive(MockComponent)]
+pub(crate) struct StatusError {
+    component: Span,
+}
+
+impl Default for StatusError {
+    fn default() -> Self {
+        Self {
+            component: Span::default()
+                .foreground(Color::Red)
+                .spans(&[TextSpan::new("")]),
+        }
+    }
+}
+
+impl Component<Msg, TisqEvent> for StatusError {
+    fn on(&mut self, _ev: Event<TisqEvent>) -> Option<Msg> {
+        None
+    }
+}
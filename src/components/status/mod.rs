@@ -1,9 +1,13 @@
+mod error;
 mod pressed_key;
 mod span;
 mod spinner;
 mod status_phantom;
+mod transaction;
 
+pub(crate) use error::StatusError;
 pub(crate) use pressed_key::PressedKey;
 pub(crate) use span::StatusSpan;
 pub(crate) use spinner::StatusSpinner;
 pub(crate) use status_phantom::DbResponseStatusListener;
+pub(crate) use transaction::TransactionSpan;
@@ -1,10 +1,15 @@
 use tui_realm_stdlib::{Span, Spinner};
 use tuirealm::{
-    props::{Color, TextSpan},
-    Component, Event, MockComponent,
+    props::{Color, PropPayload, PropValue, TextSpan},
+    AttrValue, Attribute, Component, Event, MockComponent, Sub, SubClause, SubEventClause,
 };
 
-use crate::{app::TisqEvent, tui::Msg};
+use crate::{
+    app::{model::TisqApplication, DbResponse, TisqEvent},
+    tui::{Id, Msg},
+};
+
+const PROCESSING: &str = "Process db req... ";
 
 #[derive(MockComponent)]
 pub(crate) struct StatusSpan {
@@ -16,13 +21,76 @@ impl Default for StatusSpan {
         Self {
             component: Span::default()
                 .foreground(Color::Green)
-                .spans(&[TextSpan::new("Process db req... ").underlined()]),
+                .spans(&[TextSpan::new(PROCESSING).underlined()]),
         }
     }
 }
 
+impl StatusSpan {
+    pub(crate) fn subscriptions() -> Vec<Sub<Id, TisqEvent>> {
+        vec![Sub::new(
+            SubEventClause::User(TisqEvent::DbResponse(DbResponse::None)),
+            // due to comparison of TisqEvent, it does not matter which DbResponse is used
+            SubClause::Always,
+        )]
+    }
+
+    /// Shows what app is busy with, until some database request is done
+    pub(crate) fn show(app: &mut TisqApplication, text: &str) {
+        let _ = app.attr(&Id::StatusSpan, Attribute::Text, Self::text_value(text));
+    }
+
+    pub(crate) fn reset(app: &mut TisqApplication) {
+        Self::show(app, PROCESSING);
+    }
+
+    fn text_value(text: &str) -> AttrValue {
+        AttrValue::Payload(PropPayload::Vec(vec![PropValue::TextSpan(
+            TextSpan::new(text).underlined(),
+        )]))
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.attr(Attribute::Text, Self::text_value(text));
+    }
+}
+
 impl Component<Msg, TisqEvent> for StatusSpan {
     fn on(&mut self, ev: Event<TisqEvent>) -> Option<Msg> {
+        match ev {
+            Event::User(TisqEvent::DbResponse(DbResponse::CopyProgress {
+                bytes, total, ..
+            })) => {
+                let text = match total {
+                    Some(total) if total > 0 => format!(
+                        "Copying {} of {} ({}%)... ",
+                        format_size(bytes),
+                        format_size(total),
+                        bytes * 100 / total
+                    ),
+                    _ => format!("Copying {}... ", format_size(bytes)),
+                };
+                self.set_text(&text);
+            }
+            Event::User(TisqEvent::DbResponse(response)) if response.is_final() => {
+                self.set_text(PROCESSING);
+            }
+            _ => {}
+        }
         Some(Msg::None)
     }
 }
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
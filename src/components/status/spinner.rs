@@ -6,7 +6,6 @@ use tuirealm::{
 
 use crate::{app::TisqEvent, tui::Msg};
 
-
 #[derive(MockComponent)]
 pub(crate) struct StatusSpinner {
     component: Spinner,
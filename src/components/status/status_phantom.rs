@@ -2,7 +2,7 @@ use tui_realm_stdlib::Phantom;
 use tuirealm::{Component, Event, MockComponent, Sub, SubClause, SubEventClause};
 
 use crate::{
-    app::{DbResponse, KeySubClause, SectionKeybindings, TisqEvent, TisqKeyboundAction},
+    app::{DbResponse, EditorId, KeySubClause, SectionKeybindings, TisqEvent, TisqKeyboundAction},
     Id, Msg,
 };
 
@@ -19,18 +19,65 @@ impl DbResponseStatusListener {
     }
 
     pub(crate) fn subscriptions() -> Vec<Sub<Id, TisqEvent>> {
-        vec![Sub::new(
-            SubEventClause::User(TisqEvent::DbResponse(DbResponse::None)),
-            // due to comparison of TisqEvent, it does not matter which DbResponse is used
-            SubClause::Always,
-        )]
+        vec![
+            Sub::new(
+                SubEventClause::User(TisqEvent::DbResponse(DbResponse::None)),
+                // due to comparison of TisqEvent, it does not matter which DbResponse is used
+                SubClause::Always,
+            ),
+            Sub::new(
+                SubEventClause::User(TisqEvent::MasterKeyDerived),
+                SubClause::Always,
+            ),
+        ]
     }
 }
 
 impl Component<Msg, TisqEvent> for DbResponseStatusListener {
     fn on(&mut self, ev: Event<TisqEvent>) -> Option<Msg> {
         match ev {
-            Event::User(TisqEvent::DbResponse(_)) => Some(Msg::PopDbRequestStatus),
+            Event::User(TisqEvent::DbResponse(DbResponse::TransactionStatus {
+                server_id,
+                database,
+                status,
+            })) => Some(Msg::TransactionStatusChanged(
+                EditorId::new(server_id, database),
+                status,
+            )),
+            Event::User(TisqEvent::DbResponse(DbResponse::QueryExecuted {
+                server_id,
+                database,
+                summary,
+            })) => Some(Msg::AddHistoryEntry(
+                EditorId::new(server_id, database),
+                summary,
+            )),
+            Event::User(TisqEvent::DbResponse(DbResponse::PasswordRejected(server_id))) => {
+                Some(Msg::ForgetPassword(server_id))
+            }
+            // status is popped once the result is shown in the form
+            Event::User(TisqEvent::DbResponse(DbResponse::ConnectionTested { error })) => {
+                Some(Msg::ServerConnectionTested(error))
+            }
+            // status is popped once the form is shown
+            Event::User(TisqEvent::DbResponse(DbResponse::ParametersDescribed {
+                server_id,
+                database,
+                query,
+                parameters,
+            })) => Some(Msg::ShowBindParametersForm(
+                EditorId::new(server_id, database),
+                query,
+                parameters,
+            )),
+            // status is popped once the reason is shown
+            Event::User(TisqEvent::DbResponse(DbResponse::NotCancelled(_, reason))) => {
+                Some(Msg::QueryNotCancelled(reason))
+            }
+            Event::User(TisqEvent::MasterKeyDerived) => Some(Msg::MasterKeyDerived),
+            Event::User(TisqEvent::DbResponse(response)) if response.is_final() => {
+                Some(Msg::PopDbRequestStatus)
+            }
             _ => None,
         }
     }
@@ -0,0 +1,30 @@
+use tui_realm_stdlib::Span;
+use tuirealm::{
+    props::{Color, TextSpan},
+    Component, Event, MockComponent,
+};
+
+use crate::{app::TisqEvent, tui::Msg};
+
+/// Shows state of transaction in the shown query editor,
+/// text is set by app status whenever it changes
+#[derive(MockComponent)]
+pub(crate) struct TransactionSpan {
+    component: Span,
+}
+
+impl Default for TransactionSpan {
+    fn default() -> Self {
+        Self {
+            component: Span::default()
+                .foreground(Color::Yellow)
+                .spans(&[TextSpan::new("")]),
+        }
+    }
+}
+
+impl Component<Msg, TisqEvent> for TransactionSpan {
+    fn on(&mut self, _ev: Event<TisqEvent>) -> Option<Msg> {
+        None
+    }
+}
@@ -1,11 +1,44 @@
 use serde::{Deserialize, Serialize};
 
-use crate::app::{TisqKeyboundAction, KeybindingsConfig, SnippetsConfig};
+use crate::app::{FetchLimits, KeybindingsConfig, SnippetsConfig, TisqKeyboundAction};
 
 #[derive(Serialize, Deserialize)]
 pub(crate) struct TisqConfig {
     pub(crate) keybindings: Option<KeybindingsConfig<TisqKeyboundAction>>,
     pub(crate) snippets: Option<SnippetsConfig>,
+    pub(crate) results: Option<ResultsConfig>,
+    pub(crate) scripts: Option<ScriptsConfig>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct ScriptsConfig {
+    /// Whether to skip the rest of the script after statement failed, true by default
+    pub(crate) stop_on_error: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct ResultsConfig {
+    /// How many rows are fetched at once
+    pub(crate) page_size: Option<usize>,
+    /// How many rows are fetched at most, 0 means no limit
+    pub(crate) max_rows: Option<usize>,
+}
+
+impl ResultsConfig {
+    pub(crate) fn fetch_limits(&self) -> FetchLimits {
+        let default = FetchLimits::default();
+        FetchLimits {
+            page_size: self
+                .page_size
+                .filter(|size| *size > 0)
+                .unwrap_or(default.page_size),
+            max_rows: match self.max_rows {
+                Some(0) => None,
+                Some(max_rows) => Some(max_rows),
+                None => default.max_rows,
+            },
+        }
+    }
 }
 
 impl TisqConfig {
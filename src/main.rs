@@ -24,12 +24,16 @@ fn main() -> eyre::Result<()> {
         return Ok(());
     }
 
-    if let Some(cli::Subcommands::Servers(cli::Servers {
-        subcommands: cli::ServerSubcommands::AddServer(add_server_args),
-    })) = args.subcommands
-    {
+    if let Some(cli::Subcommands::Servers(cli::Servers { subcommands })) = args.subcommands {
         let files = files::open_tisq_root()?;
-        return app::cmd::add_server::run(add_server_args, &files);
+        return match subcommands {
+            cli::ServerSubcommands::AddServer(add_server_args) => {
+                app::cmd::add_server::run(add_server_args, &files)
+            }
+            cli::ServerSubcommands::ImportServers(import_servers_args) => {
+                app::cmd::import_servers::run(import_servers_args, &files)
+            }
+        };
     }
 
     tui::run(args.debug)
@@ -2,11 +2,11 @@ extern crate tuirealm;
 
 use crate::app;
 use crate::statics::*;
-use app::{DbRequest, EditorId};
+use app::{DbRequest, EditorId, ExecutionSummary, TransactionStatus};
 
 use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::{fmt, EnvFilter, Layer};
 use tuirealm::application::PollStrategy;
 
 use tuirealm::{AttrValue, Attribute, Update};
@@ -14,6 +14,7 @@ use tuirealm::{AttrValue, Attribute, Update};
 use app::model::Model;
 use uuid::Uuid;
 
+use crate::components::BrowsedItem;
 use crate::config::TisqConfig;
 
 // Let's define the messages handled by our app. NOTE: it must derive `PartialEq`
@@ -22,6 +23,18 @@ pub(crate) enum Msg {
     AppClose,
 
     ExecuteQuery(EditorId, String, i32),
+    // query with placeholders and types server expects for them
+    ShowBindParametersForm(EditorId, String, Vec<(String, Option<String>)>),
+    SubmitBindParameters,
+    ExplainQuery(EditorId, String, bool),
+    CancelQuery(EditorId),
+    // reason why there was nothing to cancel
+    QueryNotCancelled(String),
+    FetchMoreRows(EditorId),
+    ToggleTransactionMode(EditorId),
+    CommitTransaction(EditorId),
+    RollbackTransaction(EditorId),
+    TransactionStatusChanged(EditorId, TransactionStatus),
     // ReconnectAndExecuteQuery(EditorId, String),
     ReconnectAndRepeat(DbRequest),
     ChangeFocus(Id),
@@ -42,24 +55,34 @@ pub(crate) enum Msg {
     CycleNavigation,
 
     StartAddingServer,
+    // opens the same form filled with properties of the server
+    StartEditingServer(Uuid),
     FocusPreviousInput,
     FocusNextInput,
     SubmitAddServerForm,
+    // error of connecting with properties entered in the form, if any
+    ServerConnectionTested(Option<String>),
+    SubmitPassword,
+    // master key derived from entered passphrase could be taken
+    MasterKeyDerived,
+    // passwords stored as is by previous versions are encrypted with master key
+    EncryptPlainPasswords,
+    // password was rejected by the server, so it is asked again next time
+    ForgetPassword(Uuid),
 
     DeleteBrowsedNode(String),
-
-    OpenDatabase(Uuid, String, i32),
-    OpenSchema {
-        server_id: Uuid,
-        database: String,
-        schema: String,
-        retries: i32,
-    },
-    OpenTable {
+    // new group is put into the selected group or next to the selected server
+    StartCreatingGroup(Option<BrowsedItem>),
+    StartRenamingGroup(Uuid),
+    StartMovingToGroup(BrowsedItem),
+    SubmitGroupPrompt,
+    // adds servers of pg_service.conf and PG* environment variables
+    ImportServers,
+
+    // path of the object in browser tree, starting with database
+    OpenObject {
         server_id: Uuid,
-        database: String,
-        schema: String,
-        table: String,
+        path: Vec<String>,
         retries: i32,
     },
     OpenConnection(Uuid),
@@ -69,9 +92,22 @@ pub(crate) enum Msg {
 
     ShowFetchedTable,
     ShowErrorResult,
+    ShowExplainPlan,
+    // whether server sent any messages during the last execution
+    ShowServerMessages(bool),
 
     ApplySnippet(String),
     ShowSnippets,
+
+    AddHistoryEntry(EditorId, ExecutionSummary),
+    ShowHistory(EditorId),
+    PasteFromHistory(String),
+    ExecuteFromHistory(String),
+    SendNotification {
+        editor_id: EditorId,
+        channel: String,
+        payload: String,
+    },
     EditorTryExpand {
         editor_id: EditorId,
         text: String,
@@ -103,23 +139,54 @@ pub enum Id {
     Tree,
     GlobalListener,
     SnippetsTable,
+    HistoryTable,
+    NotificationsTable,
 
     EditorTabs,
     QueryResultTable,
 
     AddServerForm,
     ServerNameInput,
+    ServerHostInput,
+    ServerPortInput,
+    ServerUserInput,
+    ServerPasswordInput,
+    PasswordStorageInput,
+    DefaultDatabaseInput,
+    SslModeInput,
     ConnectionUrlInput,
+    StatementTimeoutInput,
+    LockTimeoutInput,
+    IdleTimeoutInput,
+    TestConnectionButton,
+    AddServerFormStatus,
     FormSubmitListener,
 
+    BindParametersForm,
+    BindParameterValueInput(usize),
+    BindParameterTypeInput(usize),
+    BindParametersSubmitListener,
+
+    PasswordPrompt,
+    PasswordPromptInput,
+    PasswordPromptSubmitListener,
+
+    GroupPrompt,
+    GroupPromptInput,
+    GroupPromptSubmitListener,
+
     ShowUsedKeyToggle,
 
     ExecuteErrorResult,
+    ExplainPlanTree,
+    ServerMessagesTable,
 
     DbResponseStatusListener,
     StatusSpinner,
     StatusSpan,
     StatusPressedKey,
+    StatusTransaction,
+    StatusError,
 
     CommandLine,
 }
@@ -129,19 +196,23 @@ pub(crate) fn run(debug_logs: bool) -> eyre::Result<()> {
         DEBUG_LOG.store(true, std::sync::atomic::Ordering::Relaxed);
     }
 
-    let tracing_to_file = tracing_subscriber::registry().with(
-        fmt::layer()
-            .with_ansi(false)
-            .with_writer(|| LOG_FILE.as_ref().expect("log file not initialized!")),
-    );
-
-    if DEBUG_LOG.load(std::sync::atomic::Ordering::Relaxed) {
-        tracing_to_file.with(EnvFilter::from_default_env().add_directive("tisq=debug".parse()?))
+    let file_filter = if DEBUG_LOG.load(std::sync::atomic::Ordering::Relaxed) {
+        EnvFilter::from_default_env().add_directive("tisq=debug".parse()?)
     } else {
         // default level would be Error
-        tracing_to_file.with(EnvFilter::from_default_env())
-    }
-    .init();
+        EnvFilter::from_default_env()
+    };
+
+    // filter only applies to the file, as notices of server are shown in the app
+    tracing_subscriber::registry()
+        .with(
+            fmt::layer()
+                .with_ansi(false)
+                .with_writer(|| LOG_FILE.as_ref().expect("log file not initialized!"))
+                .with_filter(file_filter),
+        )
+        .with(app::notice_layer())
+        .init();
 
     let files_root = match FILES_ROOT.as_ref() {
         Ok(root) => root,